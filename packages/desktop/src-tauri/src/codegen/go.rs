@@ -0,0 +1,119 @@
+use super::{comments, quote, Body, Snippet};
+use crate::db::request::FormFieldType;
+
+pub fn net_http(snippet: &Snippet) -> String {
+    let mut imports = vec!["fmt", "io", "net/http"];
+    let mut setup = String::new();
+
+    let body = match &snippet.body {
+        Body::None => "nil",
+        Body::Text(text) => {
+            imports.push("strings");
+            setup.push_str(&format!("\tbody := strings.NewReader({})\n", quote(text)));
+            "body"
+        }
+        Body::File(path) => {
+            imports.push("os");
+            setup.push_str(&format!("\tbody, err := os.Open({})\n", quote(path)));
+            setup.push_str(&check(1));
+            setup.push_str("\tdefer body.Close()\n");
+            "body"
+        }
+        Body::Multipart(fields) => {
+            imports.extend(["bytes", "mime/multipart"]);
+            setup.push_str("\tbody := &bytes.Buffer{}\n\twriter := multipart.NewWriter(body)\n");
+            for field in fields {
+                match field.field_type {
+                    FormFieldType::Text => setup.push_str(&format!(
+                        "\twriter.WriteField({}, {})\n",
+                        quote(&field.key),
+                        quote(&field.value)
+                    )),
+                    FormFieldType::File => {
+                        if !imports.contains(&"os") {
+                            imports.push("os");
+                        }
+                        setup.push_str(&format!(
+                            "\t{{\n\t\tfile, err := os.Open({})\n",
+                            quote(field.value.trim())
+                        ));
+                        setup.push_str(&check(2));
+                        setup.push_str(&format!(
+                            "\t\tdefer file.Close()\n\t\tpart, err := writer.CreateFormFile({}, {})\n",
+                            quote(&field.key),
+                            quote(&super::file_name(field))
+                        ));
+                        setup.push_str(&check(2));
+                        setup.push_str("\t\tif _, err := io.Copy(part, file); err != nil {\n\t\t\tpanic(err)\n\t\t}\n\t}\n");
+                    }
+                }
+            }
+            setup.push_str("\twriter.Close()\n");
+            "body"
+        }
+    };
+    if snippet.timeout_ms.is_some() {
+        imports.push("time");
+    }
+    imports.sort_unstable();
+
+    let mut code = comments(snippet, "//", false, false);
+    code.push_str("package main\n\nimport (\n");
+    for import in imports {
+        code.push_str(&format!("\t\"{}\"\n", import));
+    }
+    code.push_str(")\n\nfunc main() {\n");
+    if !setup.is_empty() {
+        code.push_str(&setup);
+        code.push('\n');
+    }
+
+    code.push_str(&format!(
+        "\treq, err := http.NewRequest({}, {}, {})\n",
+        quote(&snippet.method),
+        quote(&snippet.url),
+        body
+    ));
+    code.push_str(&check(1));
+    for (name, value) in &snippet.headers {
+        code.push_str(&format!(
+            "\treq.Header.Set({}, {})\n",
+            quote(name),
+            quote(value)
+        ));
+    }
+    if let Body::Multipart(_) = snippet.body {
+        code.push_str("\treq.Header.Set(\"Content-Type\", writer.FormDataContentType())\n");
+    }
+
+    code.push_str("\n\tclient := &http.Client{\n");
+    if let Some(timeout_ms) = snippet.timeout_ms {
+        code.push_str(&format!(
+            "\t\tTimeout: {} * time.Millisecond,\n",
+            timeout_ms
+        ));
+    }
+    code.push_str("\t\tCheckRedirect: func(req *http.Request, via []*http.Request) error {\n");
+    match snippet.max_redirects {
+        Some(max_redirects) => code.push_str(&format!(
+            "\t\t\tif len(via) >= {} {{\n\t\t\t\treturn fmt.Errorf(\"stopped after {} redirects\")\n\t\t\t}}\n\t\t\treturn nil\n",
+            max_redirects, max_redirects
+        )),
+        None => code.push_str("\t\t\treturn http.ErrUseLastResponse\n"),
+    }
+    code.push_str("\t\t},\n\t}\n");
+
+    code.push_str("\tres, err := client.Do(req)\n");
+    code.push_str(&check(1));
+    code.push_str("\tdefer res.Body.Close()\n\n");
+    code.push_str("\tdata, err := io.ReadAll(res.Body)\n");
+    code.push_str(&check(1));
+    code.push_str("\tfmt.Println(res.Status)\n\tfmt.Println(string(data))\n}\n");
+    code
+}
+
+// `if err != nil { panic(err) }` at the given indentation
+fn check(depth: usize) -> String {
+    let indent = "\t".repeat(depth);
+    format!("{0}if err != nil {{\n{0}\tpanic(err)\n{0}}}\n", indent)
+}
@@ -0,0 +1,73 @@
+use super::{comments, quote, Body, Snippet};
+use crate::db::request::FormFieldType;
+
+pub fn fetch(snippet: &Snippet) -> String {
+    let mut code = comments(snippet, "//", false, false);
+
+    // Files are read with Node's fs module
+    let reads_files = match &snippet.body {
+        Body::File(_) => true,
+        Body::Multipart(fields) => fields.iter().any(|f| f.field_type == FormFieldType::File),
+        _ => false,
+    };
+    if reads_files {
+        code.push_str("import { openAsBlob } from \"node:fs\";\n\n");
+    }
+
+    if let Body::Multipart(fields) = &snippet.body {
+        code.push_str("const form = new FormData();\n");
+        for field in fields {
+            match field.field_type {
+                FormFieldType::Text => code.push_str(&format!(
+                    "form.append({}, {});\n",
+                    quote(&field.key),
+                    quote(&field.value)
+                )),
+                FormFieldType::File => {
+                    let options = match &field.content_type {
+                        Some(content_type) => format!(", {{ type: {} }}", quote(content_type)),
+                        None => String::new(),
+                    };
+                    code.push_str(&format!(
+                        "form.append({}, await openAsBlob({}{}), {});\n",
+                        quote(&field.key),
+                        quote(field.value.trim()),
+                        options,
+                        quote(&super::file_name(field))
+                    ));
+                }
+            }
+        }
+        code.push('\n');
+    }
+
+    code.push_str(&format!(
+        "const response = await fetch({}, {{\n  method: {},\n",
+        quote(&snippet.url),
+        quote(&snippet.method)
+    ));
+    if !snippet.headers.is_empty() {
+        code.push_str("  headers: {\n");
+        for (name, value) in &snippet.headers {
+            code.push_str(&format!("    {}: {},\n", quote(name), quote(value)));
+        }
+        code.push_str("  },\n");
+    }
+    match &snippet.body {
+        Body::None => {}
+        Body::Text(text) => code.push_str(&format!("  body: {},\n", quote(text))),
+        Body::File(path) => code.push_str(&format!("  body: await openAsBlob({}),\n", quote(path))),
+        Body::Multipart(_) => code.push_str("  body: form,\n"),
+    }
+    // fetch has no redirect limit of its own
+    if snippet.max_redirects.is_none() {
+        code.push_str("  redirect: \"manual\",\n");
+    }
+    if let Some(timeout_ms) = snippet.timeout_ms {
+        code.push_str(&format!("  signal: AbortSignal.timeout({}),\n", timeout_ms));
+    }
+    code.push_str("});\n\n");
+
+    code.push_str("console.log(response.status);\nconsole.log(await response.text());\n");
+    code
+}
@@ -0,0 +1,233 @@
+mod go;
+mod javascript;
+mod python;
+mod rust;
+mod shell;
+
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
+
+use crate::commands::http::{parse_form_fields, HttpRequestInput};
+use crate::db::request::{AwsCredentials, FormField, RequestAuth};
+use crate::net::auth;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CodegenTarget {
+    Curl,
+    Httpie,
+    Wget,
+    /// Rust with reqwest
+    Reqwest,
+    /// Python with requests
+    Python,
+    /// JavaScript `fetch`, as available in Node and browsers
+    #[serde(rename = "javascript")]
+    JavaScript,
+    /// Go with net/http
+    Go,
+}
+
+/// The request as `execute_request` would send it, ready to be written out.
+struct Snippet {
+    method: String,
+    url: String,
+    headers: Vec<(String, String)>,
+    body: Body,
+    /// Digest credentials; only some clients can answer the challenge themselves
+    digest: Option<(String, String)>,
+    aws: Option<AwsCredentials>,
+    /// `None` when redirects are not followed
+    max_redirects: Option<u32>,
+    timeout_ms: Option<u64>,
+    /// Caveats written as comments above the snippet
+    notes: Vec<String>,
+}
+
+enum Body {
+    None,
+    Text(String),
+    Multipart(Vec<FormField>),
+    /// Path of the file sent as the body
+    File(String),
+}
+
+/// Generates a snippet for `target` from a request whose variables are already resolved.
+pub fn generate(target: CodegenTarget, input: HttpRequestInput) -> Result<String, String> {
+    let snippet = prepare(input)?;
+
+    Ok(match target {
+        CodegenTarget::Curl => shell::curl(&snippet),
+        CodegenTarget::Httpie => shell::httpie(&snippet),
+        CodegenTarget::Wget => shell::wget(&snippet),
+        CodegenTarget::Reqwest => rust::reqwest(&snippet),
+        CodegenTarget::Python => python::requests(&snippet),
+        CodegenTarget::JavaScript => javascript::fetch(&snippet),
+        CodegenTarget::Go => go::net_http(&snippet),
+    })
+}
+
+// Mirrors the header, auth and body handling of `execute_request`
+fn prepare(input: HttpRequestInput) -> Result<Snippet, String> {
+    let method = input.method.to_uppercase();
+    if !["GET", "POST", "PUT", "PATCH", "DELETE", "HEAD", "OPTIONS"].contains(&method.as_str()) {
+        return Err(format!("Unsupported method: {}", input.method));
+    }
+
+    let mut url =
+        Url::parse(&input.url).map_err(|e| format!("Invalid URL {}: {}", input.url, e))?;
+
+    // Later headers replace earlier ones of the same name, as in the sent request
+    let mut headers: Vec<(String, String)> = Vec::new();
+    for kv in input.headers.iter().filter(|h| h.enabled) {
+        if HeaderName::from_bytes(kv.key.as_bytes()).is_err()
+            || HeaderValue::from_str(&kv.value).is_err()
+        {
+            continue;
+        }
+        set_header(&mut headers, &kv.key, &kv.value);
+    }
+
+    let mut notes = Vec::new();
+    let mut digest = None;
+    let mut aws = None;
+    let auth = match input.auth {
+        RequestAuth::OAuth2(config) => {
+            notes.push(format!(
+                "OAuth 2.0: replace <access_token> with a token from {}",
+                config.token_url
+            ));
+            RequestAuth::Bearer {
+                token: "<access_token>".to_string(),
+            }
+        }
+        RequestAuth::Digest { username, password } => {
+            digest = Some((username, password));
+            RequestAuth::None
+        }
+        RequestAuth::AwsSigV4(credentials) => {
+            aws = Some(credentials);
+            RequestAuth::None
+        }
+        RequestAuth::Signature(config) => {
+            notes.push(format!(
+                "The {} signature header is computed for every request and is not included",
+                config.header.trim()
+            ));
+            RequestAuth::None
+        }
+        auth => auth,
+    };
+
+    // The URL is written as given unless auth adds to its query, so that unresolved
+    // variables are not percent-encoded
+    let original = url.clone();
+    let mut auth_headers = HeaderMap::new();
+    auth::apply(&auth, &mut auth_headers, &mut url)?;
+    let url = if url == original {
+        input.url
+    } else {
+        url.to_string()
+    };
+    for (name, value) in &auth_headers {
+        set_header(
+            &mut headers,
+            name.as_str(),
+            value.to_str().unwrap_or_default(),
+        );
+    }
+
+    let content = input.body_content.filter(|b| !b.is_empty());
+    let body = match (input.body_type.as_str(), content) {
+        ("json", Some(content)) => {
+            default_header(&mut headers, "Content-Type", "application/json");
+            Body::Text(content)
+        }
+        ("raw", Some(content)) => Body::Text(content),
+        ("x-www-form-urlencoded", Some(content)) => {
+            default_header(
+                &mut headers,
+                "Content-Type",
+                "application/x-www-form-urlencoded",
+            );
+            Body::Text(content)
+        }
+        ("form-data", Some(content)) => Body::Multipart(
+            parse_form_fields(&content)
+                .into_iter()
+                .filter(|f| f.enabled)
+                .collect(),
+        ),
+        ("binary", Some(content)) => {
+            let path = content.trim().to_string();
+            let content_type = mime_guess::from_path(&path).first_or_octet_stream();
+            default_header(&mut headers, "Content-Type", content_type.as_ref());
+            Body::File(path)
+        }
+        _ => Body::None,
+    };
+
+    let redirect = input.settings.redirect;
+    Ok(Snippet {
+        method,
+        url,
+        headers,
+        body,
+        digest,
+        aws,
+        max_redirects: redirect.follow.then_some(redirect.max_redirects),
+        timeout_ms: input.settings.timeouts.total_ms,
+        notes,
+    })
+}
+
+fn set_header(headers: &mut Vec<(String, String)>, name: &str, value: &str) {
+    headers.retain(|(existing, _)| !existing.eq_ignore_ascii_case(name));
+    headers.push((name.to_string(), value.to_string()));
+}
+
+fn default_header(headers: &mut Vec<(String, String)>, name: &str, value: &str) {
+    if !headers
+        .iter()
+        .any(|(existing, _)| existing.eq_ignore_ascii_case(name))
+    {
+        headers.push((name.to_string(), value.to_string()));
+    }
+}
+
+// A double-quoted literal, valid in Python, JavaScript and Go
+fn quote(value: &str) -> String {
+    serde_json::to_string(value).unwrap_or_default()
+}
+
+// Comment lines above the snippet, including the auth the client cannot reproduce;
+// `prefix` is the language's line comment
+fn comments(snippet: &Snippet, prefix: &str, digest: bool, aws: bool) -> String {
+    let mut notes = snippet.notes.clone();
+    if snippet.digest.is_some() && !digest {
+        notes.push("Digest auth is not built in; answer the 401 challenge by hand".to_string());
+    }
+    if snippet.aws.is_some() && !aws {
+        notes.push("AWS Signature Version 4 signing is not included".to_string());
+    }
+
+    notes
+        .iter()
+        .map(|note| format!("{} {}\n", prefix, note))
+        .collect()
+}
+
+fn seconds(ms: u64) -> String {
+    (ms as f64 / 1000.0).to_string()
+}
+
+// File name sent for a multipart file field, as `file_part` picks it
+fn file_name(field: &FormField) -> String {
+    field.file_name.clone().unwrap_or_else(|| {
+        std::path::Path::new(field.value.trim())
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default()
+    })
+}
@@ -0,0 +1,80 @@
+use super::{comments, quote, seconds, Body, Snippet};
+use crate::db::request::FormFieldType;
+
+pub fn requests(snippet: &Snippet) -> String {
+    let mut code = comments(snippet, "#", true, false);
+    code.push_str("import requests\n");
+    if snippet.digest.is_some() {
+        code.push_str("from requests.auth import HTTPDigestAuth\n");
+    }
+    code.push('\n');
+
+    code.push_str(&format!("url = {}\n", quote(&snippet.url)));
+    let mut arguments = vec![quote(&snippet.method), "url".to_string()];
+
+    if !snippet.headers.is_empty() {
+        code.push_str("headers = {\n");
+        for (name, value) in &snippet.headers {
+            code.push_str(&format!("    {}: {},\n", quote(name), quote(value)));
+        }
+        code.push_str("}\n");
+        arguments.push("headers=headers".to_string());
+    }
+
+    match &snippet.body {
+        Body::None => {}
+        Body::Text(text) => {
+            code.push_str(&format!("data = {}\n", quote(text)));
+            arguments.push("data=data".to_string());
+        }
+        Body::File(path) => {
+            code.push_str(&format!("data = open({}, \"rb\")\n", quote(path)));
+            arguments.push("data=data".to_string());
+        }
+        // A list keeps repeated keys; `None` as the file name makes a plain text part
+        Body::Multipart(fields) => {
+            code.push_str("files = [\n");
+            for field in fields {
+                let part = match field.field_type {
+                    FormFieldType::Text => format!("(None, {})", quote(&field.value)),
+                    FormFieldType::File => {
+                        let mut part = format!(
+                            "({}, open({}, \"rb\")",
+                            quote(&super::file_name(field)),
+                            quote(field.value.trim())
+                        );
+                        if let Some(content_type) = &field.content_type {
+                            part.push_str(&format!(", {}", quote(content_type)));
+                        }
+                        part.push(')');
+                        part
+                    }
+                };
+                code.push_str(&format!("    ({}, {}),\n", quote(&field.key), part));
+            }
+            code.push_str("]\n");
+            arguments.push("files=files".to_string());
+        }
+    }
+
+    if let Some((username, password)) = &snippet.digest {
+        arguments.push(format!(
+            "auth=HTTPDigestAuth({}, {})",
+            quote(username),
+            quote(password)
+        ));
+    }
+    if snippet.max_redirects.is_none() {
+        arguments.push("allow_redirects=False".to_string());
+    }
+    if let Some(timeout_ms) = snippet.timeout_ms {
+        arguments.push(format!("timeout={}", seconds(timeout_ms)));
+    }
+
+    code.push_str(&format!(
+        "\nresponse = requests.request(\n    {},\n)\n\n",
+        arguments.join(",\n    ")
+    ));
+    code.push_str("print(response.status_code)\nprint(response.text)\n");
+    code
+}
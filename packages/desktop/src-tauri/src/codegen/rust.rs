@@ -0,0 +1,91 @@
+use super::{comments, Body, Snippet};
+use crate::db::request::FormFieldType;
+
+pub fn reqwest(snippet: &Snippet) -> String {
+    let mut code = comments(snippet, "//", false, false);
+    if snippet.timeout_ms.is_some() {
+        code.push_str("use std::time::Duration;\n\n");
+    }
+    code.push_str("#[tokio::main]\nasync fn main() -> Result<(), Box<dyn std::error::Error>> {\n");
+
+    code.push_str("    let client = reqwest::Client::builder()\n");
+    match snippet.max_redirects {
+        Some(max_redirects) => code.push_str(&format!(
+            "        .redirect(reqwest::redirect::Policy::limited({}))\n",
+            max_redirects
+        )),
+        None => code.push_str("        .redirect(reqwest::redirect::Policy::none())\n"),
+    }
+    if let Some(timeout_ms) = snippet.timeout_ms {
+        code.push_str(&format!(
+            "        .timeout(Duration::from_millis({}))\n",
+            timeout_ms
+        ));
+    }
+    code.push_str("        .build()?;\n\n");
+
+    if let Body::Multipart(fields) = &snippet.body {
+        code.push_str("    let form = reqwest::multipart::Form::new()");
+        for field in fields {
+            match field.field_type {
+                FormFieldType::Text => code.push_str(&format!(
+                    "\n        .text({}, {})",
+                    literal(&field.key),
+                    literal(&field.value)
+                )),
+                FormFieldType::File => {
+                    let mut part = format!(
+                        "reqwest::multipart::Part::bytes(std::fs::read({})?)\n                .file_name({})",
+                        literal(field.value.trim()),
+                        literal(&super::file_name(field))
+                    );
+                    if let Some(content_type) = &field.content_type {
+                        part.push_str(&format!(
+                            "\n                .mime_str({})?",
+                            literal(content_type)
+                        ));
+                    }
+                    code.push_str(&format!(
+                        "\n        .part(\n            {},\n            {},\n        )",
+                        literal(&field.key),
+                        part
+                    ));
+                }
+            }
+        }
+        code.push_str(";\n\n");
+    }
+
+    code.push_str(&format!(
+        "    let response = client\n        .request(reqwest::Method::{}, {})\n",
+        snippet.method,
+        literal(&snippet.url)
+    ));
+    for (name, value) in &snippet.headers {
+        code.push_str(&format!(
+            "        .header({}, {})\n",
+            literal(name),
+            literal(value)
+        ));
+    }
+    match &snippet.body {
+        Body::None => {}
+        Body::Text(text) => code.push_str(&format!("        .body({})\n", literal(text))),
+        Body::File(path) => code.push_str(&format!(
+            "        .body(std::fs::read({})?)\n",
+            literal(path)
+        )),
+        Body::Multipart(_) => code.push_str("        .multipart(form)\n"),
+    }
+    code.push_str("        .send()\n        .await?;\n\n");
+
+    code.push_str("    println!(\"{}\", response.status());\n");
+    code.push_str("    println!(\"{}\", response.text().await?);\n");
+    code.push_str("    Ok(())\n}\n");
+    code
+}
+
+// Debug formatting of a str is a valid Rust string literal
+fn literal(value: &str) -> String {
+    format!("{:?}", value)
+}
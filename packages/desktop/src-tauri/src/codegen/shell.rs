@@ -0,0 +1,260 @@
+use super::{comments, seconds, Body, Snippet};
+use crate::db::request::FormFieldType;
+
+const CONTINUATION: &str = " \\\n  ";
+
+pub fn curl(snippet: &Snippet) -> String {
+    let mut args = vec!["curl".to_string()];
+
+    // Data and forms make curl send a POST unless told otherwise
+    let has_body = !matches!(snippet.body, Body::None);
+    match snippet.method.as_str() {
+        "HEAD" if !has_body => args.push("--head".to_string()),
+        "GET" if !has_body => {}
+        "POST" if has_body => {}
+        method => args.push(format!("-X {}", method)),
+    }
+    args.push(quote(&snippet.url));
+
+    for (name, value) in &snippet.headers {
+        args.push(format!("-H {}", quote(&format!("{}: {}", name, value))));
+    }
+    if let Some((username, password)) = &snippet.digest {
+        args.push("--digest".to_string());
+        args.push(format!(
+            "-u {}",
+            quote(&format!("{}:{}", username, password))
+        ));
+    }
+    if let Some(aws) = &snippet.aws {
+        let provider = format!("aws:amz:{}:{}", aws.region.trim(), aws.service.trim());
+        args.push(format!("--aws-sigv4 {}", quote(&provider)));
+        args.push(format!(
+            "-u {}",
+            quote(&format!("{}:{}", aws.access_key_id, aws.secret_access_key))
+        ));
+        if !aws.session_token.is_empty() {
+            args.push(format!(
+                "-H {}",
+                quote(&format!("x-amz-security-token: {}", aws.session_token))
+            ));
+        }
+    }
+
+    match &snippet.body {
+        Body::None => {}
+        Body::Text(text) => args.push(format!("--data-raw {}", quote(text))),
+        Body::File(path) => args.push(format!("--data-binary {}", quote(&format!("@{}", path)))),
+        Body::Multipart(fields) => {
+            for field in fields {
+                args.push(match field.field_type {
+                    FormFieldType::Text => {
+                        format!(
+                            "--form-string {}",
+                            quote(&format!("{}={}", field.key, field.value))
+                        )
+                    }
+                    FormFieldType::File => {
+                        let mut value = format!("{}=@{}", field.key, field.value.trim());
+                        if let Some(content_type) = &field.content_type {
+                            value.push_str(&format!(";type={}", content_type));
+                        }
+                        if let Some(file_name) = &field.file_name {
+                            value.push_str(&format!(";filename={}", file_name));
+                        }
+                        format!("-F {}", quote(&value))
+                    }
+                });
+            }
+        }
+    }
+
+    if let Some(max_redirects) = snippet.max_redirects {
+        args.push(format!("-L --max-redirs {}", max_redirects));
+    }
+    if let Some(timeout_ms) = snippet.timeout_ms {
+        args.push(format!("--max-time {}", seconds(timeout_ms)));
+    }
+
+    format!(
+        "{}{}\n",
+        comments(snippet, "#", true, true),
+        args.join(CONTINUATION)
+    )
+}
+
+pub fn httpie(snippet: &Snippet) -> String {
+    let mut args = vec!["http".to_string()];
+
+    if let Some(max_redirects) = snippet.max_redirects {
+        args.push(format!("--follow --max-redirects={}", max_redirects));
+    }
+    if let Some(timeout_ms) = snippet.timeout_ms {
+        args.push(format!("--timeout={}", seconds(timeout_ms)));
+    }
+    if let Some((username, password)) = &snippet.digest {
+        args.push(format!(
+            "-A digest -a {}",
+            quote(&format!("{}:{}", username, password))
+        ));
+    }
+    match &snippet.body {
+        Body::Text(text) => args.push(format!("--raw {}", quote(text))),
+        Body::Multipart(_) => args.push("--multipart".to_string()),
+        Body::None | Body::File(_) => {}
+    }
+
+    args.push(snippet.method.clone());
+    args.push(quote(&snippet.url));
+
+    for (name, value) in &snippet.headers {
+        // `Name;` sends a header with an empty value, `Name:` would remove it
+        args.push(if value.is_empty() {
+            quote(&format!("{};", name))
+        } else {
+            quote(&format!("{}:{}", name, value))
+        });
+    }
+
+    match &snippet.body {
+        Body::Multipart(fields) => {
+            for field in fields {
+                args.push(match field.field_type {
+                    FormFieldType::Text => quote(&format!("{}={}", field.key, field.value)),
+                    FormFieldType::File => {
+                        let mut value = format!("{}@{}", field.key, field.value.trim());
+                        if let Some(content_type) = &field.content_type {
+                            value.push_str(&format!(";type={}", content_type));
+                        }
+                        quote(&value)
+                    }
+                });
+            }
+        }
+        Body::File(path) => args.push(format!("< {}", quote(path))),
+        Body::None | Body::Text(_) => {}
+    }
+
+    format!(
+        "{}{}\n",
+        comments(snippet, "#", true, false),
+        args.join(CONTINUATION)
+    )
+}
+
+pub fn wget(snippet: &Snippet) -> String {
+    let mut args = vec!["wget -q -O -".to_string()];
+
+    // wget sends a body only along with a method
+    if snippet.method != "GET" || matches!(snippet.body, Body::Text(_) | Body::File(_)) {
+        args.push(format!("--method={}", snippet.method));
+    }
+    for (name, value) in &snippet.headers {
+        args.push(format!(
+            "--header={}",
+            quote(&format!("{}: {}", name, value))
+        ));
+    }
+    // wget answers Basic and Digest challenges on its own
+    if let Some((username, password)) = &snippet.digest {
+        args.push(format!("--user={}", quote(username)));
+        args.push(format!("--password={}", quote(password)));
+    }
+
+    let mut notes = Vec::new();
+    match &snippet.body {
+        Body::None => {}
+        Body::Text(text) => args.push(format!("--body-data={}", quote(text))),
+        Body::File(path) => args.push(format!("--body-file={}", quote(path))),
+        Body::Multipart(_) => notes.push("# wget cannot send multipart form data\n"),
+    }
+
+    args.push(format!(
+        "--max-redirect={}",
+        snippet.max_redirects.unwrap_or_default()
+    ));
+    if let Some(timeout_ms) = snippet.timeout_ms {
+        args.push(format!("--timeout={}", seconds(timeout_ms)));
+    }
+    args.push(quote(&snippet.url));
+
+    format!(
+        "{}{}{}\n",
+        comments(snippet, "#", true, false),
+        notes.concat(),
+        args.join(CONTINUATION)
+    )
+}
+
+// Single quotes keep everything literal; embedded ones are closed, escaped and reopened
+fn quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "'\\''"))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::codegen::{generate, CodegenTarget};
+    use crate::commands::http::HttpRequestInput;
+    use crate::db::request::RequestAuth;
+
+    fn input(method: &str, body: &str) -> HttpRequestInput {
+        HttpRequestInput {
+            method: method.to_string(),
+            url: "https://api.example.com/search".to_string(),
+            headers: Vec::new(),
+            body_type: if body.is_empty() { "none" } else { "raw" }.to_string(),
+            body_content: Some(body.to_string()),
+            settings: Default::default(),
+            auth: RequestAuth::None,
+        }
+    }
+
+    fn curl(method: &str, body: &str) -> String {
+        generate(CodegenTarget::Curl, input(method, body)).unwrap()
+    }
+
+    fn wget(method: &str, body: &str) -> String {
+        generate(CodegenTarget::Wget, input(method, body)).unwrap()
+    }
+
+    #[test]
+    fn curl_names_the_method_when_data_would_change_it() {
+        assert!(curl("GET", "{}").contains("-X GET"));
+        assert!(curl("HEAD", "{}").contains("-X HEAD"));
+        assert!(curl("DELETE", "{}").contains("-X DELETE"));
+        // POST is what data implies
+        assert!(!curl("POST", "{}").contains("-X"));
+    }
+
+    #[test]
+    fn curl_without_a_body() {
+        let get = curl("GET", "");
+        assert!(!get.contains("-X") && !get.contains("--data"), "{}", get);
+        assert!(curl("HEAD", "").contains("--head"));
+        assert!(curl("POST", "").contains("-X POST"));
+    }
+
+    #[test]
+    fn curl_sends_the_body() {
+        assert_eq!(
+            curl("PUT", "it's"),
+            "curl \\\n  -X PUT \\\n  'https://api.example.com/search' \\\n  --data-raw 'it'\\''s' \\\n  -L --max-redirs 10\n"
+        );
+    }
+
+    #[test]
+    fn wget_names_the_method_along_with_a_body() {
+        let get = wget("GET", "{}");
+        assert!(get.contains("--method=GET"), "{}", get);
+        assert!(get.contains("--body-data='{}'"), "{}", get);
+        assert!(wget("POST", "{}").contains("--method=POST"));
+        assert!(!wget("GET", "").contains("--method"));
+        assert!(wget("DELETE", "").contains("--method=DELETE"));
+    }
+
+    #[test]
+    fn httpie_always_names_the_method() {
+        let get = generate(CodegenTarget::Httpie, input("GET", "{}")).unwrap();
+        assert!(get.contains("--raw '{}'") && get.contains("GET"), "{}", get);
+    }
+}
@@ -0,0 +1,49 @@
+use serde::Deserialize;
+use tauri::State;
+
+use crate::codegen::{self, CodegenTarget};
+use crate::commands::http::{resolve_request, HttpRequestInput};
+use crate::db::collection::KIND_REQUEST;
+use crate::db::tab::TabState;
+use crate::db::Database;
+
+/// What to generate code for: the state of an open tab, a request from history
+/// or a saved collection request.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum CodegenSource {
+    Tab { state: Box<TabState> },
+    Request { id: String },
+    CollectionItem { id: String },
+}
+
+/// Writes the request out for `target`, with the workspace's variables substituted
+/// as they would be when sending it.
+#[tauri::command]
+pub fn generate_code(
+    db: State<'_, Database>,
+    workspace_id: Option<String>,
+    target: CodegenTarget,
+    source: CodegenSource,
+) -> Result<String, String> {
+    let state = match source {
+        CodegenSource::Tab { state } => *state,
+        CodegenSource::Request { id } => db
+            .get_request(&id)?
+            .ok_or_else(|| format!("Request not found: {}", id))?
+            .to_tab_state(),
+        CodegenSource::CollectionItem { id } => {
+            let item = db
+                .get_collection_item(&id)?
+                .ok_or_else(|| format!("Collection item not found: {}", id))?;
+            if item.kind != KIND_REQUEST {
+                return Err("Only requests can be exported as code".to_string());
+            }
+            item.to_tab_state()
+        }
+    };
+
+    let variables = db.get_active_variables(workspace_id.as_deref())?;
+    let input = resolve_request(HttpRequestInput::from(state), &variables, &mut Vec::new());
+    codegen::generate(target, input)
+}
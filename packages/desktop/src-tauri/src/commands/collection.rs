@@ -0,0 +1,168 @@
+use tauri::State;
+
+use crate::db::collection::{Collection, CollectionItem, CreateCollectionItemInput};
+use crate::db::tab::TabState;
+use crate::db::Database;
+use crate::fs_store;
+
+#[tauri::command]
+pub fn get_collections(
+    db: State<'_, Database>,
+    workspace_id: String,
+) -> Result<Vec<Collection>, String> {
+    db.get_collections(&workspace_id)
+}
+
+#[tauri::command]
+pub fn create_collection(
+    db: State<'_, Database>,
+    workspace_id: String,
+    name: String,
+) -> Result<Collection, String> {
+    let sync = fs_store::lock();
+    let collection = db.create_collection(&workspace_id, &name)?;
+    fs_store::save(&sync, &db, &workspace_id)?;
+    Ok(collection)
+}
+
+#[tauri::command]
+pub fn rename_collection(
+    db: State<'_, Database>,
+    id: String,
+    name: String,
+) -> Result<Collection, String> {
+    let sync = fs_store::lock();
+    let collection = db.rename_collection(&id, &name)?;
+    fs_store::save(&sync, &db, &collection.workspace_id)?;
+    Ok(collection)
+}
+
+#[tauri::command]
+pub fn delete_collection(db: State<'_, Database>, id: String) -> Result<(), String> {
+    let sync = fs_store::lock();
+    let collection = db.get_collection(&id)?;
+    db.delete_collection(&id)?;
+    match collection {
+        Some(collection) => fs_store::save(&sync, &db, &collection.workspace_id),
+        None => Ok(()),
+    }
+}
+
+#[tauri::command]
+pub fn reorder_collections(
+    db: State<'_, Database>,
+    workspace_id: String,
+    collection_ids: Vec<String>,
+) -> Result<(), String> {
+    let sync = fs_store::lock();
+    db.reorder_collections(&workspace_id, collection_ids)?;
+    fs_store::save(&sync, &db, &workspace_id)
+}
+
+#[tauri::command]
+pub fn get_collection_items(
+    db: State<'_, Database>,
+    collection_id: String,
+) -> Result<Vec<CollectionItem>, String> {
+    db.get_collection_items(&collection_id)
+}
+
+#[tauri::command]
+pub fn get_collection_item(
+    db: State<'_, Database>,
+    id: String,
+) -> Result<Option<CollectionItem>, String> {
+    db.get_collection_item(&id)
+}
+
+#[tauri::command]
+pub fn create_collection_item(
+    db: State<'_, Database>,
+    input: CreateCollectionItemInput,
+) -> Result<CollectionItem, String> {
+    let sync = fs_store::lock();
+    let item = db.create_collection_item(input)?;
+    fs_store::save_collection(&sync, &db, &item.collection_id)?;
+    Ok(item)
+}
+
+#[tauri::command]
+pub fn rename_collection_item(
+    db: State<'_, Database>,
+    id: String,
+    name: String,
+) -> Result<CollectionItem, String> {
+    let sync = fs_store::lock();
+    let item = db.rename_collection_item(&id, &name)?;
+    fs_store::save_collection(&sync, &db, &item.collection_id)?;
+    Ok(item)
+}
+
+#[tauri::command]
+pub fn move_collection_item(
+    db: State<'_, Database>,
+    id: String,
+    collection_id: String,
+    parent_id: Option<String>,
+    position: i32,
+) -> Result<CollectionItem, String> {
+    let sync = fs_store::lock();
+    let from = db.get_collection_item(&id)?.map(|item| item.collection_id);
+    let item = db.move_collection_item(&id, &collection_id, parent_id.as_deref(), position)?;
+    // Items can move to a collection of another workspace
+    if let Some(from) = from.filter(|from| *from != collection_id) {
+        fs_store::save_collection(&sync, &db, &from)?;
+    }
+    fs_store::save_collection(&sync, &db, &collection_id)?;
+    Ok(item)
+}
+
+#[tauri::command]
+pub fn duplicate_collection_item(
+    db: State<'_, Database>,
+    id: String,
+) -> Result<CollectionItem, String> {
+    let sync = fs_store::lock();
+    let item = db.duplicate_collection_item(&id)?;
+    fs_store::save_collection(&sync, &db, &item.collection_id)?;
+    Ok(item)
+}
+
+#[tauri::command]
+pub fn delete_collection_item(db: State<'_, Database>, id: String) -> Result<(), String> {
+    let sync = fs_store::lock();
+    let item = db.get_collection_item(&id)?;
+    db.delete_collection_item(&id)?;
+    match item {
+        Some(item) => fs_store::save_collection(&sync, &db, &item.collection_id),
+        None => Ok(()),
+    }
+}
+
+#[tauri::command]
+pub fn save_tab_to_collection(
+    db: State<'_, Database>,
+    tab_id: String,
+    state: TabState,
+) -> Result<CollectionItem, String> {
+    let sync = fs_store::lock();
+    let item = db.save_tab_to_collection(&tab_id, state)?;
+    fs_store::save_collection(&sync, &db, &item.collection_id)?;
+    Ok(item)
+}
+
+#[tauri::command]
+pub fn save_tab_as_collection_request(
+    db: State<'_, Database>,
+    tab_id: String,
+    state: TabState,
+    collection_id: String,
+    parent_id: Option<String>,
+    name: String,
+) -> Result<CollectionItem, String> {
+    let sync = fs_store::lock();
+    let item =
+        db.save_tab_as_collection_request(&tab_id, state, &collection_id, parent_id, &name)?;
+    fs_store::save_collection(&sync, &db, &item.collection_id)?;
+    Ok(item)
+}
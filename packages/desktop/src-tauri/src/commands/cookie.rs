@@ -0,0 +1,45 @@
+use tauri::State;
+
+use crate::db::cookie::{Cookie, CookieInput};
+use crate::db::Database;
+
+#[tauri::command]
+pub fn get_cookies(
+    db: State<'_, Database>,
+    workspace_id: String,
+    domain: Option<String>,
+) -> Result<Vec<Cookie>, String> {
+    db.get_cookies(&workspace_id, domain.as_deref())
+}
+
+#[tauri::command]
+pub fn save_cookie(
+    db: State<'_, Database>,
+    workspace_id: String,
+    input: CookieInput,
+) -> Result<Cookie, String> {
+    db.save_cookie(&workspace_id, input)
+}
+
+#[tauri::command]
+pub fn update_cookie(
+    db: State<'_, Database>,
+    id: String,
+    input: CookieInput,
+) -> Result<Cookie, String> {
+    db.update_cookie(&id, input)
+}
+
+#[tauri::command]
+pub fn delete_cookie(db: State<'_, Database>, id: String) -> Result<(), String> {
+    db.delete_cookie(&id)
+}
+
+#[tauri::command]
+pub fn clear_cookies(
+    db: State<'_, Database>,
+    workspace_id: String,
+    domain: Option<String>,
+) -> Result<usize, String> {
+    db.clear_cookies(&workspace_id, domain.as_deref())
+}
@@ -0,0 +1,67 @@
+use tauri::State;
+
+use crate::db::environment::{Environment, EnvironmentVariable};
+use crate::db::Database;
+use crate::fs_store;
+
+#[tauri::command]
+pub fn get_environments(
+    db: State<'_, Database>,
+    workspace_id: Option<String>,
+) -> Result<Vec<Environment>, String> {
+    db.get_environments(workspace_id.as_deref())
+}
+
+#[tauri::command]
+pub fn create_environment(
+    db: State<'_, Database>,
+    workspace_id: Option<String>,
+    name: String,
+    variables: Option<Vec<EnvironmentVariable>>,
+) -> Result<Environment, String> {
+    let sync = fs_store::lock();
+    let environment = db.create_environment(
+        workspace_id.as_deref(),
+        &name,
+        variables.unwrap_or_default(),
+    )?;
+    if let Some(workspace_id) = &workspace_id {
+        fs_store::save(&sync, &db, workspace_id)?;
+    }
+    Ok(environment)
+}
+
+#[tauri::command]
+pub fn update_environment(
+    db: State<'_, Database>,
+    id: String,
+    name: String,
+    variables: Vec<EnvironmentVariable>,
+) -> Result<Environment, String> {
+    let sync = fs_store::lock();
+    let environment = db.update_environment(&id, &name, variables)?;
+    if let Some(workspace_id) = &environment.workspace_id {
+        fs_store::save(&sync, &db, workspace_id)?;
+    }
+    Ok(environment)
+}
+
+#[tauri::command]
+pub fn delete_environment(db: State<'_, Database>, id: String) -> Result<(), String> {
+    let sync = fs_store::lock();
+    let environment = db.get_environment(&id)?;
+    db.delete_environment(&id)?;
+    match environment.and_then(|environment| environment.workspace_id) {
+        Some(workspace_id) => fs_store::save(&sync, &db, &workspace_id),
+        None => Ok(()),
+    }
+}
+
+#[tauri::command]
+pub fn set_active_environment(
+    db: State<'_, Database>,
+    workspace_id: Option<String>,
+    environment_id: Option<String>,
+) -> Result<(), String> {
+    db.set_active_environment(workspace_id.as_deref(), environment_id.as_deref())
+}
@@ -0,0 +1,50 @@
+use tauri::State;
+
+use crate::db::Database;
+use crate::export::har;
+use crate::export::workspace::{self, ExportOptions};
+
+/// Writes a workspace's history as a HAR 1.2 archive; only the given entries when
+/// `request_ids` is set.
+#[tauri::command]
+pub fn export_har(
+    db: State<'_, Database>,
+    workspace_id: String,
+    request_ids: Option<Vec<String>>,
+) -> Result<String, String> {
+    let requests = match request_ids {
+        Some(ids) => {
+            let mut requests = Vec::with_capacity(ids.len());
+            for id in ids {
+                let request = db
+                    .get_request(&id)?
+                    .filter(|r| r.workspace_id == workspace_id)
+                    .ok_or_else(|| format!("Request not found: {}", id))?;
+                requests.push(request);
+            }
+            requests
+        }
+        // A negative limit lifts SQLite's LIMIT
+        None => db.get_requests_by_workspace(&workspace_id, Some(-1), None)?,
+    };
+    har::export(&requests)
+}
+
+/// Writes a workspace as a JSON archive that `import_workspace` can read on another
+/// machine; secret variable values, the proxy password and auth credentials are left out.
+#[tauri::command]
+pub fn export_workspace(
+    db: State<'_, Database>,
+    workspace_id: String,
+    include_history: bool,
+    include_tabs: bool,
+) -> Result<String, String> {
+    workspace::export(
+        &db,
+        &workspace_id,
+        ExportOptions {
+            include_history,
+            include_tabs,
+        },
+    )
+}
@@ -1,13 +1,34 @@
 use std::collections::HashMap;
+use std::path::Path;
 use std::sync::Arc;
-use std::time::Instant;
+use std::time::{Instant, SystemTime};
 
-use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
-use reqwest::Method;
+use futures_util::StreamExt;
+use reqwest::header::{
+    HeaderMap, HeaderName, HeaderValue, AUTHORIZATION, CONTENT_LENGTH, CONTENT_TYPE, COOKIE,
+};
+use reqwest::{Method, StatusCode, Url};
 use serde::{Deserialize, Serialize};
+use tauri::State;
 use tokio::sync::Mutex;
 
-use crate::db::request::KeyValue;
+use crate::db::oauth::OAuth2Config;
+use crate::db::request::{
+    AwsCredentials, FormField, FormFieldType, KeyValue, RequestAuth, RequestSettings,
+    SignatureConfig,
+};
+use crate::db::settings::TimeoutSettings;
+use crate::db::tab::TabState;
+use crate::db::Database;
+use crate::net::auth;
+use crate::net::connection::ConnectionInfo;
+use crate::net::cookies::CookieJar;
+use crate::net::oauth;
+use crate::net::pool::{ClientOptions, ClientPool, ClientSelector};
+use crate::net::redirect::{self, RedirectHop};
+use crate::net::{signing, sigv4};
+use crate::net::timing::{TimingRecorder, Timings};
+use crate::variables;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct HttpRequestInput {
@@ -16,6 +37,24 @@ pub struct HttpRequestInput {
     pub headers: Vec<KeyValue>,
     pub body_type: String,
     pub body_content: Option<String>,
+    #[serde(default)]
+    pub settings: RequestSettings,
+    #[serde(default)]
+    pub auth: RequestAuth,
+}
+
+impl From<TabState> for HttpRequestInput {
+    fn from(state: TabState) -> Self {
+        Self {
+            method: state.method,
+            url: state.url,
+            headers: state.headers,
+            body_type: state.body_type,
+            body_content: Some(state.body_content),
+            settings: state.settings,
+            auth: state.auth,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -26,6 +65,14 @@ pub struct HttpResponse {
     pub body: String,
     pub time_ms: u64,
     pub size_bytes: u64,
+    pub timings: Timings,
+    /// Redirect responses that led to this one, in order
+    pub redirects: Vec<RedirectHop>,
+    /// Connection the final response arrived on
+    pub connection: ConnectionInfo,
+    /// The request as it was sent, after environment variables were substituted
+    pub resolved_request: HttpRequestInput,
+    pub unresolved_variables: Vec<String>,
 }
 
 // Store for active requests that can be cancelled
@@ -36,18 +83,68 @@ lazy_static::lazy_static! {
 
 #[tauri::command]
 pub async fn send_http_request(
+    db: State<'_, Database>,
+    pool: State<'_, ClientPool>,
     request_id: String,
+    workspace_id: Option<String>,
     input: HttpRequestInput,
 ) -> Result<HttpResponse, String> {
-    let (cancel_tx, cancel_rx) = tokio::sync::oneshot::channel::<()>();
-    
-    // Store the cancel sender
+    let variables = db.get_active_variables(workspace_id.as_deref())?;
+    let mut unresolved_variables = Vec::new();
+    let input = resolve_request(input, &variables, &mut unresolved_variables);
+
+    let timeouts = db
+        .get_timeout_settings(workspace_id.as_deref())?
+        .with_overrides(&input.settings.timeouts);
+
+    let proxy = db.get_effective_proxy_settings(workspace_id.as_deref())?;
+
+    let clients = ClientSelector::new(
+        &pool,
+        ClientOptions::new(&timeouts, &proxy),
+        db.get_tls_host_settings()?,
+        input.settings.fresh_connection,
+    );
+
+    let (cancel_tx, mut cancel_rx) = tokio::sync::oneshot::channel::<()>();
+
+    // Store the cancel sender before anything that may wait, such as a browser sign-in
     {
         let mut active = ACTIVE_REQUESTS.lock().await;
         active.insert(request_id.clone(), cancel_tx);
     }
 
-    let result = execute_request(input, cancel_rx).await;
+    let result = async {
+        // OAuth 2.0 tokens are taken from the cache, refreshed or granted before sending
+        let input = match input.auth {
+            RequestAuth::OAuth2(config) => {
+                let token = oauth::access_token(
+                    &db,
+                    &clients,
+                    workspace_id.as_deref(),
+                    &config,
+                    &open_in_browser,
+                );
+                let token = tokio::select! {
+                    token = token => token?,
+                    _ = &mut cancel_rx => return Err("Request cancelled".to_string()),
+                };
+                HttpRequestInput {
+                    auth: RequestAuth::Bearer { token },
+                    ..input
+                }
+            }
+            auth => HttpRequestInput { auth, ..input },
+        };
+
+        // Sends outside a workspace have no jar to use
+        let jar = workspace_id
+            .filter(|_| !input.settings.disable_cookie_jar)
+            .map(|id| CookieJar::new(&db, id));
+
+        execute_request(&clients, jar.as_ref(), input, timeouts, cancel_rx).await
+    }
+    .await;
 
     // Remove from active requests
     {
@@ -55,7 +152,10 @@ pub async fn send_http_request(
         active.remove(&request_id);
     }
 
-    result
+    result.map(|response| HttpResponse {
+        unresolved_variables,
+        ..response
+    })
 }
 
 #[tauri::command]
@@ -69,14 +169,198 @@ pub async fn cancel_http_request(request_id: String) -> Result<bool, String> {
     }
 }
 
+pub(crate) fn open_in_browser(url: &str) -> Result<(), String> {
+    tauri_plugin_opener::open_url(url, None::<&str>).map_err(|e| e.to_string())
+}
+
+/// Closes all pooled connections so the next request starts from a clean slate.
+#[tauri::command]
+pub async fn reset_http_connections(pool: State<'_, ClientPool>) -> Result<usize, String> {
+    Ok(pool.reset())
+}
+
+pub(crate) fn resolve_request(
+    input: HttpRequestInput,
+    variables: &HashMap<String, String>,
+    unresolved: &mut Vec<String>,
+) -> HttpRequestInput {
+    let url = variables::substitute(&input.url, variables, unresolved);
+
+    let headers = input
+        .headers
+        .into_iter()
+        .map(|kv| KeyValue {
+            key: variables::substitute(&kv.key, variables, unresolved),
+            value: variables::substitute(&kv.value, variables, unresolved),
+            ..kv
+        })
+        .collect();
+
+    let body_content = input.body_content.map(|body| {
+        // Substitute inside each form-data field rather than in the serialized text,
+        // where a value containing quotes would corrupt it
+        if input.body_type == "form-data" {
+            let fields: Vec<FormField> = parse_form_fields(&body)
+                .into_iter()
+                .map(|field| FormField {
+                    key: variables::substitute(&field.key, variables, unresolved),
+                    value: variables::substitute(&field.value, variables, unresolved),
+                    ..field
+                })
+                .collect();
+            return serde_json::to_string(&fields).unwrap_or(body);
+        }
+        variables::substitute(&body, variables, unresolved)
+    });
+
+    let mut substitute = |value: String| variables::substitute(&value, variables, unresolved);
+    let auth = match input.auth {
+        RequestAuth::None => RequestAuth::None,
+        RequestAuth::Basic { username, password } => RequestAuth::Basic {
+            username: substitute(username),
+            password: substitute(password),
+        },
+        RequestAuth::Bearer { token } => RequestAuth::Bearer {
+            token: substitute(token),
+        },
+        RequestAuth::ApiKey {
+            key,
+            value,
+            location,
+        } => RequestAuth::ApiKey {
+            key: substitute(key),
+            value: substitute(value),
+            location,
+        },
+        RequestAuth::Digest { username, password } => RequestAuth::Digest {
+            username: substitute(username),
+            password: substitute(password),
+        },
+        RequestAuth::AwsSigV4(credentials) => RequestAuth::AwsSigV4(AwsCredentials {
+            access_key_id: substitute(credentials.access_key_id),
+            secret_access_key: substitute(credentials.secret_access_key),
+            session_token: substitute(credentials.session_token),
+            region: substitute(credentials.region),
+            service: substitute(credentials.service),
+        }),
+        RequestAuth::Signature(config) => RequestAuth::Signature(SignatureConfig {
+            key: substitute(config.key),
+            template: substitute(config.template),
+            header: substitute(config.header),
+            prefix: substitute(config.prefix),
+            timestamp_header: substitute(config.timestamp_header),
+            ..config
+        }),
+        RequestAuth::OAuth2(config) => {
+            RequestAuth::OAuth2(resolve_oauth_config(config, variables, unresolved))
+        }
+    };
+
+    HttpRequestInput {
+        method: input.method,
+        url,
+        headers,
+        body_type: input.body_type,
+        body_content,
+        settings: input.settings,
+        auth,
+    }
+}
+
+pub(crate) fn resolve_oauth_config(
+    config: OAuth2Config,
+    variables: &HashMap<String, String>,
+    unresolved: &mut Vec<String>,
+) -> OAuth2Config {
+    let mut substitute = |value: String| variables::substitute(&value, variables, unresolved);
+
+    OAuth2Config {
+        token_url: substitute(config.token_url),
+        client_id: substitute(config.client_id),
+        client_secret: substitute(config.client_secret),
+        scope: substitute(config.scope),
+        username: substitute(config.username),
+        password: substitute(config.password),
+        auth_url: substitute(config.auth_url),
+        ..config
+    }
+}
+
+// Opens a file as a streamed request body, returning its length and guessed MIME type
+async fn file_body(path: &str) -> Result<(reqwest::Body, u64, String), String> {
+    let path = Path::new(path);
+    let file = tokio::fs::File::open(path).await.map_err(|e| match e.kind() {
+        std::io::ErrorKind::NotFound => format!("File not found: {}", path.display()),
+        _ => format!("Failed to open {}: {}", path.display(), e),
+    })?;
+
+    let metadata = file.metadata().await.map_err(|e| e.to_string())?;
+    if !metadata.is_file() {
+        return Err(format!("Not a file: {}", path.display()));
+    }
+
+    let content_type = mime_guess::from_path(path)
+        .first_or_octet_stream()
+        .to_string();
+
+    Ok((reqwest::Body::from(file), metadata.len(), content_type))
+}
+
+// Form-data is normally a JSON list of fields; fall back to URL-encoded text fields
+pub(crate) fn parse_form_fields(body: &str) -> Vec<FormField> {
+    if let Ok(fields) = serde_json::from_str::<Vec<FormField>>(body) {
+        return fields;
+    }
+
+    url::form_urlencoded::parse(body.trim().as_bytes())
+        .map(|(key, value)| FormField {
+            key: key.into_owned(),
+            value: value.into_owned(),
+            enabled: true,
+            description: None,
+            field_type: FormFieldType::Text,
+            file_name: None,
+            content_type: None,
+        })
+        .collect()
+}
+
+async fn file_part(field: &FormField) -> Result<reqwest::multipart::Part, String> {
+    let path = field.value.trim();
+    let (body, length, guessed_type) = file_body(path).await?;
+
+    let file_name = field.file_name.clone().unwrap_or_else(|| {
+        Path::new(path)
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default()
+    });
+    let content_type = field.content_type.as_deref().unwrap_or(&guessed_type);
+
+    reqwest::multipart::Part::stream_with_length(body, length)
+        .file_name(file_name)
+        .mime_str(content_type)
+        .map_err(|e| format!("Invalid content type for field {}: {}", field.key, e))
+}
+
+// The encoded body of a form held in memory
+async fn form_bytes(form: reqwest::multipart::Form) -> Result<Vec<u8>, String> {
+    let mut stream = std::pin::pin!(form.into_stream());
+    let mut body = Vec::new();
+    while let Some(chunk) = stream.next().await {
+        body.extend_from_slice(&chunk.map_err(|e| e.to_string())?);
+    }
+    Ok(body)
+}
+
 async fn execute_request(
+    clients: &ClientSelector<'_>,
+    jar: Option<&CookieJar<'_>>,
     input: HttpRequestInput,
+    timeouts: TimeoutSettings,
     cancel_rx: tokio::sync::oneshot::Receiver<()>,
 ) -> Result<HttpResponse, String> {
-    let client = reqwest::Client::builder()
-        .timeout(std::time::Duration::from_secs(300)) // 5 minute timeout
-        .build()
-        .map_err(|e| e.to_string())?;
+    let resolved_request = input.clone();
 
     let method = match input.method.to_uppercase().as_str() {
         "GET" => Method::GET,
@@ -89,7 +373,8 @@ async fn execute_request(
         _ => return Err(format!("Unsupported method: {}", input.method)),
     };
 
-    let mut request_builder = client.request(method, &input.url);
+    let mut url =
+        Url::parse(&input.url).map_err(|e| format!("Invalid URL {}: {}", input.url, e))?;
 
     // Add headers
     let mut headers = HeaderMap::new();
@@ -101,85 +386,320 @@ async fn execute_request(
             headers.insert(name, value);
         }
     }
-    request_builder = request_builder.headers(headers);
 
-    // Add body based on type
-    if let Some(body_content) = input.body_content {
-        if !body_content.is_empty() {
-            match input.body_type.as_str() {
-                "json" => {
-                    request_builder = request_builder
-                        .header("Content-Type", "application/json")
-                        .body(body_content);
+    // Credentials from the auth section take precedence over hand-written headers
+    let auth_header = auth::apply(&input.auth, &mut headers, &mut url)?;
+
+    let auth = input.auth;
+    let redirect_settings = input.settings.redirect;
+    let body_type = input.body_type;
+    let body_content = input.body_content.filter(|b| !b.is_empty());
+
+    let recorder = TimingRecorder::start();
+    let start = Instant::now();
+
+    // Execute request with cancellation support. The body is read inside the recorder
+    // scope so that connection and download phases are attributed to this request.
+    let request_future = recorder.scope(async move {
+        let mut method = method;
+        let mut url = url;
+        let mut body_content = body_content;
+        let mut redirects = Vec::new();
+        let mut digest_answered = false;
+        // Set once the chain has left the origin the credentials were meant for
+        let mut left_origin = false;
+
+        loop {
+            let client = clients.client_for(&url)?;
+            let request_builder = client.request(method.clone(), url.clone());
+            let hop_headers = with_cookies(&headers, jar, &url)?;
+            let request_builder = with_body(
+                request_builder,
+                &hop_headers,
+                &body_type,
+                body_content.as_deref(),
+            )
+            .await?;
+
+            let mut request = request_builder
+                .build()
+                .map_err(|e| request_error(e, &timeouts))?;
+            match &auth {
+                RequestAuth::AwsSigV4(credentials) if !left_origin => {
+                    // A file body is streamed, so it is hashed from the file itself
+                    let streamed_hash = match (body_type.as_str(), body_content.as_deref()) {
+                        ("binary", Some(path)) if sigv4::needs_file_hash(credentials) => {
+                            Some(sigv4::file_hash(path.trim()).await?)
+                        }
+                        _ => None,
+                    };
+                    sigv4::sign(
+                        &mut request,
+                        credentials,
+                        streamed_hash.as_deref(),
+                        SystemTime::now(),
+                    )?
                 }
-                "raw" => {
-                    request_builder = request_builder.body(body_content);
+                // Signatures are only sent to the origin they are meant for
+                RequestAuth::Signature(config) if !left_origin => {
+                    signing::sign(&mut request, config, SystemTime::now())?
                 }
-                "x-www-form-urlencoded" => {
-                    request_builder = request_builder
-                        .header("Content-Type", "application/x-www-form-urlencoded")
-                        .body(body_content);
+                _ => {}
+            }
+
+            TimingRecorder::mark_request_start();
+            let response = client
+                .execute(request)
+                .await
+                .map_err(|e| request_error(e, &timeouts))?;
+            TimingRecorder::mark_response_start();
+
+            if let Some(jar) = jar {
+                jar.store(&url, response.headers())?;
+            }
+
+            let status = response.status();
+
+            // A Digest challenge is answered once per hop by repeating the request
+            if let RequestAuth::Digest { username, password } = &auth {
+                let challenge = auth::digest_challenge(response.headers()).filter(|_| {
+                    status == StatusCode::UNAUTHORIZED && !digest_answered && !left_origin
+                });
+                if let Some(challenge) = challenge {
+                    let authorization = auth::digest_authorization(
+                        &challenge, username, password, &method, &url,
+                    )?;
+                    headers.insert(AUTHORIZATION, authorization);
+                    digest_answered = true;
+                    continue;
                 }
-                "form-data" => {
-                    // Parse form data from JSON
-                    if let Ok(form_data) = serde_json::from_str::<Vec<KeyValue>>(&body_content) {
-                        let mut form = reqwest::multipart::Form::new();
-                        for kv in form_data.into_iter().filter(|f| f.enabled) {
-                            form = form.text(kv.key, kv.value);
-                        }
-                        request_builder = request_builder.multipart(form);
-                    }
+            }
+
+            if let Some(next) = redirect::next_hop(
+                &redirect_settings,
+                &method,
+                &url,
+                status,
+                response.headers(),
+            ) {
+                if redirects.len() as u32 >= redirect_settings.max_redirects {
+                    return Err(format!(
+                        "Exceeded the maximum of {} redirects",
+                        redirect_settings.max_redirects
+                    ));
                 }
-                _ => {}
+
+                redirects.push(RedirectHop {
+                    url: url.to_string(),
+                    status: status.as_u16(),
+                    headers: to_key_values(response.headers()),
+                });
+
+                left_origin |= redirect::prepare_headers(
+                    &redirect_settings,
+                    &mut headers,
+                    auth_header.as_ref(),
+                    &url,
+                    &next,
+                );
+                if !next.keep_body {
+                    body_content = None;
+                }
+                method = next.method;
+                url = next.url;
+
+                // The answer covers the old URI, so the next hop is challenged afresh
+                if digest_answered {
+                    headers.remove(AUTHORIZATION);
+                    digest_answered = false;
+                }
+                continue;
             }
+
+            let connection = clients.connection_info(&response);
+            let headers = response.headers().clone();
+            let body_bytes = response
+                .bytes()
+                .await
+                .map_err(|e| request_error(e, &timeouts))?;
+            return Ok((status, headers, body_bytes, redirects, connection));
         }
-    }
+    });
 
-    let start = Instant::now();
+    let request_future = async move {
+        if timeouts.total_ms == 0 {
+            return request_future.await;
+        }
 
-    // Execute request with cancellation support
-    let request_future = request_builder.send();
+        let limit = std::time::Duration::from_millis(timeouts.total_ms);
+        tokio::time::timeout(limit, request_future)
+            .await
+            .unwrap_or_else(|_| {
+                Err(format!(
+                    "Request timed out: no complete response within {} ms",
+                    timeouts.total_ms
+                ))
+            })
+    };
 
     tokio::select! {
         result = request_future => {
-            match result {
-                Ok(response) => {
-                    let status = response.status().as_u16();
-                    let status_text = response.status().canonical_reason().unwrap_or("").to_string();
-                    
-                    // Extract headers
-                    let headers: Vec<KeyValue> = response
-                        .headers()
-                        .iter()
-                        .map(|(name, value)| KeyValue {
-                            key: name.to_string(),
-                            value: value.to_str().unwrap_or("").to_string(),
-                            enabled: true,
-                            description: None,
-                        })
-                        .collect();
-
-                    // Get body
-                    let body_bytes = response.bytes().await.map_err(|e| e.to_string())?;
-                    let size_bytes = body_bytes.len() as u64;
-                    let body = String::from_utf8_lossy(&body_bytes).to_string();
-                    
-                    let time_ms = start.elapsed().as_millis() as u64;
-
-                    Ok(HttpResponse {
-                        status,
-                        status_text,
-                        headers,
-                        body,
-                        time_ms,
-                        size_bytes,
-                    })
-                }
-                Err(e) => Err(e.to_string()),
-            }
+            let (status_code, response_headers, body_bytes, redirects, connection) = result?;
+            let timings = recorder.finish();
+            let status = status_code.as_u16();
+            let status_text = status_code.canonical_reason().unwrap_or("").to_string();
+
+            // Extract headers
+            let headers = to_key_values(&response_headers);
+
+            // Get body
+            let size_bytes = body_bytes.len() as u64;
+            let body = String::from_utf8_lossy(&body_bytes).to_string();
+
+            let time_ms = start.elapsed().as_millis() as u64;
+
+            Ok(HttpResponse {
+                status,
+                status_text,
+                headers,
+                body,
+                time_ms,
+                size_bytes,
+                timings,
+                redirects,
+                connection,
+                resolved_request,
+                unresolved_variables: Vec::new(),
+            })
         }
         _ = cancel_rx => {
             Err("Request cancelled".to_string())
         }
     }
 }
+
+// Adds headers and the body to a request. Called again for every redirect hop,
+// so file bodies are reopened rather than replayed from memory.
+async fn with_body(
+    mut request_builder: reqwest::RequestBuilder,
+    headers: &HeaderMap,
+    body_type: &str,
+    body_content: Option<&str>,
+) -> Result<reqwest::RequestBuilder, String> {
+    let has_content_type = headers.contains_key(CONTENT_TYPE);
+    request_builder = request_builder.headers(headers.clone());
+
+    // Add body based on type
+    if let Some(body_content) = body_content {
+        match body_type {
+            "json" => {
+                request_builder = request_builder
+                    .header("Content-Type", "application/json")
+                    .body(body_content.to_string());
+            }
+            "raw" => {
+                request_builder = request_builder.body(body_content.to_string());
+            }
+            "x-www-form-urlencoded" => {
+                request_builder = request_builder
+                    .header("Content-Type", "application/x-www-form-urlencoded")
+                    .body(body_content.to_string());
+            }
+            "form-data" => {
+                let mut form = reqwest::multipart::Form::new();
+                let fields: Vec<FormField> = parse_form_fields(body_content)
+                    .into_iter()
+                    .filter(|f| f.enabled)
+                    .collect();
+                let has_files = fields.iter().any(|f| f.field_type == FormFieldType::File);
+                for field in fields {
+                    form = match field.field_type {
+                        FormFieldType::Text => form.text(field.key, field.value),
+                        FormFieldType::File => {
+                            let part = file_part(&field).await?;
+                            form.part(field.key, part)
+                        }
+                    };
+                }
+                request_builder = if has_files {
+                    request_builder.multipart(form)
+                } else {
+                    // Forms of text fields are sent from memory, so they can be hashed for signing
+                    let content_type = format!("multipart/form-data; boundary={}", form.boundary());
+                    request_builder
+                        .header(CONTENT_TYPE, content_type)
+                        .body(form_bytes(form).await?)
+                };
+            }
+            "binary" => {
+                // Body content is the path of the file to upload
+                let (body, length, content_type) = file_body(body_content.trim()).await?;
+                if !has_content_type {
+                    request_builder = request_builder.header(CONTENT_TYPE, content_type);
+                }
+                request_builder = request_builder.header(CONTENT_LENGTH, length).body(body);
+            }
+            _ => {}
+        }
+    }
+
+    Ok(request_builder)
+}
+
+// Adds the jar's cookies for `url` after any the request sets itself
+fn with_cookies(
+    headers: &HeaderMap,
+    jar: Option<&CookieJar<'_>>,
+    url: &Url,
+) -> Result<HeaderMap, String> {
+    let mut headers = headers.clone();
+    let Some(cookies) = jar.map(|jar| jar.header_for(url)).transpose()?.flatten() else {
+        return Ok(headers);
+    };
+
+    let value = match headers.get(COOKIE).and_then(|v| v.to_str().ok()) {
+        Some(existing) if !existing.trim().is_empty() => format!("{}; {}", existing, cookies),
+        _ => cookies,
+    };
+    let value = HeaderValue::from_str(&value).map_err(|e| format!("Invalid cookie: {}", e))?;
+    headers.insert(COOKIE, value);
+
+    Ok(headers)
+}
+
+// Reports timeouts distinctly from other transport failures
+fn request_error(e: reqwest::Error, timeouts: &TimeoutSettings) -> String {
+    if !e.is_timeout() {
+        return e.to_string();
+    }
+
+    if e.is_connect() {
+        if timeouts.connect_ms > 0 {
+            format!(
+                "Connect timed out: no connection within {} ms",
+                timeouts.connect_ms
+            )
+        } else {
+            format!("Connect timed out: {}", e)
+        }
+    } else if timeouts.read_ms > 0 {
+        format!(
+            "Read timed out: no data received for {} ms",
+            timeouts.read_ms
+        )
+    } else {
+        format!("Read timed out: {}", e)
+    }
+}
+
+fn to_key_values(headers: &HeaderMap) -> Vec<KeyValue> {
+    headers
+        .iter()
+        .map(|(name, value)| KeyValue {
+            key: name.to_string(),
+            value: value.to_str().unwrap_or("").to_string(),
+            enabled: true,
+            description: None,
+        })
+        .collect()
+}
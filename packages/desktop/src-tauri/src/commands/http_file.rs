@@ -0,0 +1,119 @@
+use std::path::Path;
+
+use tauri::State;
+
+use crate::db::collection::{CollectionItem, KIND_FOLDER};
+use crate::db::tab::TabState;
+use crate::db::Database;
+use crate::http_file;
+use crate::import::{
+    self, ImportIssue, ImportReport, Imported, ImportedCollection, ImportedEnvironment,
+    ImportedItem,
+};
+
+/// Opens a `.http` / `.rest` file as a new collection that is saved back to the file.
+/// Its `@variables` become an environment named after the file.
+#[tauri::command]
+pub fn open_http_file(
+    db: State<'_, Database>,
+    workspace_id: Option<String>,
+    path: String,
+) -> Result<ImportReport, String> {
+    let path = std::fs::canonicalize(&path).map_err(|e| e.to_string())?;
+    let text = std::fs::read_to_string(&path).map_err(|e| e.to_string())?;
+    let file = http_file::parse(&text, path.parent().unwrap_or(Path::new("")));
+
+    let name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let variables = file.variables();
+    let imported = Imported {
+        name: name.clone(),
+        collections: vec![ImportedCollection {
+            name: name.clone(),
+            items: file
+                .requests()
+                .map(|(name, state)| ImportedItem::Request {
+                    name: name.to_string(),
+                    state: Box::new(state.clone()),
+                })
+                .collect(),
+        }],
+        environments: if variables.is_empty() {
+            Vec::new()
+        } else {
+            vec![ImportedEnvironment { name, variables }]
+        },
+        skipped: file.unsupported(),
+    };
+
+    let report = import::store(&db, workspace_id.as_deref(), imported)?;
+    let collection_id = &report.collection_ids[0];
+    db.set_collection_file_path(collection_id, Some(&path.to_string_lossy()))?;
+    let names: Vec<String> = file.requests().map(|(name, _)| name.to_string()).collect();
+    db.set_file_requests(collection_id, &names)?;
+    Ok(report)
+}
+
+/// Writes a collection opened with `open_http_file` back to its file, returning what
+/// could not be written.
+///
+/// Requests that did not change keep their text as it is on disk, and requests added to
+/// the file outside the app are kept. The format has no folders, so their requests are
+/// written at the top level, and edits to the environment made from the file's
+/// variables are not written back.
+#[tauri::command]
+pub fn save_http_file(
+    db: State<'_, Database>,
+    collection_id: String,
+) -> Result<Vec<ImportIssue>, String> {
+    let collection = db
+        .get_collection(&collection_id)?
+        .ok_or_else(|| "Collection not found".to_string())?;
+    let path = collection
+        .file_path
+        .ok_or_else(|| "Collection was not opened from a .http file".to_string())?;
+    let path = Path::new(&path);
+    let dir = path.parent().unwrap_or(Path::new(""));
+
+    // Start from the file as it is now, which may have been edited since it was opened
+    let text = match std::fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(e.to_string()),
+    };
+    let mut file = http_file::parse(&text, dir);
+
+    let items = db.get_collection_items(&collection_id)?;
+    let mut requests = Vec::new();
+    let mut issues = Vec::new();
+    flatten(&items, None, &mut requests, &mut issues);
+    let synced = db.get_file_requests(&collection_id)?;
+    issues.extend(file.update(&requests, synced.as_deref(), dir));
+
+    std::fs::write(path, file.to_string()).map_err(|e| e.to_string())?;
+    let names: Vec<String> = requests.into_iter().map(|(name, _)| name).collect();
+    db.set_file_requests(&collection_id, &names)?;
+    Ok(issues)
+}
+
+// Requests in tree order; items are already sorted by position
+fn flatten(
+    items: &[CollectionItem],
+    parent_id: Option<&str>,
+    requests: &mut Vec<(String, TabState)>,
+    issues: &mut Vec<ImportIssue>,
+) {
+    for item in items.iter().filter(|i| i.parent_id.as_deref() == parent_id) {
+        if item.kind == KIND_FOLDER {
+            issues.push(ImportIssue {
+                location: item.name.clone(),
+                message: "Folder; its requests are saved at the top level".to_string(),
+            });
+            flatten(items, Some(&item.id), requests, issues);
+        } else {
+            requests.push((item.name.clone(), item.to_tab_state()));
+        }
+    }
+}
@@ -0,0 +1,100 @@
+use std::path::Path;
+
+use serde::Deserialize;
+use tauri::State;
+
+use crate::db::Database;
+use crate::import::curl::CurlImport;
+use crate::import::{self, bruno, curl, har, insomnia, openapi, postman, workspace, ImportReport};
+
+/// Where the requests of a HAR archive are imported to.
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HarTarget {
+    /// History entries, with their responses and timings
+    History,
+    /// A collection of the requests alone
+    Collection,
+}
+
+/// Parses a pasted curl command into the state of a new tab, with the options that
+/// could not be carried over.
+#[tauri::command]
+pub fn import_curl(command: String) -> Result<CurlImport, String> {
+    curl::parse(&command)
+}
+
+/// Imports a Postman collection or environment file into a workspace, or into a new
+/// workspace when `workspace_id` is `None`.
+#[tauri::command]
+pub fn import_postman(
+    db: State<'_, Database>,
+    workspace_id: Option<String>,
+    content: String,
+) -> Result<ImportReport, String> {
+    let imported = postman::parse(&content)?;
+    import::store(&db, workspace_id.as_deref(), imported)
+}
+
+/// Imports an OpenAPI 3 or Swagger 2 document (JSON or YAML) as a collection, with an
+/// environment per server.
+#[tauri::command]
+pub fn import_openapi(
+    db: State<'_, Database>,
+    workspace_id: Option<String>,
+    content: String,
+) -> Result<ImportReport, String> {
+    let imported = openapi::parse(&content)?;
+    import::store(&db, workspace_id.as_deref(), imported)
+}
+
+/// Imports an Insomnia v4 JSON export; each Insomnia workspace becomes a collection.
+#[tauri::command]
+pub fn import_insomnia(
+    db: State<'_, Database>,
+    workspace_id: Option<String>,
+    content: String,
+) -> Result<ImportReport, String> {
+    let imported = insomnia::parse(&content)?;
+    import::store(&db, workspace_id.as_deref(), imported)
+}
+
+/// Imports a Bruno collection from its directory, including its environments.
+#[tauri::command]
+pub fn import_bruno(
+    db: State<'_, Database>,
+    workspace_id: Option<String>,
+    path: String,
+) -> Result<ImportReport, String> {
+    let imported = bruno::read(Path::new(&path))?;
+    import::store(&db, workspace_id.as_deref(), imported)
+}
+
+/// Imports a HAR archive captured by browser devtools into a workspace's history or
+/// as a collection; a new workspace is created when `workspace_id` is `None`.
+#[tauri::command]
+pub fn import_har(
+    db: State<'_, Database>,
+    workspace_id: Option<String>,
+    content: String,
+    target: HarTarget,
+) -> Result<ImportReport, String> {
+    let archive = har::parse(&content)?;
+    match target {
+        HarTarget::History => har::store(&db, workspace_id.as_deref(), archive),
+        HarTarget::Collection => {
+            import::store(&db, workspace_id.as_deref(), archive.into_collection())
+        }
+    }
+}
+
+/// Imports a workspace archive as a new workspace, named `name` or after the exported one.
+#[tauri::command]
+pub fn import_workspace(
+    db: State<'_, Database>,
+    content: String,
+    name: Option<String>,
+) -> Result<ImportReport, String> {
+    let archive = workspace::parse(&content)?;
+    workspace::store(&db, archive, name.as_deref())
+}
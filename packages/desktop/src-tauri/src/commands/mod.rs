@@ -2,10 +2,28 @@ pub mod workspace;
 pub mod request;
 pub mod tab;
 pub mod settings;
+pub mod environment;
+pub mod collection;
+pub mod tls;
+pub mod cookie;
+pub mod oauth;
+pub mod import;
+pub mod export;
+pub mod http_file;
+pub mod codegen;
 pub mod http;
 
 pub use workspace::*;
 pub use request::*;
 pub use tab::*;
 pub use settings::*;
+pub use environment::*;
+pub use collection::*;
+pub use tls::*;
+pub use cookie::*;
+pub use oauth::*;
+pub use import::*;
+pub use export::*;
+pub use http_file::*;
+pub use codegen::*;
 pub use http::*;
@@ -0,0 +1,37 @@
+use tauri::State;
+
+use crate::commands::http::{open_in_browser, resolve_oauth_config};
+use crate::db::oauth::{OAuth2Config, OAuthToken};
+use crate::db::Database;
+use crate::net::oauth;
+use crate::net::pool::{ClientOptions, ClientPool, ClientSelector};
+
+/// Returns the cached token for `config`, refreshing or obtaining one as needed. With
+/// `force`, a new token is obtained even if the cached one is still valid.
+#[tauri::command]
+pub async fn get_oauth_token(
+    db: State<'_, Database>,
+    pool: State<'_, ClientPool>,
+    workspace_id: String,
+    config: OAuth2Config,
+    force: bool,
+) -> Result<OAuthToken, String> {
+    let variables = db.get_active_variables(Some(&workspace_id))?;
+    let config = resolve_oauth_config(config, &variables, &mut Vec::new());
+
+    let timeouts = db.get_timeout_settings(Some(&workspace_id))?;
+    let proxy = db.get_effective_proxy_settings(Some(&workspace_id))?;
+    let clients = ClientSelector::new(
+        &pool,
+        ClientOptions::new(&timeouts, &proxy),
+        db.get_tls_host_settings()?,
+        false,
+    );
+
+    oauth::token(&db, &clients, &workspace_id, &config, force, &open_in_browser).await
+}
+
+#[tauri::command]
+pub fn clear_oauth_tokens(db: State<'_, Database>, workspace_id: String) -> Result<usize, String> {
+    db.clear_oauth_tokens(&workspace_id)
+}
@@ -1,6 +1,6 @@
 use tauri::State;
 
-use crate::db::settings::AppSettings;
+use crate::db::settings::{AppSettings, ProxySettings, TimeoutSettings};
 use crate::db::Database;
 
 #[tauri::command]
@@ -22,3 +22,37 @@ pub fn get_all_settings(db: State<'_, Database>) -> Result<AppSettings, String>
 pub fn save_all_settings(db: State<'_, Database>, settings: AppSettings) -> Result<(), String> {
     db.save_all_settings(&settings)
 }
+
+#[tauri::command]
+pub fn get_timeout_settings(
+    db: State<'_, Database>,
+    workspace_id: String,
+) -> Result<TimeoutSettings, String> {
+    db.get_timeout_settings(Some(&workspace_id))
+}
+
+#[tauri::command]
+pub fn save_timeout_settings(
+    db: State<'_, Database>,
+    workspace_id: String,
+    timeouts: TimeoutSettings,
+) -> Result<(), String> {
+    db.save_timeout_settings(&workspace_id, &timeouts)
+}
+
+#[tauri::command]
+pub fn get_proxy_settings(
+    db: State<'_, Database>,
+    workspace_id: Option<String>,
+) -> Result<Option<ProxySettings>, String> {
+    db.get_proxy_settings(workspace_id.as_deref())
+}
+
+#[tauri::command]
+pub fn save_proxy_settings(
+    db: State<'_, Database>,
+    workspace_id: Option<String>,
+    proxy: Option<ProxySettings>,
+) -> Result<(), String> {
+    db.save_proxy_settings(workspace_id.as_deref(), proxy.as_ref())
+}
@@ -0,0 +1,31 @@
+use tauri::State;
+
+use crate::db::tls::{TlsHostSettings, TlsHostSettingsInput};
+use crate::db::Database;
+
+#[tauri::command]
+pub fn get_tls_host_settings(db: State<'_, Database>) -> Result<Vec<TlsHostSettings>, String> {
+    db.get_tls_host_settings()
+}
+
+#[tauri::command]
+pub fn create_tls_host_settings(
+    db: State<'_, Database>,
+    input: TlsHostSettingsInput,
+) -> Result<TlsHostSettings, String> {
+    db.create_tls_host_settings(input)
+}
+
+#[tauri::command]
+pub fn update_tls_host_settings(
+    db: State<'_, Database>,
+    id: String,
+    input: TlsHostSettingsInput,
+) -> Result<TlsHostSettings, String> {
+    db.update_tls_host_settings(&id, input)
+}
+
+#[tauri::command]
+pub fn delete_tls_host_settings(db: State<'_, Database>, id: String) -> Result<(), String> {
+    db.delete_tls_host_settings(&id)
+}
@@ -1,7 +1,10 @@
-use tauri::State;
+use std::path::Path;
+
+use tauri::{AppHandle, State};
 
 use crate::db::workspace::Workspace;
 use crate::db::Database;
+use crate::fs_store::{self, watcher};
 
 #[tauri::command]
 pub fn get_all_workspaces(db: State<'_, Database>) -> Result<Vec<Workspace>, String> {
@@ -29,5 +32,32 @@ pub fn update_workspace(
 
 #[tauri::command]
 pub fn delete_workspace(db: State<'_, Database>, id: String) -> Result<(), String> {
-    db.delete_workspace(&id)
+    db.delete_workspace(&id)?;
+    watcher::unwatch(&id);
+    Ok(())
+}
+
+/// Stores a workspace's collections and environments in a directory, one file per
+/// request, and reloads them when the files change; `None` goes back to keeping them
+/// in the database alone, leaving the files in place.
+///
+/// A directory that already holds a workspace, such as a git checkout, replaces the
+/// workspace's collections and environments. History and tabs stay in the database.
+#[tauri::command]
+pub fn set_workspace_directory(
+    app: AppHandle,
+    db: State<'_, Database>,
+    id: String,
+    directory: Option<String>,
+) -> Result<Workspace, String> {
+    let Some(directory) = directory else {
+        watcher::unwatch(&id);
+        return db.set_workspace_directory(&id, None);
+    };
+
+    let workspace = fs_store::bind(&db, &id, Path::new(&directory))?;
+    if let Some(directory) = workspace.directory.as_deref() {
+        watcher::watch(&app, &id, Path::new(directory))?;
+    }
+    Ok(workspace)
 }
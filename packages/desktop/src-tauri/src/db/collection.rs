@@ -0,0 +1,849 @@
+use std::collections::HashMap;
+
+use rusqlite::{params, Connection, Row};
+use serde::{Deserialize, Serialize};
+
+use super::request::{KeyValue, RequestAuth, RequestSettings};
+use super::tab::TabState;
+use super::Database;
+
+pub const KIND_FOLDER: &str = "folder";
+pub const KIND_REQUEST: &str = "request";
+
+const COLLECTION_COLUMNS: &str =
+    "id, workspace_id, name, position, file_path, created_at, updated_at";
+
+const ITEM_COLUMNS: &str = "id, collection_id, parent_id, kind, name, position, method, url, \
+                            params, headers, body_type, body_content, settings, auth, \
+                            created_at, updated_at";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Collection {
+    pub id: String,
+    pub workspace_id: String,
+    pub name: String,
+    pub position: i32,
+    /// The `.http` file the collection was opened from and is saved back to
+    pub file_path: Option<String>,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CollectionItem {
+    pub id: String,
+    pub collection_id: String,
+    pub parent_id: Option<String>,
+    /// Either "folder" or "request"; request fields are `None` for folders
+    pub kind: String,
+    pub name: String,
+    pub position: i32,
+    pub method: Option<String>,
+    pub url: Option<String>,
+    pub params: Option<Vec<KeyValue>>,
+    pub headers: Option<Vec<KeyValue>>,
+    pub body_type: Option<String>,
+    pub body_content: Option<String>,
+    pub settings: Option<RequestSettings>,
+    pub auth: Option<RequestAuth>,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateCollectionItemInput {
+    pub collection_id: String,
+    pub parent_id: Option<String>,
+    pub kind: String,
+    pub name: String,
+    pub state: Option<TabState>,
+}
+
+impl Collection {
+    pub fn new(workspace_id: &str, name: &str, position: i32) -> Self {
+        let now = now_millis();
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            workspace_id: workspace_id.to_string(),
+            name: name.to_string(),
+            position,
+            file_path: None,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+}
+
+impl CollectionItem {
+    /// A new folder, or a request holding `state`, at `position` among its siblings.
+    pub fn new(
+        collection_id: &str,
+        parent_id: Option<&str>,
+        kind: &str,
+        name: &str,
+        position: i32,
+        state: Option<TabState>,
+    ) -> Self {
+        let now = now_millis();
+        let mut item = Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            collection_id: collection_id.to_string(),
+            parent_id: parent_id.map(str::to_string),
+            kind: kind.to_string(),
+            name: name.to_string(),
+            position,
+            method: None,
+            url: None,
+            params: None,
+            headers: None,
+            body_type: None,
+            body_content: None,
+            settings: None,
+            auth: None,
+            created_at: now,
+            updated_at: now,
+        };
+
+        if kind == KIND_REQUEST {
+            let state = state.unwrap_or_default();
+            item.method = Some(state.method);
+            item.url = Some(state.url);
+            item.params = Some(state.params);
+            item.headers = Some(state.headers);
+            item.body_type = Some(state.body_type);
+            item.body_content = Some(state.body_content);
+            item.settings = Some(state.settings);
+            item.auth = Some(state.auth);
+        }
+
+        item
+    }
+
+    pub fn to_tab_state(&self) -> TabState {
+        TabState {
+            method: self.method.clone().unwrap_or_else(|| "GET".to_string()),
+            url: self.url.clone().unwrap_or_default(),
+            params: self.params.clone().unwrap_or_default(),
+            headers: self.headers.clone().unwrap_or_default(),
+            body_type: self.body_type.clone().unwrap_or_else(|| "none".to_string()),
+            body_content: self.body_content.clone().unwrap_or_default(),
+            settings: self.settings.clone().unwrap_or_default(),
+            auth: self.auth.clone().unwrap_or_default(),
+            is_dirty: false,
+        }
+    }
+}
+
+fn now_millis() -> i64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_millis() as i64
+}
+
+fn row_to_collection(row: &Row) -> rusqlite::Result<Collection> {
+    Ok(Collection {
+        id: row.get(0)?,
+        workspace_id: row.get(1)?,
+        name: row.get(2)?,
+        position: row.get(3)?,
+        file_path: row.get(4)?,
+        created_at: row.get(5)?,
+        updated_at: row.get(6)?,
+    })
+}
+
+fn row_to_item(row: &Row) -> rusqlite::Result<CollectionItem> {
+    let params_json: Option<String> = row.get(8)?;
+    let headers_json: Option<String> = row.get(9)?;
+    let settings_json: Option<String> = row.get(12)?;
+    let auth_json: Option<String> = row.get(13)?;
+
+    Ok(CollectionItem {
+        id: row.get(0)?,
+        collection_id: row.get(1)?,
+        parent_id: row.get(2)?,
+        kind: row.get(3)?,
+        name: row.get(4)?,
+        position: row.get(5)?,
+        method: row.get(6)?,
+        url: row.get(7)?,
+        params: params_json.and_then(|s| serde_json::from_str(&s).ok()),
+        headers: headers_json.and_then(|s| serde_json::from_str(&s).ok()),
+        body_type: row.get(10)?,
+        body_content: row.get(11)?,
+        settings: settings_json.and_then(|s| serde_json::from_str(&s).ok()),
+        auth: auth_json.and_then(|s| serde_json::from_str(&s).ok()),
+        created_at: row.get(14)?,
+        updated_at: row.get(15)?,
+    })
+}
+
+fn query_item(conn: &Connection, id: &str) -> Option<CollectionItem> {
+    conn.query_row(
+        &format!(
+            "SELECT {} FROM collection_items WHERE id = ?1",
+            ITEM_COLUMNS
+        ),
+        [id],
+        row_to_item,
+    )
+    .ok()
+}
+
+// Returns the item and all of its descendants, parents always before their children
+fn query_subtree(conn: &Connection, id: &str) -> Result<Vec<CollectionItem>, String> {
+    let mut stmt = conn
+        .prepare(&format!(
+            "WITH RECURSIVE subtree(item_id, depth) AS (
+                SELECT id, 0 FROM collection_items WHERE id = ?1
+                UNION ALL
+                SELECT c.id, s.depth + 1 FROM collection_items c
+                INNER JOIN subtree s ON c.parent_id = s.item_id
+             )
+             SELECT {} FROM collection_items
+             INNER JOIN subtree ON collection_items.id = subtree.item_id
+             ORDER BY subtree.depth ASC, position ASC",
+            ITEM_COLUMNS
+        ))
+        .map_err(|e| e.to_string())?;
+
+    let items = stmt
+        .query_map([id], row_to_item)
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    Ok(items)
+}
+
+fn query_sibling_ids(
+    conn: &Connection,
+    collection_id: &str,
+    parent_id: Option<&str>,
+) -> Result<Vec<String>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT id FROM collection_items
+             WHERE collection_id = ?1 AND parent_id IS ?2
+             ORDER BY position ASC",
+        )
+        .map_err(|e| e.to_string())?;
+
+    let ids = stmt
+        .query_map(params![collection_id, parent_id], |row| row.get(0))
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    Ok(ids)
+}
+
+fn write_positions(conn: &Connection, ids: &[String]) -> Result<(), String> {
+    for (position, id) in ids.iter().enumerate() {
+        conn.execute(
+            "UPDATE collection_items SET position = ?1 WHERE id = ?2",
+            params![&(position as i32), id],
+        )
+        .map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+pub(super) fn insert_collection(conn: &Connection, collection: &Collection) -> Result<(), String> {
+    conn.execute(
+        &format!(
+            "INSERT INTO collections ({}) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            COLLECTION_COLUMNS
+        ),
+        params![
+            &collection.id,
+            &collection.workspace_id,
+            &collection.name,
+            &collection.position,
+            &collection.file_path,
+            &collection.created_at,
+            &collection.updated_at,
+        ],
+    )
+    .map_err(|e| e.to_string())?;
+
+    Ok(())
+}
+
+pub(super) fn insert_item(conn: &Connection, item: &CollectionItem) -> Result<(), String> {
+    let params_json = item
+        .params
+        .as_ref()
+        .and_then(|p| serde_json::to_string(p).ok());
+    let headers_json = item
+        .headers
+        .as_ref()
+        .and_then(|h| serde_json::to_string(h).ok());
+    let settings_json = item
+        .settings
+        .as_ref()
+        .and_then(|s| serde_json::to_string(s).ok());
+    let auth_json = item
+        .auth
+        .as_ref()
+        .and_then(|a| serde_json::to_string(a).ok());
+
+    conn.execute(
+        "INSERT INTO collection_items (id, collection_id, parent_id, kind, name, position, method, url, params,
+                                       headers, body_type, body_content, settings, auth, created_at, updated_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16)",
+        params![
+            &item.id,
+            &item.collection_id,
+            &item.parent_id,
+            &item.kind,
+            &item.name,
+            &item.position,
+            &item.method,
+            &item.url,
+            &params_json,
+            &headers_json,
+            &item.body_type,
+            &item.body_content,
+            &settings_json,
+            &auth_json,
+            &item.created_at,
+            &item.updated_at,
+        ],
+    )
+    .map_err(|e| e.to_string())?;
+
+    Ok(())
+}
+
+/// Replaces every collection of a workspace and their items, keeping the given ids;
+/// used when its files are reloaded, see `fs_store`.
+pub(super) fn replace_collections(
+    conn: &Connection,
+    workspace_id: &str,
+    collections: &[(Collection, Vec<CollectionItem>)],
+) -> Result<(), String> {
+    // What was recorded of the .http files collections are linked to is not in the files
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, file_requests FROM collections
+             WHERE workspace_id = ?1 AND file_requests IS NOT NULL",
+        )
+        .map_err(|e| e.to_string())?;
+    let file_requests: Vec<(String, String)> = stmt
+        .query_map([workspace_id], |row| Ok((row.get(0)?, row.get(1)?)))
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    conn.execute(
+        "DELETE FROM collection_items
+         WHERE collection_id IN (SELECT id FROM collections WHERE workspace_id = ?1)",
+        [workspace_id],
+    )
+    .map_err(|e| e.to_string())?;
+    conn.execute(
+        "DELETE FROM collections WHERE workspace_id = ?1",
+        [workspace_id],
+    )
+    .map_err(|e| e.to_string())?;
+
+    for (collection, items) in collections {
+        insert_collection(
+            conn,
+            &Collection {
+                workspace_id: workspace_id.to_string(),
+                ..collection.clone()
+            },
+        )?;
+        for item in items {
+            insert_item(conn, item)?;
+        }
+    }
+    for (id, names) in &file_requests {
+        conn.execute(
+            "UPDATE collections SET file_requests = ?1 WHERE id = ?2",
+            params![names, id],
+        )
+        .map_err(|e| e.to_string())?;
+    }
+
+    Ok(())
+}
+
+// Checks that `parent_id` is a folder of `collection_id`
+fn validate_parent(
+    conn: &Connection,
+    collection_id: &str,
+    parent_id: Option<&str>,
+) -> Result<(), String> {
+    if let Some(parent_id) = parent_id {
+        let parent =
+            query_item(conn, parent_id).ok_or_else(|| "Parent folder not found".to_string())?;
+        if parent.kind != KIND_FOLDER || parent.collection_id != collection_id {
+            return Err("Parent must be a folder in the same collection".to_string());
+        }
+    }
+    Ok(())
+}
+
+impl Database {
+    pub fn get_collections(&self, workspace_id: &str) -> Result<Vec<Collection>, String> {
+        let conn = self.conn.lock().unwrap();
+
+        let mut stmt = conn
+            .prepare(&format!(
+                "SELECT {} FROM collections
+                 WHERE workspace_id = ?1
+                 ORDER BY position ASC",
+                COLLECTION_COLUMNS
+            ))
+            .map_err(|e| e.to_string())?;
+
+        let collections = stmt
+            .query_map([workspace_id], row_to_collection)
+            .map_err(|e| e.to_string())?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(collections)
+    }
+
+    pub fn get_collection(&self, id: &str) -> Result<Option<Collection>, String> {
+        let conn = self.conn.lock().unwrap();
+
+        let collection = conn
+            .query_row(
+                &format!(
+                    "SELECT {} FROM collections WHERE id = ?1",
+                    COLLECTION_COLUMNS
+                ),
+                [id],
+                row_to_collection,
+            )
+            .ok();
+
+        Ok(collection)
+    }
+
+    pub fn create_collection(&self, workspace_id: &str, name: &str) -> Result<Collection, String> {
+        let conn = self.conn.lock().unwrap();
+
+        let max_position: i32 = conn
+            .query_row(
+                "SELECT COALESCE(MAX(position), -1) FROM collections WHERE workspace_id = ?1",
+                [workspace_id],
+                |row| row.get(0),
+            )
+            .unwrap_or(-1);
+
+        let collection = Collection::new(workspace_id, name, max_position + 1);
+        insert_collection(&conn, &collection)?;
+
+        Ok(collection)
+    }
+
+    pub fn rename_collection(&self, id: &str, name: &str) -> Result<Collection, String> {
+        let conn = self.conn.lock().unwrap();
+
+        conn.execute(
+            "UPDATE collections SET name = ?1, updated_at = ?2 WHERE id = ?3",
+            params![name, &now_millis(), id],
+        )
+        .map_err(|e| e.to_string())?;
+
+        drop(conn);
+        self.get_collection(id)?
+            .ok_or_else(|| "Collection not found".to_string())
+    }
+
+    pub fn set_collection_file_path(
+        &self,
+        id: &str,
+        file_path: Option<&str>,
+    ) -> Result<Collection, String> {
+        let conn = self.conn.lock().unwrap();
+
+        conn.execute(
+            "UPDATE collections SET file_path = ?1, updated_at = ?2 WHERE id = ?3",
+            params![file_path, &now_millis(), id],
+        )
+        .map_err(|e| e.to_string())?;
+
+        drop(conn);
+        self.get_collection(id)?
+            .ok_or_else(|| "Collection not found".to_string())
+    }
+
+    /// Names of the requests in a collection's `.http` file when it was last read or
+    /// written, telling requests added to the file since apart from ones deleted in
+    /// the app. `None` if it was not recorded.
+    pub fn get_file_requests(&self, id: &str) -> Result<Option<Vec<String>>, String> {
+        let conn = self.conn.lock().unwrap();
+
+        let names: Option<String> = conn
+            .query_row(
+                "SELECT file_requests FROM collections WHERE id = ?1",
+                [id],
+                |row| row.get(0),
+            )
+            .map_err(|e| e.to_string())?;
+
+        Ok(names.and_then(|s| serde_json::from_str(&s).ok()))
+    }
+
+    pub fn set_file_requests(&self, id: &str, names: &[String]) -> Result<(), String> {
+        let conn = self.conn.lock().unwrap();
+
+        let names_json = serde_json::to_string(names).map_err(|e| e.to_string())?;
+        conn.execute(
+            "UPDATE collections SET file_requests = ?1 WHERE id = ?2",
+            params![&names_json, id],
+        )
+        .map_err(|e| e.to_string())?;
+
+        Ok(())
+    }
+
+    pub fn delete_collection(&self, id: &str) -> Result<(), String> {
+        let conn = self.conn.lock().unwrap();
+
+        conn.execute(
+            "DELETE FROM collection_items WHERE collection_id = ?1",
+            [id],
+        )
+        .map_err(|e| e.to_string())?;
+        conn.execute("DELETE FROM collections WHERE id = ?1", [id])
+            .map_err(|e| e.to_string())?;
+
+        Ok(())
+    }
+
+    pub fn reorder_collections(
+        &self,
+        workspace_id: &str,
+        collection_ids: Vec<String>,
+    ) -> Result<(), String> {
+        let conn = self.conn.lock().unwrap();
+
+        for (position, collection_id) in collection_ids.iter().enumerate() {
+            conn.execute(
+                "UPDATE collections SET position = ?1 WHERE id = ?2 AND workspace_id = ?3",
+                params![&(position as i32), collection_id, workspace_id],
+            )
+            .map_err(|e| e.to_string())?;
+        }
+
+        Ok(())
+    }
+
+    /// Returns every item of a collection as a flat list ordered by position;
+    /// the tree is rebuilt from `parent_id`.
+    pub fn get_collection_items(&self, collection_id: &str) -> Result<Vec<CollectionItem>, String> {
+        let conn = self.conn.lock().unwrap();
+
+        let mut stmt = conn
+            .prepare(&format!(
+                "SELECT {} FROM collection_items WHERE collection_id = ?1 ORDER BY position ASC",
+                ITEM_COLUMNS
+            ))
+            .map_err(|e| e.to_string())?;
+
+        let items = stmt
+            .query_map([collection_id], row_to_item)
+            .map_err(|e| e.to_string())?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(items)
+    }
+
+    pub fn get_collection_item(&self, id: &str) -> Result<Option<CollectionItem>, String> {
+        let conn = self.conn.lock().unwrap();
+        Ok(query_item(&conn, id))
+    }
+
+    pub fn create_collection_item(
+        &self,
+        input: CreateCollectionItemInput,
+    ) -> Result<CollectionItem, String> {
+        if input.kind != KIND_FOLDER && input.kind != KIND_REQUEST {
+            return Err(format!("Unsupported collection item kind: {}", input.kind));
+        }
+
+        let conn = self.conn.lock().unwrap();
+        validate_parent(&conn, &input.collection_id, input.parent_id.as_deref())?;
+
+        let position = query_sibling_ids(&conn, &input.collection_id, input.parent_id.as_deref())?
+            .len() as i32;
+
+        let item = CollectionItem::new(
+            &input.collection_id,
+            input.parent_id.as_deref(),
+            &input.kind,
+            &input.name,
+            position,
+            input.state,
+        );
+
+        insert_item(&conn, &item)?;
+
+        Ok(item)
+    }
+
+    pub fn rename_collection_item(&self, id: &str, name: &str) -> Result<CollectionItem, String> {
+        let conn = self.conn.lock().unwrap();
+
+        conn.execute(
+            "UPDATE collection_items SET name = ?1, updated_at = ?2 WHERE id = ?3",
+            params![name, &now_millis(), id],
+        )
+        .map_err(|e| e.to_string())?;
+
+        query_item(&conn, id).ok_or_else(|| "Collection item not found".to_string())
+    }
+
+    /// Overwrites the request fields of a collection request with the given editor state.
+    pub fn save_collection_request(
+        &self,
+        id: &str,
+        state: &TabState,
+    ) -> Result<CollectionItem, String> {
+        let conn = self.conn.lock().unwrap();
+
+        let params_json = serde_json::to_string(&state.params).map_err(|e| e.to_string())?;
+        let headers_json = serde_json::to_string(&state.headers).map_err(|e| e.to_string())?;
+        let settings_json = serde_json::to_string(&state.settings).map_err(|e| e.to_string())?;
+        let auth_json = serde_json::to_string(&state.auth).map_err(|e| e.to_string())?;
+
+        let changes = conn
+            .execute(
+                "UPDATE collection_items
+                 SET method = ?1, url = ?2, params = ?3, headers = ?4, body_type = ?5,
+                     body_content = ?6, settings = ?7, auth = ?8, updated_at = ?9
+                 WHERE id = ?10 AND kind = ?11",
+                params![
+                    &state.method,
+                    &state.url,
+                    &params_json,
+                    &headers_json,
+                    &state.body_type,
+                    &state.body_content,
+                    &settings_json,
+                    &auth_json,
+                    &now_millis(),
+                    id,
+                    KIND_REQUEST,
+                ],
+            )
+            .map_err(|e| e.to_string())?;
+
+        if changes == 0 {
+            return Err("Collection request not found".to_string());
+        }
+
+        query_item(&conn, id).ok_or_else(|| "Collection request not found".to_string())
+    }
+
+    /// Moves an item (and its descendants) under `parent_id` of `collection_id` at `position`.
+    pub fn move_collection_item(
+        &self,
+        id: &str,
+        collection_id: &str,
+        parent_id: Option<&str>,
+        position: i32,
+    ) -> Result<CollectionItem, String> {
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction().map_err(|e| e.to_string())?;
+
+        let item = query_item(&tx, id).ok_or_else(|| "Collection item not found".to_string())?;
+        validate_parent(&tx, collection_id, parent_id)?;
+
+        let subtree = query_subtree(&tx, id)?;
+        if let Some(parent_id) = parent_id {
+            if subtree.iter().any(|i| i.id == parent_id) {
+                return Err("Cannot move a folder into itself".to_string());
+            }
+        }
+
+        // Close the gap left in the old parent
+        let old_siblings: Vec<String> =
+            query_sibling_ids(&tx, &item.collection_id, item.parent_id.as_deref())?
+                .into_iter()
+                .filter(|sibling| sibling != id)
+                .collect();
+        write_positions(&tx, &old_siblings)?;
+
+        let mut new_siblings: Vec<String> = query_sibling_ids(&tx, collection_id, parent_id)?
+            .into_iter()
+            .filter(|sibling| sibling != id)
+            .collect();
+        let index = (position.max(0) as usize).min(new_siblings.len());
+        new_siblings.insert(index, id.to_string());
+
+        tx.execute(
+            "UPDATE collection_items SET parent_id = ?1, updated_at = ?2 WHERE id = ?3",
+            params![parent_id, &now_millis(), id],
+        )
+        .map_err(|e| e.to_string())?;
+
+        for descendant in &subtree {
+            tx.execute(
+                "UPDATE collection_items SET collection_id = ?1 WHERE id = ?2",
+                params![collection_id, &descendant.id],
+            )
+            .map_err(|e| e.to_string())?;
+        }
+
+        write_positions(&tx, &new_siblings)?;
+
+        let moved = query_item(&tx, id).ok_or_else(|| "Collection item not found".to_string())?;
+        tx.commit().map_err(|e| e.to_string())?;
+
+        Ok(moved)
+    }
+
+    /// Copies an item right after itself; folders are copied with all of their contents.
+    pub fn duplicate_collection_item(&self, id: &str) -> Result<CollectionItem, String> {
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction().map_err(|e| e.to_string())?;
+
+        let subtree = query_subtree(&tx, id)?;
+        let original = subtree
+            .first()
+            .cloned()
+            .ok_or_else(|| "Collection item not found".to_string())?;
+
+        let now = now_millis();
+        let mut id_map: HashMap<String, String> = HashMap::new();
+        let mut copy_id = String::new();
+
+        for item in subtree {
+            let new_id = uuid::Uuid::new_v4().to_string();
+            let is_root = item.id == original.id;
+            let parent_id = if is_root {
+                item.parent_id.clone()
+            } else {
+                item.parent_id.as_ref().and_then(|p| id_map.get(p).cloned())
+            };
+
+            id_map.insert(item.id.clone(), new_id.clone());
+            if is_root {
+                copy_id = new_id.clone();
+            }
+
+            insert_item(
+                &tx,
+                &CollectionItem {
+                    id: new_id,
+                    parent_id,
+                    name: if is_root {
+                        format!("{} Copy", item.name)
+                    } else {
+                        item.name.clone()
+                    },
+                    created_at: now,
+                    updated_at: now,
+                    ..item
+                },
+            )?;
+        }
+
+        // Place the copy right after the original
+        let mut siblings: Vec<String> =
+            query_sibling_ids(&tx, &original.collection_id, original.parent_id.as_deref())?
+                .into_iter()
+                .filter(|sibling| sibling != &copy_id)
+                .collect();
+        let index = siblings
+            .iter()
+            .position(|sibling| sibling == &original.id)
+            .map(|i| i + 1)
+            .unwrap_or(siblings.len());
+        siblings.insert(index, copy_id.clone());
+        write_positions(&tx, &siblings)?;
+
+        let copy =
+            query_item(&tx, &copy_id).ok_or_else(|| "Collection item not found".to_string())?;
+        tx.commit().map_err(|e| e.to_string())?;
+
+        Ok(copy)
+    }
+
+    pub fn delete_collection_item(&self, id: &str) -> Result<(), String> {
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction().map_err(|e| e.to_string())?;
+
+        let item = match query_item(&tx, id) {
+            Some(item) => item,
+            None => return Ok(()),
+        };
+
+        let subtree = query_subtree(&tx, id)?;
+        for descendant in &subtree {
+            tx.execute(
+                "DELETE FROM collection_items WHERE id = ?1",
+                [&descendant.id],
+            )
+            .map_err(|e| e.to_string())?;
+        }
+
+        let siblings = query_sibling_ids(&tx, &item.collection_id, item.parent_id.as_deref())?;
+        write_positions(&tx, &siblings)?;
+        tx.commit().map_err(|e| e.to_string())?;
+
+        Ok(())
+    }
+
+    /// Saves a tab into the collection request it is linked to and marks the tab clean.
+    pub fn save_tab_to_collection(
+        &self,
+        tab_id: &str,
+        state: TabState,
+    ) -> Result<CollectionItem, String> {
+        let request_id = self
+            .get_tab(tab_id)?
+            .ok_or_else(|| "Tab not found".to_string())?
+            .request_id
+            .ok_or_else(|| "Tab is not linked to a collection request".to_string())?;
+
+        let item = self.save_collection_request(&request_id, &state)?;
+        self.update_tab(
+            tab_id,
+            TabState {
+                is_dirty: false,
+                ..state
+            },
+            Some(item.id.clone()),
+        )?;
+
+        Ok(item)
+    }
+
+    /// Creates a new collection request from a tab and links the tab to it.
+    pub fn save_tab_as_collection_request(
+        &self,
+        tab_id: &str,
+        state: TabState,
+        collection_id: &str,
+        parent_id: Option<String>,
+        name: &str,
+    ) -> Result<CollectionItem, String> {
+        let state = TabState {
+            is_dirty: false,
+            ..state
+        };
+
+        let item = self.create_collection_item(CreateCollectionItemInput {
+            collection_id: collection_id.to_string(),
+            parent_id,
+            kind: KIND_REQUEST.to_string(),
+            name: name.to_string(),
+            state: Some(state.clone()),
+        })?;
+        self.update_tab(tab_id, state, Some(item.id.clone()))?;
+
+        Ok(item)
+    }
+}
@@ -0,0 +1,278 @@
+use rusqlite::{params, Row};
+use serde::{Deserialize, Serialize};
+
+use super::Database;
+
+const COOKIE_COLUMNS: &str = "id, workspace_id, name, value, domain, path, expires, secure, \
+                              http_only, same_site, host_only, created_at, updated_at";
+
+/// A cookie in a workspace's jar.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Cookie {
+    pub id: String,
+    pub workspace_id: String,
+    pub name: String,
+    pub value: String,
+    /// Lowercase host name, without a leading dot
+    pub domain: String,
+    pub path: String,
+    /// Expiry in milliseconds since the epoch; `None` for session cookies
+    pub expires: Option<i64>,
+    pub secure: bool,
+    pub http_only: bool,
+    pub same_site: Option<String>,
+    /// Sent to `domain` only, not to its subdomains
+    pub host_only: bool,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CookieInput {
+    pub name: String,
+    pub value: String,
+    pub domain: String,
+    #[serde(default = "default_path")]
+    pub path: String,
+    #[serde(default)]
+    pub expires: Option<i64>,
+    #[serde(default)]
+    pub secure: bool,
+    #[serde(default)]
+    pub http_only: bool,
+    #[serde(default)]
+    pub same_site: Option<String>,
+    #[serde(default = "default_host_only")]
+    pub host_only: bool,
+}
+
+fn default_path() -> String {
+    "/".to_string()
+}
+
+fn default_host_only() -> bool {
+    true
+}
+
+fn row_to_cookie(row: &Row) -> rusqlite::Result<Cookie> {
+    let secure: i32 = row.get(7)?;
+    let http_only: i32 = row.get(8)?;
+    let host_only: i32 = row.get(10)?;
+
+    Ok(Cookie {
+        id: row.get(0)?,
+        workspace_id: row.get(1)?,
+        name: row.get(2)?,
+        value: row.get(3)?,
+        domain: row.get(4)?,
+        path: row.get(5)?,
+        expires: row.get(6)?,
+        secure: secure == 1,
+        http_only: http_only == 1,
+        same_site: row.get(9)?,
+        host_only: host_only == 1,
+        created_at: row.get(11)?,
+        updated_at: row.get(12)?,
+    })
+}
+
+fn validate_cookie(input: CookieInput) -> Result<CookieInput, String> {
+    let name = input.name.trim().to_string();
+    if name.is_empty() {
+        return Err("Cookie name is required".to_string());
+    }
+
+    let domain = input.domain.trim().trim_start_matches('.').to_lowercase();
+    if domain.is_empty() {
+        return Err("Cookie domain is required".to_string());
+    }
+
+    let path = if input.path.starts_with('/') {
+        input.path
+    } else {
+        default_path()
+    };
+
+    Ok(CookieInput {
+        name,
+        domain,
+        path,
+        ..input
+    })
+}
+
+impl Database {
+    /// Lists the cookies of a workspace, optionally only those set for `domain`.
+    pub fn get_cookies(
+        &self,
+        workspace_id: &str,
+        domain: Option<&str>,
+    ) -> Result<Vec<Cookie>, String> {
+        let conn = self.conn.lock().unwrap();
+
+        let mut stmt = conn
+            .prepare(&format!(
+                "SELECT {} FROM cookies
+                 WHERE workspace_id = ?1 AND (?2 IS NULL OR domain = ?2)
+                 ORDER BY domain ASC, path ASC, name ASC",
+                COOKIE_COLUMNS
+            ))
+            .map_err(|e| e.to_string())?;
+
+        let domain = domain.map(|d| d.trim().trim_start_matches('.').to_lowercase());
+        let cookies = stmt
+            .query_map(params![workspace_id, domain], row_to_cookie)
+            .map_err(|e| e.to_string())?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(cookies)
+    }
+
+    /// Stores a cookie, replacing the one with the same domain, path and name.
+    pub fn save_cookie(&self, workspace_id: &str, input: CookieInput) -> Result<Cookie, String> {
+        let input = validate_cookie(input)?;
+        let conn = self.conn.lock().unwrap();
+
+        let id = uuid::Uuid::new_v4().to_string();
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_millis() as i64;
+
+        // Keep the id and creation time of a replaced cookie
+        conn.execute(
+            &format!(
+                "INSERT INTO cookies ({})
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)
+                 ON CONFLICT (workspace_id, domain, path, name) DO UPDATE SET
+                    value = excluded.value,
+                    expires = excluded.expires,
+                    secure = excluded.secure,
+                    http_only = excluded.http_only,
+                    same_site = excluded.same_site,
+                    host_only = excluded.host_only,
+                    updated_at = excluded.updated_at",
+                COOKIE_COLUMNS
+            ),
+            params![
+                &id,
+                workspace_id,
+                &input.name,
+                &input.value,
+                &input.domain,
+                &input.path,
+                &input.expires,
+                &(input.secure as i32),
+                &(input.http_only as i32),
+                &input.same_site,
+                &(input.host_only as i32),
+                &now,
+                &now,
+            ],
+        )
+        .map_err(|e| e.to_string())?;
+
+        conn.query_row(
+            &format!(
+                "SELECT {} FROM cookies
+                 WHERE workspace_id = ?1 AND domain = ?2 AND path = ?3 AND name = ?4",
+                COOKIE_COLUMNS
+            ),
+            params![workspace_id, &input.domain, &input.path, &input.name],
+            row_to_cookie,
+        )
+        .map_err(|e| e.to_string())
+    }
+
+    pub fn update_cookie(&self, id: &str, input: CookieInput) -> Result<Cookie, String> {
+        let input = validate_cookie(input)?;
+        let conn = self.conn.lock().unwrap();
+
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_millis() as i64;
+
+        let changes = conn
+            .execute(
+                "UPDATE cookies
+                 SET name = ?1, value = ?2, domain = ?3, path = ?4, expires = ?5, secure = ?6,
+                     http_only = ?7, same_site = ?8, host_only = ?9, updated_at = ?10
+                 WHERE id = ?11",
+                params![
+                    &input.name,
+                    &input.value,
+                    &input.domain,
+                    &input.path,
+                    &input.expires,
+                    &(input.secure as i32),
+                    &(input.http_only as i32),
+                    &input.same_site,
+                    &(input.host_only as i32),
+                    &now,
+                    id,
+                ],
+            )
+            .map_err(|e| match e.sqlite_error_code() {
+                Some(rusqlite::ErrorCode::ConstraintViolation) => format!(
+                    "A cookie named {} already exists for {}{}",
+                    input.name, input.domain, input.path
+                ),
+                _ => e.to_string(),
+            })?;
+
+        if changes == 0 {
+            return Err("Cookie not found".to_string());
+        }
+
+        conn.query_row(
+            &format!("SELECT {} FROM cookies WHERE id = ?1", COOKIE_COLUMNS),
+            [id],
+            row_to_cookie,
+        )
+        .map_err(|e| e.to_string())
+    }
+
+    pub fn delete_cookie(&self, id: &str) -> Result<(), String> {
+        let conn = self.conn.lock().unwrap();
+
+        conn.execute("DELETE FROM cookies WHERE id = ?1", [id])
+            .map_err(|e| e.to_string())?;
+
+        Ok(())
+    }
+
+    /// Removes a cookie by its identity, as a response expiring it does.
+    pub fn remove_cookie(
+        &self,
+        workspace_id: &str,
+        domain: &str,
+        path: &str,
+        name: &str,
+    ) -> Result<(), String> {
+        let conn = self.conn.lock().unwrap();
+
+        conn.execute(
+            "DELETE FROM cookies
+             WHERE workspace_id = ?1 AND domain = ?2 AND path = ?3 AND name = ?4",
+            params![workspace_id, domain, path, name],
+        )
+        .map_err(|e| e.to_string())?;
+
+        Ok(())
+    }
+
+    /// Empties the jar of a workspace, or only the cookies of `domain`. Returns how many
+    /// cookies were removed.
+    pub fn clear_cookies(&self, workspace_id: &str, domain: Option<&str>) -> Result<usize, String> {
+        let conn = self.conn.lock().unwrap();
+
+        let domain = domain.map(|d| d.trim().trim_start_matches('.').to_lowercase());
+        conn.execute(
+            "DELETE FROM cookies WHERE workspace_id = ?1 AND (?2 IS NULL OR domain = ?2)",
+            params![workspace_id, domain],
+        )
+        .map_err(|e| e.to_string())
+    }
+}
@@ -0,0 +1,272 @@
+use std::collections::HashMap;
+
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+
+use super::Database;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EnvironmentVariable {
+    pub key: String,
+    pub value: String,
+    pub enabled: bool,
+    #[serde(default)]
+    pub secret: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Environment {
+    pub id: String,
+    /// `None` for global environments shared by every workspace
+    pub workspace_id: Option<String>,
+    pub name: String,
+    pub variables: Vec<EnvironmentVariable>,
+    pub is_active: bool,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+impl Environment {
+    pub fn new(
+        workspace_id: Option<&str>,
+        name: &str,
+        variables: Vec<EnvironmentVariable>,
+    ) -> Self {
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_millis() as i64;
+
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            workspace_id: workspace_id.map(|s| s.to_string()),
+            name: name.to_string(),
+            variables,
+            is_active: false,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+}
+
+pub(super) fn insert_environment(
+    conn: &Connection,
+    environment: &Environment,
+) -> Result<(), String> {
+    let variables_json =
+        serde_json::to_string(&environment.variables).map_err(|e| e.to_string())?;
+
+    conn.execute(
+        "INSERT INTO environments (id, workspace_id, name, variables, is_active, created_at, updated_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        params![
+            &environment.id,
+            &environment.workspace_id,
+            &environment.name,
+            &variables_json,
+            &(environment.is_active as i32),
+            &environment.created_at,
+            &environment.updated_at,
+        ],
+    )
+    .map_err(|e| e.to_string())?;
+
+    Ok(())
+}
+
+/// Replaces the environments of a workspace, keeping the given ids; used when its
+/// files are reloaded, see `fs_store`.
+pub(super) fn replace_environments(
+    conn: &Connection,
+    workspace_id: &str,
+    environments: &[Environment],
+) -> Result<(), String> {
+    conn.execute(
+        "DELETE FROM environments WHERE workspace_id = ?1",
+        [workspace_id],
+    )
+    .map_err(|e| e.to_string())?;
+
+    for environment in environments {
+        insert_environment(
+            conn,
+            &Environment {
+                workspace_id: Some(workspace_id.to_string()),
+                ..environment.clone()
+            },
+        )?;
+    }
+
+    Ok(())
+}
+
+impl Database {
+    /// Lists the environments of a workspace, or the global ones when `workspace_id` is `None`.
+    pub fn get_environments(&self, workspace_id: Option<&str>) -> Result<Vec<Environment>, String> {
+        let conn = self.conn.lock().unwrap();
+
+        let mut stmt = conn
+            .prepare(
+                "SELECT id, workspace_id, name, variables, is_active, created_at, updated_at
+                 FROM environments
+                 WHERE workspace_id IS ?1
+                 ORDER BY created_at ASC",
+            )
+            .map_err(|e| e.to_string())?;
+
+        let environments = stmt
+            .query_map([workspace_id], |row| {
+                let variables_json: String = row.get(3)?;
+                let is_active: i32 = row.get(4)?;
+
+                Ok(Environment {
+                    id: row.get(0)?,
+                    workspace_id: row.get(1)?,
+                    name: row.get(2)?,
+                    variables: serde_json::from_str(&variables_json).unwrap_or_default(),
+                    is_active: is_active == 1,
+                    created_at: row.get(5)?,
+                    updated_at: row.get(6)?,
+                })
+            })
+            .map_err(|e| e.to_string())?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(environments)
+    }
+
+    pub fn get_environment(&self, id: &str) -> Result<Option<Environment>, String> {
+        let conn = self.conn.lock().unwrap();
+
+        let environment = conn
+            .query_row(
+                "SELECT id, workspace_id, name, variables, is_active, created_at, updated_at
+                 FROM environments WHERE id = ?1",
+                [id],
+                |row| {
+                    let variables_json: String = row.get(3)?;
+                    let is_active: i32 = row.get(4)?;
+
+                    Ok(Environment {
+                        id: row.get(0)?,
+                        workspace_id: row.get(1)?,
+                        name: row.get(2)?,
+                        variables: serde_json::from_str(&variables_json).unwrap_or_default(),
+                        is_active: is_active == 1,
+                        created_at: row.get(5)?,
+                        updated_at: row.get(6)?,
+                    })
+                },
+            )
+            .ok();
+
+        Ok(environment)
+    }
+
+    pub fn create_environment(
+        &self,
+        workspace_id: Option<&str>,
+        name: &str,
+        variables: Vec<EnvironmentVariable>,
+    ) -> Result<Environment, String> {
+        let conn = self.conn.lock().unwrap();
+
+        let environment = Environment::new(workspace_id, name, variables);
+        insert_environment(&conn, &environment)?;
+
+        Ok(environment)
+    }
+
+    pub fn update_environment(
+        &self,
+        id: &str,
+        name: &str,
+        variables: Vec<EnvironmentVariable>,
+    ) -> Result<Environment, String> {
+        let conn = self.conn.lock().unwrap();
+
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_millis() as i64;
+        let variables_json = serde_json::to_string(&variables).map_err(|e| e.to_string())?;
+
+        conn.execute(
+            "UPDATE environments SET name = ?1, variables = ?2, updated_at = ?3 WHERE id = ?4",
+            params![name, &variables_json, &now, id],
+        )
+        .map_err(|e| e.to_string())?;
+
+        drop(conn);
+        self.get_environment(id)?
+            .ok_or_else(|| "Environment not found".to_string())
+    }
+
+    pub fn delete_environment(&self, id: &str) -> Result<(), String> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute("DELETE FROM environments WHERE id = ?1", [id])
+            .map_err(|e| e.to_string())?;
+        Ok(())
+    }
+
+    /// Activates `environment_id` within its scope, or deactivates the scope when it is `None`.
+    pub fn set_active_environment(
+        &self,
+        workspace_id: Option<&str>,
+        environment_id: Option<&str>,
+    ) -> Result<(), String> {
+        let conn = self.conn.lock().unwrap();
+
+        // Deactivate all environments in the same scope
+        conn.execute(
+            "UPDATE environments SET is_active = 0 WHERE workspace_id IS ?1",
+            [workspace_id],
+        )
+        .map_err(|e| e.to_string())?;
+
+        if let Some(environment_id) = environment_id {
+            conn.execute(
+                "UPDATE environments SET is_active = 1 WHERE id = ?1 AND workspace_id IS ?2",
+                params![environment_id, workspace_id],
+            )
+            .map_err(|e| e.to_string())?;
+        }
+
+        Ok(())
+    }
+
+    /// Collects the enabled variables of the active global environment, overridden by
+    /// those of the active environment of `workspace_id`.
+    pub fn get_active_variables(
+        &self,
+        workspace_id: Option<&str>,
+    ) -> Result<HashMap<String, String>, String> {
+        let conn = self.conn.lock().unwrap();
+
+        let mut stmt = conn
+            .prepare(
+                "SELECT variables FROM environments
+                 WHERE is_active = 1 AND (workspace_id IS NULL OR workspace_id = ?1)
+                 ORDER BY workspace_id IS NOT NULL ASC",
+            )
+            .map_err(|e| e.to_string())?;
+
+        let scopes: Vec<String> = stmt
+            .query_map([workspace_id], |row| row.get(0))
+            .map_err(|e| e.to_string())?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        let mut variables = HashMap::new();
+        for variables_json in scopes {
+            let scope: Vec<EnvironmentVariable> =
+                serde_json::from_str(&variables_json).unwrap_or_default();
+            for variable in scope.into_iter().filter(|v| v.enabled && !v.key.is_empty()) {
+                variables.insert(variable.key, variable.value);
+            }
+        }
+
+        Ok(variables)
+    }
+}
@@ -0,0 +1,98 @@
+use super::collection::{insert_collection, insert_item, Collection, CollectionItem};
+use super::environment::{insert_environment, Environment};
+use super::request::{insert_request, SavedRequest};
+use super::settings::{
+    write_workspace_setting, ProxySettings, TimeoutSettings, PROXY_KEY, TIMEOUTS_KEY,
+};
+use super::tab::{insert_tab, Tab};
+use super::workspace::{insert_workspace, Workspace};
+use super::Database;
+
+/// Everything an import adds to a workspace, written in one transaction so that an
+/// import that fails half way leaves nothing behind. Rows carry the id of the
+/// workspace they go into.
+#[derive(Debug, Default)]
+pub struct ImportBatch {
+    /// Created first when the import goes into a new workspace
+    pub workspace: Option<Workspace>,
+    pub timeouts: Option<TimeoutSettings>,
+    pub proxy: Option<ProxySettings>,
+    /// Placed after the workspace's collections, in this order
+    pub collections: Vec<(Collection, Vec<CollectionItem>)>,
+    /// An active one replaces the workspace's active environment
+    pub environments: Vec<Environment>,
+    pub history: Vec<SavedRequest>,
+    /// Placed after the workspace's tabs; an active one becomes the active tab
+    pub tabs: Vec<Tab>,
+}
+
+impl Database {
+    pub fn insert_import(&self, workspace_id: &str, batch: ImportBatch) -> Result<(), String> {
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction().map_err(|e| e.to_string())?;
+
+        if let Some(workspace) = &batch.workspace {
+            insert_workspace(&tx, workspace)?;
+        }
+        if let Some(timeouts) = &batch.timeouts {
+            let json = serde_json::to_string(timeouts).map_err(|e| e.to_string())?;
+            write_workspace_setting(&tx, workspace_id, TIMEOUTS_KEY, &json)?;
+        }
+        if let Some(proxy) = &batch.proxy {
+            let json = serde_json::to_string(proxy).map_err(|e| e.to_string())?;
+            write_workspace_setting(&tx, workspace_id, PROXY_KEY, &json)?;
+        }
+
+        let first: i32 = tx
+            .query_row(
+                "SELECT COALESCE(MAX(position), -1) + 1 FROM collections WHERE workspace_id = ?1",
+                [workspace_id],
+                |row| row.get(0),
+            )
+            .map_err(|e| e.to_string())?;
+        for (position, (mut collection, items)) in batch.collections.into_iter().enumerate() {
+            collection.position = first + position as i32;
+            insert_collection(&tx, &collection)?;
+            for item in &items {
+                insert_item(&tx, item)?;
+            }
+        }
+
+        if batch.environments.iter().any(|e| e.is_active) {
+            tx.execute(
+                "UPDATE environments SET is_active = 0 WHERE workspace_id = ?1",
+                [workspace_id],
+            )
+            .map_err(|e| e.to_string())?;
+        }
+        for environment in &batch.environments {
+            insert_environment(&tx, environment)?;
+        }
+
+        for request in &batch.history {
+            insert_request(&tx, request)?;
+        }
+
+        if batch.tabs.iter().any(|tab| tab.is_active) {
+            tx.execute(
+                "UPDATE tabs SET is_active = 0 WHERE workspace_id = ?1",
+                [workspace_id],
+            )
+            .map_err(|e| e.to_string())?;
+        }
+        let first: i32 = tx
+            .query_row(
+                "SELECT COALESCE(MAX(position), -1) + 1 FROM tabs WHERE workspace_id = ?1",
+                [workspace_id],
+                |row| row.get(0),
+            )
+            .map_err(|e| e.to_string())?;
+        for (position, mut tab) in batch.tabs.into_iter().enumerate() {
+            tab.position = first + position as i32;
+            insert_tab(&tx, &tab)?;
+        }
+
+        tx.commit().map_err(|e| e.to_string())?;
+        Ok(())
+    }
+}
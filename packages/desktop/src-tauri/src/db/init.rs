@@ -30,12 +30,15 @@ impl Database {
             "CREATE TABLE IF NOT EXISTS workspaces (
                 id TEXT PRIMARY KEY,
                 name TEXT NOT NULL,
+                directory TEXT,
                 created_at INTEGER NOT NULL,
                 updated_at INTEGER NOT NULL
             )",
             [],
         )?;
 
+        add_column_if_missing(&conn, "workspaces", "directory", "TEXT")?;
+
         // Requests/History table
         conn.execute(
             "CREATE TABLE IF NOT EXISTS requests (
@@ -54,6 +57,11 @@ impl Database {
                 response_body TEXT,
                 response_time_ms INTEGER,
                 response_size_bytes INTEGER,
+                response_timings TEXT,
+                response_redirects TEXT,
+                response_connection TEXT,
+                auth TEXT,
+                response_body_encoding TEXT,
                 created_at INTEGER NOT NULL,
                 updated_at INTEGER NOT NULL,
                 FOREIGN KEY (workspace_id) REFERENCES workspaces(id) ON DELETE CASCADE
@@ -61,6 +69,13 @@ impl Database {
             [],
         )?;
 
+        // Columns added after the initial release
+        add_column_if_missing(&conn, "requests", "response_timings", "TEXT")?;
+        add_column_if_missing(&conn, "requests", "response_redirects", "TEXT")?;
+        add_column_if_missing(&conn, "requests", "response_connection", "TEXT")?;
+        add_column_if_missing(&conn, "requests", "auth", "TEXT")?;
+        add_column_if_missing(&conn, "requests", "response_body_encoding", "TEXT")?;
+
         // Create index on workspace_id for faster lookups
         conn.execute(
             "CREATE INDEX IF NOT EXISTS idx_requests_workspace_id ON requests(workspace_id)",
@@ -137,6 +152,145 @@ impl Database {
             [],
         )?;
 
+        // Per-workspace settings, stored as JSON values
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS workspace_settings (
+                workspace_id TEXT NOT NULL,
+                key TEXT NOT NULL,
+                value TEXT NOT NULL,
+                PRIMARY KEY (workspace_id, key),
+                FOREIGN KEY (workspace_id) REFERENCES workspaces(id) ON DELETE CASCADE
+            )",
+            [],
+        )?;
+
+        // TLS settings per host (extra CAs, client certificates, verification toggle)
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS tls_host_settings (
+                id TEXT PRIMARY KEY,
+                host TEXT NOT NULL UNIQUE,
+                ca_paths TEXT NOT NULL,
+                client_cert TEXT,
+                insecure INTEGER NOT NULL DEFAULT 0,
+                created_at INTEGER NOT NULL,
+                updated_at INTEGER NOT NULL
+            )",
+            [],
+        )?;
+
+        // Cookie jar, one per workspace
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS cookies (
+                id TEXT PRIMARY KEY,
+                workspace_id TEXT NOT NULL,
+                name TEXT NOT NULL,
+                value TEXT NOT NULL,
+                domain TEXT NOT NULL,
+                path TEXT NOT NULL,
+                expires INTEGER,
+                secure INTEGER NOT NULL DEFAULT 0,
+                http_only INTEGER NOT NULL DEFAULT 0,
+                same_site TEXT,
+                host_only INTEGER NOT NULL DEFAULT 1,
+                created_at INTEGER NOT NULL,
+                updated_at INTEGER NOT NULL,
+                UNIQUE (workspace_id, domain, path, name),
+                FOREIGN KEY (workspace_id) REFERENCES workspaces(id) ON DELETE CASCADE
+            )",
+            [],
+        )?;
+
+        // OAuth 2.0 tokens, cached per workspace and token configuration
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS oauth_tokens (
+                id TEXT PRIMARY KEY,
+                workspace_id TEXT NOT NULL,
+                cache_key TEXT NOT NULL,
+                access_token TEXT NOT NULL,
+                token_type TEXT NOT NULL,
+                refresh_token TEXT,
+                scope TEXT,
+                expires_at INTEGER,
+                created_at INTEGER NOT NULL,
+                updated_at INTEGER NOT NULL,
+                UNIQUE (workspace_id, cache_key),
+                FOREIGN KEY (workspace_id) REFERENCES workspaces(id) ON DELETE CASCADE
+            )",
+            [],
+        )?;
+
+        // Environments table (workspace_id is NULL for global environments)
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS environments (
+                id TEXT PRIMARY KEY,
+                workspace_id TEXT,
+                name TEXT NOT NULL,
+                variables TEXT NOT NULL,
+                is_active INTEGER NOT NULL DEFAULT 0,
+                created_at INTEGER NOT NULL,
+                updated_at INTEGER NOT NULL,
+                FOREIGN KEY (workspace_id) REFERENCES workspaces(id) ON DELETE CASCADE
+            )",
+            [],
+        )?;
+
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_environments_workspace_id ON environments(workspace_id)",
+            [],
+        )?;
+
+        // Collections table (named, durable requests, separate from history)
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS collections (
+                id TEXT PRIMARY KEY,
+                workspace_id TEXT NOT NULL,
+                name TEXT NOT NULL,
+                position INTEGER NOT NULL,
+                file_path TEXT,
+                created_at INTEGER NOT NULL,
+                updated_at INTEGER NOT NULL,
+                FOREIGN KEY (workspace_id) REFERENCES workspaces(id) ON DELETE CASCADE
+            )",
+            [],
+        )?;
+
+        add_column_if_missing(&conn, "collections", "file_path", "TEXT")?;
+        // Names of the requests in the file when it was last read or written (JSON array)
+        add_column_if_missing(&conn, "collections", "file_requests", "TEXT")?;
+
+        // Collection items table (folders and requests, parent_id is NULL at the collection root)
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS collection_items (
+                id TEXT PRIMARY KEY,
+                collection_id TEXT NOT NULL,
+                parent_id TEXT,
+                kind TEXT NOT NULL,
+                name TEXT NOT NULL,
+                position INTEGER NOT NULL,
+                method TEXT,
+                url TEXT,
+                params TEXT,
+                headers TEXT,
+                body_type TEXT,
+                body_content TEXT,
+                settings TEXT,
+                auth TEXT,
+                created_at INTEGER NOT NULL,
+                updated_at INTEGER NOT NULL,
+                FOREIGN KEY (collection_id) REFERENCES collections(id) ON DELETE CASCADE,
+                FOREIGN KEY (parent_id) REFERENCES collection_items(id) ON DELETE CASCADE
+            )",
+            [],
+        )?;
+
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_collection_items_collection_id ON collection_items(collection_id)",
+            [],
+        )?;
+
+        add_column_if_missing(&conn, "collection_items", "settings", "TEXT")?;
+        add_column_if_missing(&conn, "collection_items", "auth", "TEXT")?;
+
         Ok(())
     }
 
@@ -168,3 +322,26 @@ impl Database {
         Ok(())
     }
 }
+
+// Adds a column to an existing table; CREATE TABLE IF NOT EXISTS leaves older databases untouched
+fn add_column_if_missing(
+    conn: &Connection,
+    table: &str,
+    column: &str,
+    definition: &str,
+) -> Result<()> {
+    let exists = conn
+        .prepare(&format!("PRAGMA table_info({})", table))?
+        .query_map([], |row| row.get::<_, String>(1))?
+        .filter_map(|r| r.ok())
+        .any(|name| name == column);
+
+    if !exists {
+        conn.execute(
+            &format!("ALTER TABLE {} ADD COLUMN {} {}", table, column, definition),
+            [],
+        )?;
+    }
+
+    Ok(())
+}
@@ -3,5 +3,11 @@ pub mod workspace;
 pub mod request;
 pub mod tab;
 pub mod settings;
+pub mod environment;
+pub mod collection;
+pub mod tls;
+pub mod cookie;
+pub mod oauth;
+pub mod import;
 
 pub use init::Database;
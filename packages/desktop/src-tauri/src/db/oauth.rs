@@ -0,0 +1,181 @@
+use rusqlite::{params, OptionalExtension, Row};
+use serde::{Deserialize, Serialize};
+
+use super::Database;
+
+const TOKEN_COLUMNS: &str = "id, workspace_id, cache_key, access_token, token_type, \
+                             refresh_token, scope, expires_at, created_at, updated_at";
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OAuth2GrantType {
+    #[default]
+    ClientCredentials,
+    Password,
+    /// Authorization code with PKCE, received on a temporary localhost listener
+    AuthorizationCode,
+}
+
+/// How the client authenticates to the token endpoint.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OAuth2ClientAuth {
+    /// `client_id` and `client_secret` in an `Authorization: Basic` header
+    #[default]
+    Basic,
+    /// `client_id` and `client_secret` as form parameters
+    Body,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OAuth2Config {
+    pub grant_type: OAuth2GrantType,
+    pub token_url: String,
+    pub client_id: String,
+    pub client_secret: String,
+    pub client_auth: OAuth2ClientAuth,
+    /// Space-separated scopes
+    pub scope: String,
+    /// Resource owner credentials, for the password grant
+    pub username: String,
+    pub password: String,
+    /// Authorization endpoint, for the authorization code grant
+    pub auth_url: String,
+    /// Port of the `http://127.0.0.1:<port>/callback` redirect listener; 0 picks a free one
+    pub redirect_port: u16,
+}
+
+/// A cached access token.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OAuthToken {
+    pub id: String,
+    pub workspace_id: String,
+    /// Identifies the configuration the token was obtained with
+    pub cache_key: String,
+    pub access_token: String,
+    pub token_type: String,
+    pub refresh_token: Option<String>,
+    pub scope: Option<String>,
+    /// Expiry in milliseconds since the epoch; `None` when the server did not say
+    pub expires_at: Option<i64>,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+/// Token fields as received from the token endpoint.
+#[derive(Debug, Clone)]
+pub struct OAuthTokenInput {
+    pub access_token: String,
+    pub token_type: String,
+    pub refresh_token: Option<String>,
+    pub scope: Option<String>,
+    pub expires_at: Option<i64>,
+}
+
+fn row_to_token(row: &Row) -> rusqlite::Result<OAuthToken> {
+    Ok(OAuthToken {
+        id: row.get(0)?,
+        workspace_id: row.get(1)?,
+        cache_key: row.get(2)?,
+        access_token: row.get(3)?,
+        token_type: row.get(4)?,
+        refresh_token: row.get(5)?,
+        scope: row.get(6)?,
+        expires_at: row.get(7)?,
+        created_at: row.get(8)?,
+        updated_at: row.get(9)?,
+    })
+}
+
+impl Database {
+    pub fn get_oauth_token(
+        &self,
+        workspace_id: &str,
+        cache_key: &str,
+    ) -> Result<Option<OAuthToken>, String> {
+        let conn = self.conn.lock().unwrap();
+
+        conn.query_row(
+            &format!(
+                "SELECT {} FROM oauth_tokens WHERE workspace_id = ?1 AND cache_key = ?2",
+                TOKEN_COLUMNS
+            ),
+            params![workspace_id, cache_key],
+            row_to_token,
+        )
+        .optional()
+        .map_err(|e| e.to_string())
+    }
+
+    /// Stores a token, replacing the one cached for the same configuration.
+    pub fn save_oauth_token(
+        &self,
+        workspace_id: &str,
+        cache_key: &str,
+        token: &OAuthTokenInput,
+    ) -> Result<OAuthToken, String> {
+        let conn = self.conn.lock().unwrap();
+
+        let id = uuid::Uuid::new_v4().to_string();
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_millis() as i64;
+
+        conn.execute(
+            &format!(
+                "INSERT INTO oauth_tokens ({})
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)
+                 ON CONFLICT (workspace_id, cache_key) DO UPDATE SET
+                    access_token = excluded.access_token,
+                    token_type = excluded.token_type,
+                    refresh_token = excluded.refresh_token,
+                    scope = excluded.scope,
+                    expires_at = excluded.expires_at,
+                    updated_at = excluded.updated_at",
+                TOKEN_COLUMNS
+            ),
+            params![
+                &id,
+                workspace_id,
+                cache_key,
+                &token.access_token,
+                &token.token_type,
+                &token.refresh_token,
+                &token.scope,
+                &token.expires_at,
+                &now,
+                &now,
+            ],
+        )
+        .map_err(|e| e.to_string())?;
+
+        drop(conn);
+        self.get_oauth_token(workspace_id, cache_key)?
+            .ok_or_else(|| "OAuth token not found".to_string())
+    }
+
+    pub fn delete_oauth_token(&self, workspace_id: &str, cache_key: &str) -> Result<(), String> {
+        let conn = self.conn.lock().unwrap();
+
+        conn.execute(
+            "DELETE FROM oauth_tokens WHERE workspace_id = ?1 AND cache_key = ?2",
+            params![workspace_id, cache_key],
+        )
+        .map_err(|e| e.to_string())?;
+
+        Ok(())
+    }
+
+    /// Forgets every cached token of a workspace. Returns how many were removed.
+    pub fn clear_oauth_tokens(&self, workspace_id: &str) -> Result<usize, String> {
+        let conn = self.conn.lock().unwrap();
+
+        conn.execute(
+            "DELETE FROM oauth_tokens WHERE workspace_id = ?1",
+            [workspace_id],
+        )
+        .map_err(|e| e.to_string())
+    }
+}
@@ -1,7 +1,19 @@
-use rusqlite::params;
+use rusqlite::{params, Connection, Row};
 use serde::{Deserialize, Serialize};
 
+use super::oauth::OAuth2Config;
+use super::tab::TabState;
 use super::Database;
+use crate::net::connection::ConnectionInfo;
+use crate::net::redirect::RedirectHop;
+use crate::net::timing::Timings;
+
+const REQUEST_COLUMNS: &str = "id, workspace_id, name, method, url, params, headers, body_type, \
+                               body_content, response_status, response_status_text, \
+                               response_headers, response_body, response_time_ms, \
+                               response_size_bytes, response_timings, response_redirects, \
+                               response_connection, auth, created_at, updated_at, \
+                               response_body_encoding";
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct KeyValue {
@@ -12,6 +24,226 @@ pub struct KeyValue {
     pub description: Option<String>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RedirectSettings {
+    pub follow: bool,
+    pub max_redirects: u32,
+    /// Keep the method and body on 301/302 instead of switching POST to GET
+    pub keep_method: bool,
+    /// Drop credentials when redirected to another origin
+    pub strip_auth_cross_origin: bool,
+}
+
+impl Default for RedirectSettings {
+    fn default() -> Self {
+        Self {
+            follow: true,
+            max_redirects: 10,
+            keep_method: false,
+            strip_auth_cross_origin: true,
+        }
+    }
+}
+
+/// Per-request timeouts in milliseconds; `None` falls back to the workspace setting.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TimeoutOverrides {
+    pub connect_ms: Option<u64>,
+    pub read_ms: Option<u64>,
+    pub total_ms: Option<u64>,
+}
+
+/// Where an API key is sent.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ApiKeyLocation {
+    #[default]
+    Header,
+    Query,
+}
+
+/// Credentials for a request. They are turned into headers or query parameters when
+/// the request is sent, so they never appear in the header list.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum RequestAuth {
+    #[default]
+    None,
+    Basic {
+        username: String,
+        password: String,
+    },
+    Bearer {
+        token: String,
+    },
+    ApiKey {
+        key: String,
+        value: String,
+        #[serde(default)]
+        location: ApiKeyLocation,
+    },
+    /// HTTP Digest; the first response's challenge is answered with a second request
+    Digest {
+        username: String,
+        password: String,
+    },
+    /// Bearer token obtained from an OAuth 2.0 token endpoint and cached per workspace
+    #[serde(rename = "oauth2")]
+    OAuth2(OAuth2Config),
+    /// AWS Signature Version 4, computed over the final request of every hop
+    #[serde(rename = "aws_sigv4")]
+    AwsSigV4(AwsCredentials),
+    /// HMAC or JWT signature over a template, placed in a header of every hop
+    Signature(SignatureConfig),
+}
+
+impl RequestAuth {
+    /// The same auth with its passwords, tokens and keys left empty, for files that are
+    /// shared. Values that only reference a variable, such as `{{token}}`, are kept.
+    pub fn without_secrets(&self) -> RequestAuth {
+        let mut auth = self.clone();
+        for secret in auth.secrets_mut() {
+            if !is_variable_reference(secret) {
+                secret.clear();
+            }
+        }
+        auth
+    }
+
+    /// Fills the secrets `without_secrets` left empty from `previous`, when it is the
+    /// same kind of auth.
+    pub fn with_secrets_from(mut self, previous: &RequestAuth) -> RequestAuth {
+        if std::mem::discriminant(&self) != std::mem::discriminant(previous) {
+            return self;
+        }
+        let mut previous = previous.clone();
+        for (secret, kept) in self.secrets_mut().into_iter().zip(previous.secrets_mut()) {
+            if secret.is_empty() {
+                *secret = std::mem::take(kept);
+            }
+        }
+        self
+    }
+
+    fn secrets_mut(&mut self) -> Vec<&mut String> {
+        match self {
+            RequestAuth::None => vec![],
+            RequestAuth::Basic { password, .. } | RequestAuth::Digest { password, .. } => {
+                vec![password]
+            }
+            RequestAuth::Bearer { token } => vec![token],
+            RequestAuth::ApiKey { value, .. } => vec![value],
+            RequestAuth::OAuth2(config) => vec![&mut config.client_secret, &mut config.password],
+            RequestAuth::AwsSigV4(credentials) => vec![
+                &mut credentials.secret_access_key,
+                &mut credentials.session_token,
+            ],
+            RequestAuth::Signature(config) => vec![&mut config.key],
+        }
+    }
+}
+
+// `{{name}}` and nothing else
+fn is_variable_reference(value: &str) -> bool {
+    value
+        .trim()
+        .strip_prefix("{{")
+        .and_then(|rest| rest.strip_suffix("}}"))
+        .is_some_and(|name| !name.contains("{{") && !name.contains("}}"))
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AwsCredentials {
+    pub access_key_id: String,
+    pub secret_access_key: String,
+    /// Only for temporary credentials
+    pub session_token: String,
+    pub region: String,
+    /// Signing name of the service, such as `execute-api` or `s3`
+    pub service: String,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SignatureAlgorithm {
+    #[default]
+    HmacSha256,
+    HmacSha512,
+    /// RSASSA-PKCS1-v1_5 with SHA-256
+    Rs256,
+    /// ECDSA on P-256 with SHA-256
+    Es256,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SignatureEncoding {
+    #[default]
+    Hex,
+    Base64,
+    /// A compact JWT whose claims are the rendered template
+    Jwt,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SignatureConfig {
+    pub algorithm: SignatureAlgorithm,
+    /// Shared secret for HMAC, PEM private key (PKCS#8, PKCS#1 or SEC1) otherwise
+    pub key: String,
+    /// String to sign, with `{method}`, `{path}`, `{query}`, `{target}`, `{host}`, `{url}`,
+    /// `{timestamp}`, `{timestamp_ms}`, `{nonce}`, `{body}` and `{body_sha256}` placeholders
+    pub template: String,
+    pub encoding: SignatureEncoding,
+    /// Header that receives the signature
+    pub header: String,
+    /// Prepended to the signature, such as `sha256=` or `Bearer `
+    pub prefix: String,
+    /// Header that carries `{timestamp}` for the server to check; empty to leave it out
+    pub timestamp_header: String,
+}
+
+/// Per-request options that are not part of the HTTP message itself.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RequestSettings {
+    pub redirect: RedirectSettings,
+    pub timeouts: TimeoutOverrides,
+    /// Open a new connection instead of reusing a pooled one
+    pub fresh_connection: bool,
+    /// Neither send cookies from the workspace jar nor store the ones set by the response
+    pub disable_cookie_jar: bool,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FormFieldType {
+    #[default]
+    Text,
+    File,
+}
+
+/// A multipart form-data field; for file fields `value` is the path of the file on disk.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FormField {
+    pub key: String,
+    pub value: String,
+    pub enabled: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(rename = "type", default)]
+    pub field_type: FormFieldType,
+    /// Overrides the file name sent for file fields (defaults to the name of the file on disk)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file_name: Option<String>,
+    /// Overrides the guessed MIME type of file fields
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content_type: Option<String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SavedRequest {
     pub id: String,
@@ -27,12 +259,69 @@ pub struct SavedRequest {
     pub response_status_text: Option<String>,
     pub response_headers: Option<Vec<KeyValue>>,
     pub response_body: Option<String>,
+    /// `base64` when the body is binary and `response_body` holds it base64-encoded
+    pub response_body_encoding: Option<String>,
     pub response_time_ms: Option<i64>,
     pub response_size_bytes: Option<i64>,
+    pub response_timings: Option<Timings>,
+    pub response_redirects: Option<Vec<RedirectHop>>,
+    pub response_connection: Option<ConnectionInfo>,
+    pub auth: Option<RequestAuth>,
     pub created_at: i64,
     pub updated_at: i64,
 }
 
+impl SavedRequest {
+    pub fn new(input: CreateRequestInput) -> Self {
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_millis() as i64;
+
+        // Generate name from method and URL
+        let name = format!("{} {}", input.method, extract_path(&input.url));
+
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            workspace_id: input.workspace_id,
+            name,
+            method: input.method,
+            url: input.url,
+            params: input.params,
+            headers: input.headers,
+            body_type: input.body_type,
+            body_content: input.body_content,
+            response_status: input.response_status,
+            response_status_text: input.response_status_text,
+            response_headers: input.response_headers,
+            response_body: input.response_body,
+            response_body_encoding: input.response_body_encoding,
+            response_time_ms: input.response_time_ms,
+            response_size_bytes: input.response_size_bytes,
+            response_timings: input.response_timings,
+            response_redirects: input.response_redirects,
+            response_connection: input.response_connection,
+            auth: input.auth,
+            created_at: input.created_at.unwrap_or(now),
+            updated_at: now,
+        }
+    }
+
+    pub fn to_tab_state(&self) -> TabState {
+        TabState {
+            method: self.method.clone(),
+            url: self.url.clone(),
+            params: self.params.clone().unwrap_or_default(),
+            headers: self.headers.clone().unwrap_or_default(),
+            body_type: self.body_type.clone().unwrap_or_else(|| "none".to_string()),
+            body_content: self.body_content.clone().unwrap_or_default(),
+            settings: Default::default(),
+            auth: self.auth.clone().unwrap_or_default(),
+            is_dirty: false,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CreateRequestInput {
     pub workspace_id: String,
@@ -46,8 +335,122 @@ pub struct CreateRequestInput {
     pub response_status_text: Option<String>,
     pub response_headers: Option<Vec<KeyValue>>,
     pub response_body: Option<String>,
+    #[serde(default)]
+    pub response_body_encoding: Option<String>,
     pub response_time_ms: Option<i64>,
     pub response_size_bytes: Option<i64>,
+    #[serde(default)]
+    pub response_timings: Option<Timings>,
+    #[serde(default)]
+    pub response_redirects: Option<Vec<RedirectHop>>,
+    #[serde(default)]
+    pub response_connection: Option<ConnectionInfo>,
+    #[serde(default)]
+    pub auth: Option<RequestAuth>,
+    /// When the request was sent, for entries imported from elsewhere; defaults to now
+    #[serde(default)]
+    pub created_at: Option<i64>,
+}
+
+pub(super) fn insert_request(conn: &Connection, request: &SavedRequest) -> Result<(), String> {
+    let params_json = request
+        .params
+        .as_ref()
+        .and_then(|p| serde_json::to_string(p).ok());
+    let headers_json = request
+        .headers
+        .as_ref()
+        .and_then(|h| serde_json::to_string(h).ok());
+    let response_headers_json = request
+        .response_headers
+        .as_ref()
+        .and_then(|h| serde_json::to_string(h).ok());
+    let response_timings_json = request
+        .response_timings
+        .as_ref()
+        .and_then(|t| serde_json::to_string(t).ok());
+    let response_redirects_json = request
+        .response_redirects
+        .as_ref()
+        .and_then(|r| serde_json::to_string(r).ok());
+    let response_connection_json = request
+        .response_connection
+        .as_ref()
+        .and_then(|c| serde_json::to_string(c).ok());
+    let auth_json = request
+        .auth
+        .as_ref()
+        .and_then(|a| serde_json::to_string(a).ok());
+
+    conn.execute(
+        &format!(
+            "INSERT INTO requests ({})
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19, ?20, ?21, ?22)",
+            REQUEST_COLUMNS
+        ),
+        params![
+            &request.id,
+            &request.workspace_id,
+            &request.name,
+            &request.method,
+            &request.url,
+            &params_json,
+            &headers_json,
+            &request.body_type,
+            &request.body_content,
+            &request.response_status,
+            &request.response_status_text,
+            &response_headers_json,
+            &request.response_body,
+            &request.response_time_ms,
+            &request.response_size_bytes,
+            &response_timings_json,
+            &response_redirects_json,
+            &response_connection_json,
+            &auth_json,
+            &request.created_at,
+            &request.updated_at,
+            &request.response_body_encoding,
+        ],
+    )
+    .map_err(|e| e.to_string())?;
+
+    Ok(())
+}
+
+fn row_to_request(row: &Row) -> rusqlite::Result<SavedRequest> {
+    let params_json: Option<String> = row.get(5)?;
+    let headers_json: Option<String> = row.get(6)?;
+    let response_headers_json: Option<String> = row.get(11)?;
+    let response_timings_json: Option<String> = row.get(15)?;
+    let response_redirects_json: Option<String> = row.get(16)?;
+    let response_connection_json: Option<String> = row.get(17)?;
+    let auth_json: Option<String> = row.get(18)?;
+
+    Ok(SavedRequest {
+        id: row.get(0)?,
+        workspace_id: row.get(1)?,
+        name: row.get(2)?,
+        method: row.get(3)?,
+        url: row.get(4)?,
+        params: params_json.and_then(|s| serde_json::from_str(&s).ok()),
+        headers: headers_json.and_then(|s| serde_json::from_str(&s).ok()),
+        body_type: row.get(7)?,
+        body_content: row.get(8)?,
+        response_status: row.get(9)?,
+        response_status_text: row.get(10)?,
+        response_headers: response_headers_json.and_then(|s| serde_json::from_str(&s).ok()),
+        response_body: row.get(12)?,
+        response_body_encoding: row.get(21)?,
+        response_time_ms: row.get(13)?,
+        response_size_bytes: row.get(14)?,
+        response_timings: response_timings_json.and_then(|s| serde_json::from_str(&s).ok()),
+        response_redirects: response_redirects_json.and_then(|s| serde_json::from_str(&s).ok()),
+        response_connection: response_connection_json.and_then(|s| serde_json::from_str(&s).ok()),
+        auth: auth_json.and_then(|s| serde_json::from_str(&s).ok()),
+        created_at: row.get(19)?,
+        updated_at: row.get(20)?,
+    })
 }
 
 impl Database {
@@ -63,44 +466,17 @@ impl Database {
         let offset = offset.unwrap_or(0);
 
         let mut stmt = conn
-            .prepare(
-                "SELECT id, workspace_id, name, method, url, params, headers, body_type, body_content,
-                        response_status, response_status_text, response_headers, response_body,
-                        response_time_ms, response_size_bytes, created_at, updated_at
-                 FROM requests 
-                 WHERE workspace_id = ?1 
-                 ORDER BY created_at DESC 
+            .prepare(&format!(
+                "SELECT {} FROM requests
+                 WHERE workspace_id = ?1
+                 ORDER BY created_at DESC
                  LIMIT ?2 OFFSET ?3",
-            )
+                REQUEST_COLUMNS
+            ))
             .map_err(|e| e.to_string())?;
 
         let requests = stmt
-            .query_map(params![workspace_id, limit, offset], |row| {
-                let params_json: Option<String> = row.get(5)?;
-                let headers_json: Option<String> = row.get(6)?;
-                let response_headers_json: Option<String> = row.get(11)?;
-
-                Ok(SavedRequest {
-                    id: row.get(0)?,
-                    workspace_id: row.get(1)?,
-                    name: row.get(2)?,
-                    method: row.get(3)?,
-                    url: row.get(4)?,
-                    params: params_json.and_then(|s| serde_json::from_str(&s).ok()),
-                    headers: headers_json.and_then(|s| serde_json::from_str(&s).ok()),
-                    body_type: row.get(7)?,
-                    body_content: row.get(8)?,
-                    response_status: row.get(9)?,
-                    response_status_text: row.get(10)?,
-                    response_headers: response_headers_json
-                        .and_then(|s| serde_json::from_str(&s).ok()),
-                    response_body: row.get(12)?,
-                    response_time_ms: row.get(13)?,
-                    response_size_bytes: row.get(14)?,
-                    created_at: row.get(15)?,
-                    updated_at: row.get(16)?,
-                })
-            })
+            .query_map(params![workspace_id, limit, offset], row_to_request)
             .map_err(|e| e.to_string())?
             .filter_map(|r| r.ok())
             .collect();
@@ -112,42 +488,13 @@ impl Database {
         let conn = self.conn.lock().unwrap();
 
         let mut stmt = conn
-            .prepare(
-                "SELECT id, workspace_id, name, method, url, params, headers, body_type, body_content,
-                        response_status, response_status_text, response_headers, response_body,
-                        response_time_ms, response_size_bytes, created_at, updated_at
-                 FROM requests WHERE id = ?1",
-            )
+            .prepare(&format!(
+                "SELECT {} FROM requests WHERE id = ?1",
+                REQUEST_COLUMNS
+            ))
             .map_err(|e| e.to_string())?;
 
-        let request = stmt
-            .query_row([id], |row| {
-                let params_json: Option<String> = row.get(5)?;
-                let headers_json: Option<String> = row.get(6)?;
-                let response_headers_json: Option<String> = row.get(11)?;
-
-                Ok(SavedRequest {
-                    id: row.get(0)?,
-                    workspace_id: row.get(1)?,
-                    name: row.get(2)?,
-                    method: row.get(3)?,
-                    url: row.get(4)?,
-                    params: params_json.and_then(|s| serde_json::from_str(&s).ok()),
-                    headers: headers_json.and_then(|s| serde_json::from_str(&s).ok()),
-                    body_type: row.get(7)?,
-                    body_content: row.get(8)?,
-                    response_status: row.get(9)?,
-                    response_status_text: row.get(10)?,
-                    response_headers: response_headers_json
-                        .and_then(|s| serde_json::from_str(&s).ok()),
-                    response_body: row.get(12)?,
-                    response_time_ms: row.get(13)?,
-                    response_size_bytes: row.get(14)?,
-                    created_at: row.get(15)?,
-                    updated_at: row.get(16)?,
-                })
-            })
-            .ok();
+        let request = stmt.query_row([id], row_to_request).ok();
 
         Ok(request)
     }
@@ -155,74 +502,10 @@ impl Database {
     pub fn create_request(&self, input: CreateRequestInput) -> Result<SavedRequest, String> {
         let conn = self.conn.lock().unwrap();
 
-        let id = uuid::Uuid::new_v4().to_string();
-        let now = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap()
-            .as_millis() as i64;
-
-        // Generate name from method and URL
-        let name = format!("{} {}", input.method, extract_path(&input.url));
-
-        let params_json = input
-            .params
-            .as_ref()
-            .and_then(|p| serde_json::to_string(p).ok());
-        let headers_json = input
-            .headers
-            .as_ref()
-            .and_then(|h| serde_json::to_string(h).ok());
-        let response_headers_json = input
-            .response_headers
-            .as_ref()
-            .and_then(|h| serde_json::to_string(h).ok());
-
-        conn.execute(
-            "INSERT INTO requests (id, workspace_id, name, method, url, params, headers, body_type, body_content,
-                                   response_status, response_status_text, response_headers, response_body,
-                                   response_time_ms, response_size_bytes, created_at, updated_at)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17)",
-            params![
-                &id,
-                &input.workspace_id,
-                &name,
-                &input.method,
-                &input.url,
-                &params_json,
-                &headers_json,
-                &input.body_type,
-                &input.body_content,
-                &input.response_status,
-                &input.response_status_text,
-                &response_headers_json,
-                &input.response_body,
-                &input.response_time_ms,
-                &input.response_size_bytes,
-                &now,
-                &now,
-            ],
-        )
-        .map_err(|e| e.to_string())?;
+        let request = SavedRequest::new(input);
+        insert_request(&conn, &request)?;
 
-        Ok(SavedRequest {
-            id,
-            workspace_id: input.workspace_id,
-            name,
-            method: input.method,
-            url: input.url,
-            params: input.params,
-            headers: input.headers,
-            body_type: input.body_type,
-            body_content: input.body_content,
-            response_status: input.response_status,
-            response_status_text: input.response_status_text,
-            response_headers: input.response_headers,
-            response_body: input.response_body,
-            response_time_ms: input.response_time_ms,
-            response_size_bytes: input.response_size_bytes,
-            created_at: now,
-            updated_at: now,
-        })
+        Ok(request)
     }
 
     pub fn search_requests(
@@ -236,48 +519,21 @@ impl Database {
 
         // Use FTS5 for search
         let mut stmt = conn
-            .prepare(
-                "SELECT r.id, r.workspace_id, r.name, r.method, r.url, r.params, r.headers, r.body_type, r.body_content,
-                        r.response_status, r.response_status_text, r.response_headers, r.response_body,
-                        r.response_time_ms, r.response_size_bytes, r.created_at, r.updated_at
-                 FROM requests r
-                 INNER JOIN requests_fts fts ON r.id = fts.id
-                 WHERE r.workspace_id = ?1 AND requests_fts MATCH ?2
-                 ORDER BY r.created_at DESC
+            .prepare(&format!(
+                "SELECT {} FROM requests
+                 WHERE workspace_id = ?1
+                   AND id IN (SELECT id FROM requests_fts WHERE requests_fts MATCH ?2)
+                 ORDER BY created_at DESC
                  LIMIT ?3",
-            )
+                REQUEST_COLUMNS
+            ))
             .map_err(|e| e.to_string())?;
 
         // Prepare query for FTS5 (add * for prefix matching)
         let fts_query = format!("{}*", query.replace('"', ""));
 
         let requests = stmt
-            .query_map(params![workspace_id, &fts_query, limit], |row| {
-                let params_json: Option<String> = row.get(5)?;
-                let headers_json: Option<String> = row.get(6)?;
-                let response_headers_json: Option<String> = row.get(11)?;
-
-                Ok(SavedRequest {
-                    id: row.get(0)?,
-                    workspace_id: row.get(1)?,
-                    name: row.get(2)?,
-                    method: row.get(3)?,
-                    url: row.get(4)?,
-                    params: params_json.and_then(|s| serde_json::from_str(&s).ok()),
-                    headers: headers_json.and_then(|s| serde_json::from_str(&s).ok()),
-                    body_type: row.get(7)?,
-                    body_content: row.get(8)?,
-                    response_status: row.get(9)?,
-                    response_status_text: row.get(10)?,
-                    response_headers: response_headers_json
-                        .and_then(|s| serde_json::from_str(&s).ok()),
-                    response_body: row.get(12)?,
-                    response_time_ms: row.get(13)?,
-                    response_size_bytes: row.get(14)?,
-                    created_at: row.get(15)?,
-                    updated_at: row.get(16)?,
-                })
-            })
+            .query_map(params![workspace_id, &fts_query, limit], row_to_request)
             .map_err(|e| e.to_string())?
             .filter_map(|r| r.ok())
             .collect();
@@ -316,7 +572,7 @@ impl Database {
     }
 }
 
-fn extract_path(url: &str) -> &str {
+pub(crate) fn extract_path(url: &str) -> &str {
     // Try to extract path from URL
     if let Some(idx) = url.find("://") {
         let after_protocol = &url[idx + 3..];
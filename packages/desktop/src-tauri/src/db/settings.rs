@@ -1,8 +1,12 @@
-use rusqlite::params;
+use rusqlite::{params, Connection};
 use serde::{Deserialize, Serialize};
 
+use super::request::TimeoutOverrides;
 use super::Database;
 
+pub(super) const TIMEOUTS_KEY: &str = "timeouts";
+pub(super) const PROXY_KEY: &str = "proxy";
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AppSettings {
     pub theme: String,
@@ -20,6 +24,85 @@ impl Default for AppSettings {
     }
 }
 
+/// Request timeouts in milliseconds; 0 disables a timeout.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TimeoutSettings {
+    /// Time allowed to establish a connection, including the TLS handshake
+    pub connect_ms: u64,
+    /// Longest pause allowed between reads once connected
+    pub read_ms: u64,
+    /// Time allowed for the whole exchange, redirects and body download included
+    pub total_ms: u64,
+}
+
+impl Default for TimeoutSettings {
+    fn default() -> Self {
+        Self {
+            connect_ms: 0,
+            read_ms: 0,
+            total_ms: 300_000, // 5 minutes
+        }
+    }
+}
+
+impl TimeoutSettings {
+    pub fn with_overrides(self, overrides: &TimeoutOverrides) -> Self {
+        Self {
+            connect_ms: overrides.connect_ms.unwrap_or(self.connect_ms),
+            read_ms: overrides.read_ms.unwrap_or(self.read_ms),
+            total_ms: overrides.total_ms.unwrap_or(self.total_ms),
+        }
+    }
+}
+
+/// Proxy configuration, saved globally and optionally overridden per workspace.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ProxySettings {
+    /// Route requests through the proxies below
+    pub enabled: bool,
+    pub http_url: String,
+    pub https_url: String,
+    pub socks_url: String,
+    pub username: String,
+    pub password: String,
+    /// Hosts, `.domain` suffixes, IPs or CIDR ranges that are reached directly
+    pub no_proxy: Vec<String>,
+    /// Fill in anything left blank from `HTTP_PROXY`, `HTTPS_PROXY`, `ALL_PROXY` and `NO_PROXY`
+    pub use_system: bool,
+}
+
+impl Default for ProxySettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            http_url: String::new(),
+            https_url: String::new(),
+            socks_url: String::new(),
+            username: String::new(),
+            password: String::new(),
+            no_proxy: Vec::new(),
+            use_system: true,
+        }
+    }
+}
+
+pub(super) fn write_workspace_setting(
+    conn: &Connection,
+    workspace_id: &str,
+    key: &str,
+    value: &str,
+) -> Result<(), String> {
+    conn.execute(
+        "INSERT OR REPLACE INTO workspace_settings (workspace_id, key, value) VALUES (?1, ?2, ?3)",
+        params![workspace_id, key, value],
+    )
+    .map_err(|e| e.to_string())?;
+
+    Ok(())
+}
+
 impl Database {
     pub fn get_setting(&self, key: &str) -> Result<Option<String>, String> {
         let conn = self.conn.lock().unwrap();
@@ -70,4 +153,119 @@ impl Database {
         }
         Ok(())
     }
+
+    pub fn get_workspace_setting(
+        &self,
+        workspace_id: &str,
+        key: &str,
+    ) -> Result<Option<String>, String> {
+        let conn = self.conn.lock().unwrap();
+
+        let value: Option<String> = conn
+            .query_row(
+                "SELECT value FROM workspace_settings WHERE workspace_id = ?1 AND key = ?2",
+                params![workspace_id, key],
+                |row| row.get(0),
+            )
+            .ok();
+
+        Ok(value)
+    }
+
+    pub fn set_workspace_setting(
+        &self,
+        workspace_id: &str,
+        key: &str,
+        value: &str,
+    ) -> Result<(), String> {
+        let conn = self.conn.lock().unwrap();
+        write_workspace_setting(&conn, workspace_id, key, value)
+    }
+
+    /// Timeouts for a workspace, or the defaults when none were saved.
+    pub fn get_timeout_settings(
+        &self,
+        workspace_id: Option<&str>,
+    ) -> Result<TimeoutSettings, String> {
+        let Some(workspace_id) = workspace_id else {
+            return Ok(TimeoutSettings::default());
+        };
+
+        let timeouts = self
+            .get_workspace_setting(workspace_id, TIMEOUTS_KEY)?
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default();
+
+        Ok(timeouts)
+    }
+
+    pub fn save_timeout_settings(
+        &self,
+        workspace_id: &str,
+        timeouts: &TimeoutSettings,
+    ) -> Result<(), String> {
+        let json = serde_json::to_string(timeouts).map_err(|e| e.to_string())?;
+        self.set_workspace_setting(workspace_id, TIMEOUTS_KEY, &json)
+    }
+
+    pub fn delete_workspace_setting(&self, workspace_id: &str, key: &str) -> Result<(), String> {
+        let conn = self.conn.lock().unwrap();
+
+        conn.execute(
+            "DELETE FROM workspace_settings WHERE workspace_id = ?1 AND key = ?2",
+            params![workspace_id, key],
+        )
+        .map_err(|e| e.to_string())?;
+
+        Ok(())
+    }
+
+    /// Proxy settings saved for a workspace, or the global ones when `workspace_id` is `None`.
+    pub fn get_proxy_settings(
+        &self,
+        workspace_id: Option<&str>,
+    ) -> Result<Option<ProxySettings>, String> {
+        let json = match workspace_id {
+            Some(workspace_id) => self.get_workspace_setting(workspace_id, PROXY_KEY)?,
+            None => self.get_setting(PROXY_KEY)?,
+        };
+
+        Ok(json.and_then(|json| serde_json::from_str(&json).ok()))
+    }
+
+    /// Saves proxy settings; `None` removes a workspace override or resets the global ones.
+    pub fn save_proxy_settings(
+        &self,
+        workspace_id: Option<&str>,
+        proxy: Option<&ProxySettings>,
+    ) -> Result<(), String> {
+        match (workspace_id, proxy) {
+            (Some(workspace_id), Some(proxy)) => {
+                let json = serde_json::to_string(proxy).map_err(|e| e.to_string())?;
+                self.set_workspace_setting(workspace_id, PROXY_KEY, &json)
+            }
+            (Some(workspace_id), None) => self.delete_workspace_setting(workspace_id, PROXY_KEY),
+            (None, proxy) => {
+                let json = serde_json::to_string(&proxy.cloned().unwrap_or_default())
+                    .map_err(|e| e.to_string())?;
+                self.set_setting(PROXY_KEY, &json)
+            }
+        }
+    }
+
+    /// The proxy settings a request in `workspace_id` is sent with.
+    pub fn get_effective_proxy_settings(
+        &self,
+        workspace_id: Option<&str>,
+    ) -> Result<ProxySettings, String> {
+        if let Some(proxy) = workspace_id
+            .map(|id| self.get_proxy_settings(Some(id)))
+            .transpose()?
+            .flatten()
+        {
+            return Ok(proxy);
+        }
+
+        Ok(self.get_proxy_settings(None)?.unwrap_or_default())
+    }
 }
@@ -1,4 +1,4 @@
-use rusqlite::params;
+use rusqlite::{params, Connection};
 use serde::{Deserialize, Serialize};
 
 use super::Database;
@@ -21,6 +21,10 @@ pub struct TabState {
     pub headers: Vec<super::request::KeyValue>,
     pub body_type: String,
     pub body_content: String,
+    #[serde(default)]
+    pub settings: super::request::RequestSettings,
+    #[serde(default)]
+    pub auth: super::request::RequestAuth,
     pub is_dirty: bool,
 }
 
@@ -33,11 +37,33 @@ impl Default for TabState {
             headers: Vec::new(),
             body_type: "none".to_string(),
             body_content: String::new(),
+            settings: Default::default(),
+            auth: Default::default(),
             is_dirty: false,
         }
     }
 }
 
+pub(super) fn insert_tab(conn: &Connection, tab: &Tab) -> Result<(), String> {
+    let state_json = serde_json::to_string(&tab.state).map_err(|e| e.to_string())?;
+
+    conn.execute(
+        "INSERT INTO tabs (id, workspace_id, request_id, state, position, is_active)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        params![
+            &tab.id,
+            &tab.workspace_id,
+            &tab.request_id,
+            &state_json,
+            &tab.position,
+            &(tab.is_active as i32),
+        ],
+    )
+    .map_err(|e| e.to_string())?;
+
+    Ok(())
+}
+
 impl Database {
     pub fn get_tabs_by_workspace(&self, workspace_id: &str) -> Result<Vec<Tab>, String> {
         let conn = self.conn.lock().unwrap();
@@ -73,6 +99,34 @@ impl Database {
         Ok(tabs)
     }
 
+    pub fn get_tab(&self, id: &str) -> Result<Option<Tab>, String> {
+        let conn = self.conn.lock().unwrap();
+
+        let tab = conn
+            .query_row(
+                "SELECT id, workspace_id, request_id, state, position, is_active
+                 FROM tabs WHERE id = ?1",
+                [id],
+                |row| {
+                    let state_json: String = row.get(3)?;
+                    let state: TabState = serde_json::from_str(&state_json).unwrap_or_default();
+                    let is_active: i32 = row.get(5)?;
+
+                    Ok(Tab {
+                        id: row.get(0)?,
+                        workspace_id: row.get(1)?,
+                        request_id: row.get(2)?,
+                        state,
+                        position: row.get(4)?,
+                        is_active: is_active == 1,
+                    })
+                },
+            )
+            .ok();
+
+        Ok(tab)
+    }
+
     pub fn create_tab(&self, workspace_id: &str, state: Option<TabState>) -> Result<Tab, String> {
         let conn = self.conn.lock().unwrap();
 
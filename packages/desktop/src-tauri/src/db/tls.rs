@@ -0,0 +1,203 @@
+use rusqlite::{params, Row};
+use serde::{Deserialize, Serialize};
+
+use super::Database;
+
+const TLS_COLUMNS: &str = "id, host, ca_paths, client_cert, insecure, created_at, updated_at";
+
+/// Client certificate presented for mutual TLS.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(tag = "format", rename_all = "lowercase")]
+pub enum ClientCertificate {
+    /// Certificate chain and private key in separate PEM files
+    Pem { cert_path: String, key_path: String },
+    /// Certificate chain and private key bundled in a `.p12`/`.pfx` file
+    Pkcs12 { path: String, password: String },
+}
+
+/// TLS options for one host. `host` is either an exact host name, optionally with a
+/// port (`api.internal:8443`), or a wildcard covering subdomains (`*.internal`).
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct TlsHostSettings {
+    pub id: String,
+    pub host: String,
+    /// PEM files with CA certificates trusted in addition to the system roots
+    pub ca_paths: Vec<String>,
+    pub client_cert: Option<ClientCertificate>,
+    /// Skip certificate verification entirely
+    pub insecure: bool,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TlsHostSettingsInput {
+    pub host: String,
+    #[serde(default)]
+    pub ca_paths: Vec<String>,
+    #[serde(default)]
+    pub client_cert: Option<ClientCertificate>,
+    #[serde(default)]
+    pub insecure: bool,
+}
+
+fn row_to_tls_settings(row: &Row) -> rusqlite::Result<TlsHostSettings> {
+    let ca_paths_json: String = row.get(2)?;
+    let client_cert_json: Option<String> = row.get(3)?;
+    let insecure: i32 = row.get(4)?;
+
+    Ok(TlsHostSettings {
+        id: row.get(0)?,
+        host: row.get(1)?,
+        ca_paths: serde_json::from_str(&ca_paths_json).unwrap_or_default(),
+        client_cert: client_cert_json.and_then(|s| serde_json::from_str(&s).ok()),
+        insecure: insecure == 1,
+        created_at: row.get(5)?,
+        updated_at: row.get(6)?,
+    })
+}
+
+fn validate_host(host: &str) -> Result<String, String> {
+    let host = host.trim().to_lowercase();
+    if host.is_empty() {
+        return Err("Host is required".to_string());
+    }
+    if host.contains('/') {
+        return Err(format!("Expected a host name, not a URL: {}", host));
+    }
+    Ok(host)
+}
+
+fn write_error(e: rusqlite::Error, host: &str) -> String {
+    match e.sqlite_error_code() {
+        Some(rusqlite::ErrorCode::ConstraintViolation) => {
+            format!("TLS settings for {} already exist", host)
+        }
+        _ => e.to_string(),
+    }
+}
+
+impl Database {
+    pub fn get_tls_host_settings(&self) -> Result<Vec<TlsHostSettings>, String> {
+        let conn = self.conn.lock().unwrap();
+
+        let mut stmt = conn
+            .prepare(&format!(
+                "SELECT {} FROM tls_host_settings ORDER BY host ASC",
+                TLS_COLUMNS
+            ))
+            .map_err(|e| e.to_string())?;
+
+        let settings = stmt
+            .query_map([], row_to_tls_settings)
+            .map_err(|e| e.to_string())?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(settings)
+    }
+
+    pub fn create_tls_host_settings(
+        &self,
+        input: TlsHostSettingsInput,
+    ) -> Result<TlsHostSettings, String> {
+        let conn = self.conn.lock().unwrap();
+
+        let id = uuid::Uuid::new_v4().to_string();
+        let host = validate_host(&input.host)?;
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_millis() as i64;
+        let ca_paths_json = serde_json::to_string(&input.ca_paths).map_err(|e| e.to_string())?;
+        let client_cert_json = input
+            .client_cert
+            .as_ref()
+            .and_then(|c| serde_json::to_string(c).ok());
+
+        conn.execute(
+            &format!(
+                "INSERT INTO tls_host_settings ({}) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                TLS_COLUMNS
+            ),
+            params![
+                &id,
+                &host,
+                &ca_paths_json,
+                &client_cert_json,
+                &(input.insecure as i32),
+                &now,
+                &now,
+            ],
+        )
+        .map_err(|e| write_error(e, &host))?;
+
+        Ok(TlsHostSettings {
+            id,
+            host,
+            ca_paths: input.ca_paths,
+            client_cert: input.client_cert,
+            insecure: input.insecure,
+            created_at: now,
+            updated_at: now,
+        })
+    }
+
+    pub fn update_tls_host_settings(
+        &self,
+        id: &str,
+        input: TlsHostSettingsInput,
+    ) -> Result<TlsHostSettings, String> {
+        let conn = self.conn.lock().unwrap();
+
+        let host = validate_host(&input.host)?;
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_millis() as i64;
+        let ca_paths_json = serde_json::to_string(&input.ca_paths).map_err(|e| e.to_string())?;
+        let client_cert_json = input
+            .client_cert
+            .as_ref()
+            .and_then(|c| serde_json::to_string(c).ok());
+
+        let changes = conn
+            .execute(
+                "UPDATE tls_host_settings
+                 SET host = ?1, ca_paths = ?2, client_cert = ?3, insecure = ?4, updated_at = ?5
+                 WHERE id = ?6",
+                params![
+                    &host,
+                    &ca_paths_json,
+                    &client_cert_json,
+                    &(input.insecure as i32),
+                    &now,
+                    id,
+                ],
+            )
+            .map_err(|e| write_error(e, &host))?;
+
+        if changes == 0 {
+            return Err("TLS settings not found".to_string());
+        }
+
+        conn.query_row(
+            &format!(
+                "SELECT {} FROM tls_host_settings WHERE id = ?1",
+                TLS_COLUMNS
+            ),
+            [id],
+            row_to_tls_settings,
+        )
+        .map_err(|e| e.to_string())
+    }
+
+    pub fn delete_tls_host_settings(&self, id: &str) -> Result<(), String> {
+        let conn = self.conn.lock().unwrap();
+
+        conn.execute("DELETE FROM tls_host_settings WHERE id = ?1", [id])
+            .map_err(|e| e.to_string())?;
+
+        Ok(())
+    }
+}
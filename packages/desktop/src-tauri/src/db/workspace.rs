@@ -1,35 +1,75 @@
-use rusqlite::params;
+use rusqlite::{params, Connection, Row};
 use serde::{Deserialize, Serialize};
 
+use super::collection::{replace_collections, Collection, CollectionItem};
+use super::environment::{replace_environments, Environment};
 use super::Database;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Workspace {
     pub id: String,
     pub name: String,
+    /// Directory the workspace's collections and environments are stored in, see `fs_store`
+    pub directory: Option<String>,
     pub created_at: i64,
     pub updated_at: i64,
 }
 
+fn row_to_workspace(row: &Row) -> rusqlite::Result<Workspace> {
+    Ok(Workspace {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        directory: row.get(2)?,
+        created_at: row.get(3)?,
+        updated_at: row.get(4)?,
+    })
+}
+
+impl Workspace {
+    pub fn new(name: &str) -> Self {
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_millis() as i64;
+
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            name: name.to_string(),
+            directory: None,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+}
+
+pub(super) fn insert_workspace(conn: &Connection, workspace: &Workspace) -> Result<(), String> {
+    conn.execute(
+        "INSERT INTO workspaces (id, name, created_at, updated_at) VALUES (?1, ?2, ?3, ?4)",
+        params![
+            &workspace.id,
+            &workspace.name,
+            &workspace.created_at,
+            &workspace.updated_at
+        ],
+    )
+    .map_err(|e| e.to_string())?;
+
+    Ok(())
+}
+
 impl Database {
     pub fn get_all_workspaces(&self) -> Result<Vec<Workspace>, String> {
         let conn = self.conn.lock().unwrap();
 
         let mut stmt = conn
             .prepare(
-                "SELECT id, name, created_at, updated_at FROM workspaces ORDER BY created_at ASC",
+                "SELECT id, name, directory, created_at, updated_at FROM workspaces
+                 ORDER BY created_at ASC",
             )
             .map_err(|e| e.to_string())?;
 
         let workspaces = stmt
-            .query_map([], |row| {
-                Ok(Workspace {
-                    id: row.get(0)?,
-                    name: row.get(1)?,
-                    created_at: row.get(2)?,
-                    updated_at: row.get(3)?,
-                })
-            })
+            .query_map([], row_to_workspace)
             .map_err(|e| e.to_string())?
             .filter_map(|r| r.ok())
             .collect();
@@ -41,19 +81,12 @@ impl Database {
         let conn = self.conn.lock().unwrap();
 
         let mut stmt = conn
-            .prepare("SELECT id, name, created_at, updated_at FROM workspaces WHERE id = ?1")
+            .prepare(
+                "SELECT id, name, directory, created_at, updated_at FROM workspaces WHERE id = ?1",
+            )
             .map_err(|e| e.to_string())?;
 
-        let workspace = stmt
-            .query_row([id], |row| {
-                Ok(Workspace {
-                    id: row.get(0)?,
-                    name: row.get(1)?,
-                    created_at: row.get(2)?,
-                    updated_at: row.get(3)?,
-                })
-            })
-            .ok();
+        let workspace = stmt.query_row([id], row_to_workspace).ok();
 
         Ok(workspace)
     }
@@ -61,27 +94,38 @@ impl Database {
     pub fn create_workspace(&self, name: &str) -> Result<Workspace, String> {
         let conn = self.conn.lock().unwrap();
 
-        let id = uuid::Uuid::new_v4().to_string();
+        let workspace = Workspace::new(name);
+        insert_workspace(&conn, &workspace)?;
+
+        Ok(workspace)
+    }
+
+    pub fn update_workspace(&self, id: &str, name: &str) -> Result<Workspace, String> {
+        let conn = self.conn.lock().unwrap();
+
         let now = std::time::SystemTime::now()
             .duration_since(std::time::UNIX_EPOCH)
             .unwrap()
             .as_millis() as i64;
 
         conn.execute(
-            "INSERT INTO workspaces (id, name, created_at, updated_at) VALUES (?1, ?2, ?3, ?4)",
-            params![&id, name, &now, &now],
+            "UPDATE workspaces SET name = ?1, updated_at = ?2 WHERE id = ?3",
+            params![name, &now, id],
         )
         .map_err(|e| e.to_string())?;
 
-        Ok(Workspace {
-            id,
-            name: name.to_string(),
-            created_at: now,
-            updated_at: now,
-        })
+        // Get the updated workspace
+        drop(conn);
+        self.get_workspace(id)?
+            .ok_or_else(|| "Workspace not found".to_string())
     }
 
-    pub fn update_workspace(&self, id: &str, name: &str) -> Result<Workspace, String> {
+    /// Binds a workspace to a directory, or unbinds it when `directory` is `None`.
+    pub fn set_workspace_directory(
+        &self,
+        id: &str,
+        directory: Option<&str>,
+    ) -> Result<Workspace, String> {
         let conn = self.conn.lock().unwrap();
 
         let now = std::time::SystemTime::now()
@@ -90,17 +134,34 @@ impl Database {
             .as_millis() as i64;
 
         conn.execute(
-            "UPDATE workspaces SET name = ?1, updated_at = ?2 WHERE id = ?3",
-            params![name, &now, id],
+            "UPDATE workspaces SET directory = ?1, updated_at = ?2 WHERE id = ?3",
+            params![directory, &now, id],
         )
         .map_err(|e| e.to_string())?;
 
-        // Get the updated workspace
         drop(conn);
         self.get_workspace(id)?
             .ok_or_else(|| "Workspace not found".to_string())
     }
 
+    /// Replaces the collections and environments of a workspace with those read from
+    /// its directory, keeping the given ids, in one transaction; see `fs_store`.
+    pub fn replace_collections_and_environments(
+        &self,
+        workspace_id: &str,
+        collections: &[(Collection, Vec<CollectionItem>)],
+        environments: &[Environment],
+    ) -> Result<(), String> {
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction().map_err(|e| e.to_string())?;
+
+        replace_collections(&tx, workspace_id, collections)?;
+        replace_environments(&tx, workspace_id, environments)?;
+
+        tx.commit().map_err(|e| e.to_string())?;
+        Ok(())
+    }
+
     pub fn delete_workspace(&self, id: &str) -> Result<(), String> {
         let conn = self.conn.lock().unwrap();
 
@@ -0,0 +1,315 @@
+use std::net::SocketAddr;
+
+use base64::Engine;
+use cookie::time::format_description::well_known::Rfc3339;
+use cookie::time::OffsetDateTime;
+use serde_json::{json, Map, Value};
+
+use crate::commands::http::parse_form_fields;
+use crate::db::request::{FormFieldType, KeyValue, SavedRequest};
+use crate::net::timing::Timings;
+
+/// Writes history entries as a HAR 1.2 archive, oldest first.
+///
+/// Auth settings are left out so the archive can be shared without credentials;
+/// only headers the request carried itself are written.
+pub fn export(requests: &[SavedRequest]) -> Result<String, String> {
+    let mut requests: Vec<&SavedRequest> = requests.iter().collect();
+    requests.sort_by_key(|request| request.created_at);
+
+    let archive = json!({
+        "log": {
+            "version": "1.2",
+            "creator": {
+                "name": "PingIT",
+                "version": env!("CARGO_PKG_VERSION"),
+            },
+            "entries": requests.into_iter().map(entry).collect::<Vec<_>>(),
+        }
+    });
+    serde_json::to_string_pretty(&archive).map_err(|e| e.to_string())
+}
+
+fn entry(saved: &SavedRequest) -> Value {
+    let http_version = saved
+        .response_connection
+        .as_ref()
+        .map_or("HTTP/1.1", |c| c.http_version.as_str());
+    let (timings, time) = timings(saved.response_timings.as_ref(), saved.response_time_ms);
+    let started =
+        OffsetDateTime::from_unix_timestamp_nanos(i128::from(saved.created_at) * 1_000_000)
+            .ok()
+            .and_then(|started| started.format(&Rfc3339).ok());
+
+    let mut entry = json!({
+        "startedDateTime": started,
+        "time": time,
+        "request": request(saved, http_version),
+        "response": response(saved, http_version),
+        "cache": {},
+        "timings": timings,
+    });
+
+    if let Some(address) = saved
+        .response_connection
+        .as_ref()
+        .and_then(|c| c.remote_address.as_deref())
+    {
+        // HAR keeps the IP and the port apart; addresses imported from HAR have no port
+        match address.parse::<SocketAddr>() {
+            Ok(address) => {
+                entry["serverIPAddress"] = json!(address.ip().to_string());
+                entry["connection"] = json!(address.port().to_string());
+            }
+            Err(_) => entry["serverIPAddress"] = json!(address.trim_matches(['[', ']'])),
+        }
+    }
+    if let Some(redirects) = saved.response_redirects.as_ref().filter(|r| !r.is_empty()) {
+        // Custom fields start with an underscore; read back by `import::har`
+        entry["_redirects"] = json!(redirects);
+    }
+
+    entry
+}
+
+fn request(saved: &SavedRequest, http_version: &str) -> Value {
+    let headers: Vec<&KeyValue> = saved
+        .headers
+        .iter()
+        .flatten()
+        .filter(|h| h.enabled)
+        .collect();
+    let cookies: Vec<Value> = headers
+        .iter()
+        .filter(|h| h.key.eq_ignore_ascii_case("cookie"))
+        .flat_map(|h| h.value.split(';'))
+        .filter_map(|pair| pair.trim().split_once('='))
+        .map(|(name, value)| json!({ "name": name, "value": value }))
+        .collect();
+    let query: Vec<Value> = saved
+        .params
+        .iter()
+        .flatten()
+        .filter(|p| p.enabled)
+        .map(|p| json!({ "name": p.key, "value": p.value }))
+        .collect();
+
+    let post_data = post_data(saved, &headers);
+    let body_size = post_data
+        .as_ref()
+        .and_then(|p| p["text"].as_str())
+        .map_or(0, |text| text.len() as i64);
+
+    let mut request = json!({
+        "method": saved.method,
+        "url": saved.url,
+        "httpVersion": http_version,
+        "cookies": cookies,
+        "headers": pairs(&headers),
+        "queryString": query,
+        "headersSize": -1,
+        "bodySize": body_size,
+    });
+    if let Some(post_data) = post_data {
+        request["postData"] = post_data;
+    }
+    request
+}
+
+fn post_data(saved: &SavedRequest, headers: &[&KeyValue]) -> Option<Value> {
+    let content = saved.body_content.as_deref().unwrap_or_default();
+    let content_type = header(headers, "content-type");
+
+    let post_data = match saved.body_type.as_deref().unwrap_or("none") {
+        "json" => json!({ "mimeType": "application/json", "text": content }),
+        "raw" => json!({
+            "mimeType": content_type.unwrap_or_default(),
+            "text": content,
+        }),
+        "x-www-form-urlencoded" => {
+            let params: Vec<Value> = url::form_urlencoded::parse(content.trim().as_bytes())
+                .map(|(name, value)| json!({ "name": name, "value": value }))
+                .collect();
+            json!({
+                "mimeType": "application/x-www-form-urlencoded",
+                "text": content,
+                "params": params,
+            })
+        }
+        "form-data" => {
+            let params: Vec<Value> = parse_form_fields(content)
+                .into_iter()
+                .filter(|f| f.enabled)
+                .map(|f| match f.field_type {
+                    FormFieldType::Text => json!({ "name": f.key, "value": f.value }),
+                    FormFieldType::File => {
+                        let file_name = f.file_name.clone().unwrap_or_else(|| file_name(&f.value));
+                        let mut param = json!({ "name": f.key, "fileName": file_name });
+                        if let Some(content_type) = f.content_type {
+                            param["contentType"] = json!(content_type);
+                        }
+                        param
+                    }
+                })
+                .collect();
+            json!({ "mimeType": "multipart/form-data", "text": "", "params": params })
+        }
+        "binary" => {
+            // Body content is the path of the file that was sent; embed it when still there
+            let path = content.trim();
+            let mime_type = content_type.map(str::to_string).unwrap_or_else(|| {
+                mime_guess::from_path(path)
+                    .first_or_octet_stream()
+                    .to_string()
+            });
+            let mut post_data = json!({ "mimeType": mime_type, "text": "", "comment": path });
+            if let Ok(bytes) = std::fs::read(path) {
+                match String::from_utf8(bytes) {
+                    Ok(text) => post_data["text"] = json!(text),
+                    Err(e) => {
+                        post_data["text"] =
+                            json!(base64::engine::general_purpose::STANDARD.encode(e.into_bytes()));
+                        post_data["encoding"] = json!("base64");
+                    }
+                }
+            }
+            post_data
+        }
+        _ => return None,
+    };
+    Some(post_data)
+}
+
+fn response(saved: &SavedRequest, http_version: &str) -> Value {
+    let Some(status) = saved.response_status else {
+        // No response was received; browsers write failed requests the same way
+        return json!({
+            "status": 0,
+            "statusText": "",
+            "httpVersion": "",
+            "cookies": [],
+            "headers": [],
+            "content": { "size": 0, "mimeType": "x-unknown" },
+            "redirectURL": "",
+            "headersSize": -1,
+            "bodySize": -1,
+        });
+    };
+
+    let headers: Vec<&KeyValue> = saved.response_headers.iter().flatten().collect();
+    let cookies: Vec<Value> = headers
+        .iter()
+        .filter(|h| h.key.eq_ignore_ascii_case("set-cookie"))
+        .filter_map(|h| cookie::Cookie::parse(h.value.as_str()).ok())
+        .map(|c| {
+            let mut cookie = Map::new();
+            cookie.insert("name".to_string(), json!(c.name()));
+            cookie.insert("value".to_string(), json!(c.value()));
+            if let Some(path) = c.path() {
+                cookie.insert("path".to_string(), json!(path));
+            }
+            if let Some(domain) = c.domain() {
+                cookie.insert("domain".to_string(), json!(domain));
+            }
+            if let Some(expires) = c
+                .expires_datetime()
+                .and_then(|expires| expires.format(&Rfc3339).ok())
+            {
+                cookie.insert("expires".to_string(), json!(expires));
+            }
+            if let Some(http_only) = c.http_only() {
+                cookie.insert("httpOnly".to_string(), json!(http_only));
+            }
+            if let Some(secure) = c.secure() {
+                cookie.insert("secure".to_string(), json!(secure));
+            }
+            Value::Object(cookie)
+        })
+        .collect();
+
+    let body = saved.response_body.as_deref().unwrap_or_default();
+    let size = saved.response_size_bytes.unwrap_or(body.len() as i64);
+    let mut content = json!({
+        "size": size,
+        "mimeType": header(&headers, "content-type").unwrap_or("x-unknown"),
+        "text": body,
+    });
+    if let Some(encoding) = saved.response_body_encoding.as_deref() {
+        content["encoding"] = json!(encoding);
+    }
+
+    let redirect_url = if (300..400).contains(&status) {
+        header(&headers, "location").unwrap_or_default()
+    } else {
+        ""
+    };
+
+    json!({
+        "status": status,
+        "statusText": saved.response_status_text.as_deref().unwrap_or_default(),
+        "httpVersion": http_version,
+        "cookies": cookies,
+        "headers": pairs(&headers),
+        "content": content,
+        "redirectURL": redirect_url,
+        "headersSize": -1,
+        "bodySize": size,
+    })
+}
+
+// HAR timings, and their total as the entry's `time`. TLS is part of `connect`,
+// and the whole time to the first byte is counted as `wait`.
+fn timings(timings: Option<&Timings>, response_time_ms: Option<i64>) -> (Value, f64) {
+    let Some(t) = timings else {
+        // Entries recorded before timings were measured only have the total
+        let total = response_time_ms.unwrap_or(0) as f64;
+        return (
+            json!({ "blocked": -1, "dns": -1, "connect": -1, "ssl": -1,
+                    "send": 0, "wait": total, "receive": 0 }),
+            total,
+        );
+    };
+
+    let connect = match (t.tcp_ms, t.tls_ms) {
+        (None, None) => None,
+        (tcp, tls) => Some(tcp.unwrap_or(0.0) + tls.unwrap_or(0.0)),
+    };
+    let measured = t.dns_ms.unwrap_or(0.0) + connect.unwrap_or(0.0) + t.ttfb_ms + t.download_ms;
+    // Whatever the phases do not cover (such as waiting for a pooled connection)
+    let blocked = Some(t.total_ms - measured).filter(|ms| *ms > 0.0);
+
+    let phase = |ms: Option<f64>| ms.map_or(json!(-1), |ms| json!(ms));
+    (
+        json!({
+            "blocked": phase(blocked),
+            "dns": phase(t.dns_ms),
+            "connect": phase(connect),
+            "ssl": phase(t.tls_ms),
+            "send": 0,
+            "wait": t.ttfb_ms,
+            "receive": t.download_ms,
+        }),
+        measured + blocked.unwrap_or(0.0),
+    )
+}
+
+fn pairs(headers: &[&KeyValue]) -> Vec<Value> {
+    headers
+        .iter()
+        .map(|h| json!({ "name": h.key, "value": h.value }))
+        .collect()
+}
+
+fn header<'a>(headers: &[&'a KeyValue], name: &str) -> Option<&'a str> {
+    headers
+        .iter()
+        .find(|h| h.key.eq_ignore_ascii_case(name))
+        .map(|h| h.value.as_str())
+}
+
+fn file_name(path: &str) -> String {
+    std::path::Path::new(path.trim())
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
@@ -0,0 +1,143 @@
+use serde::{Deserialize, Serialize};
+
+use crate::db::collection::{Collection, CollectionItem};
+use crate::db::environment::Environment;
+use crate::db::request::SavedRequest;
+use crate::db::settings::{ProxySettings, TimeoutSettings};
+use crate::db::tab::Tab;
+use crate::db::workspace::Workspace;
+use crate::db::Database;
+
+/// Identifies a workspace archive among other JSON files.
+pub const FORMAT: &str = "pingit-workspace";
+/// Bumped when the archive changes in a way older versions cannot read.
+pub const VERSION: u32 = 1;
+
+/// A workspace written out to be moved to another machine or shared.
+///
+/// Ids are those of the exporting database; `import::workspace` gives everything new ids.
+/// Passwords, tokens and keys in the auth of requests, tabs and history are left empty.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct WorkspaceArchive {
+    pub format: String,
+    pub version: u32,
+    pub exported_at: i64,
+    pub workspace: Workspace,
+    pub settings: ArchivedSettings,
+    pub collections: Vec<ArchivedCollection>,
+    /// Values of secret variables are left empty
+    pub environments: Vec<Environment>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub history: Option<Vec<SavedRequest>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tabs: Option<Vec<Tab>>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct ArchivedSettings {
+    #[serde(default)]
+    pub timeouts: Option<TimeoutSettings>,
+    /// The password is left empty
+    #[serde(default)]
+    pub proxy: Option<ProxySettings>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ArchivedCollection {
+    pub collection: Collection,
+    /// Folders and requests, with their parents given by `parent_id`
+    pub items: Vec<CollectionItem>,
+}
+
+/// What goes into an archive besides collections, environments and settings.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ExportOptions {
+    pub include_history: bool,
+    pub include_tabs: bool,
+}
+
+/// Writes a workspace as a versioned JSON archive, without secret values: neither those
+/// of secret variables and the proxy password nor the credentials of request auth.
+pub fn export(db: &Database, workspace_id: &str, options: ExportOptions) -> Result<String, String> {
+    let workspace = db
+        .get_workspace(workspace_id)?
+        .ok_or_else(|| "Workspace not found".to_string())?;
+
+    let proxy = db
+        .get_proxy_settings(Some(workspace_id))?
+        .map(|proxy| ProxySettings {
+            password: String::new(),
+            ..proxy
+        });
+
+    let mut collections = Vec::new();
+    for collection in db.get_collections(workspace_id)? {
+        let mut items = db.get_collection_items(&collection.id)?;
+        for auth in items.iter_mut().filter_map(|item| item.auth.as_mut()) {
+            *auth = auth.without_secrets();
+        }
+        collections.push(ArchivedCollection {
+            // A linked .http file only exists on this machine
+            collection: Collection {
+                file_path: None,
+                ..collection
+            },
+            items,
+        });
+    }
+
+    let mut environments = db.get_environments(Some(workspace_id))?;
+    for variable in environments
+        .iter_mut()
+        .flat_map(|e| e.variables.iter_mut())
+        .filter(|v| v.secret)
+    {
+        variable.value.clear();
+    }
+
+    let history = if options.include_history {
+        // A negative limit lifts SQLite's LIMIT
+        let mut history = db.get_requests_by_workspace(workspace_id, Some(-1), None)?;
+        for auth in history
+            .iter_mut()
+            .filter_map(|request| request.auth.as_mut())
+        {
+            *auth = auth.without_secrets();
+        }
+        Some(history)
+    } else {
+        None
+    };
+    let tabs = if options.include_tabs {
+        let mut tabs = db.get_tabs_by_workspace(workspace_id)?;
+        for tab in &mut tabs {
+            tab.state.auth = tab.state.auth.without_secrets();
+        }
+        Some(tabs)
+    } else {
+        None
+    };
+
+    let archive = WorkspaceArchive {
+        format: FORMAT.to_string(),
+        version: VERSION,
+        exported_at: std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_millis() as i64,
+        // A bound directory only exists on this machine
+        workspace: Workspace {
+            directory: None,
+            ..workspace
+        },
+        settings: ArchivedSettings {
+            timeouts: Some(db.get_timeout_settings(Some(workspace_id))?),
+            proxy,
+        },
+        collections,
+        environments,
+        history,
+        tabs,
+    };
+    serde_json::to_string_pretty(&archive).map_err(|e| e.to_string())
+}
@@ -0,0 +1,848 @@
+pub mod watcher;
+
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard};
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::db::collection::{Collection, CollectionItem, KIND_FOLDER, KIND_REQUEST};
+use crate::db::environment::{Environment, EnvironmentVariable};
+use crate::db::request::{KeyValue, RequestAuth, RequestSettings};
+use crate::db::workspace::Workspace;
+use crate::db::Database;
+
+const FORMAT: &str = "pingit-directory";
+const VERSION: u32 = 1;
+
+const WORKSPACE_FILE: &str = "pingit.json";
+const COLLECTIONS_DIR: &str = "collections";
+const ENVIRONMENTS_DIR: &str = "environments";
+const COLLECTION_FILE: &str = "collection.json";
+const FOLDER_FILE: &str = "folder.json";
+const REQUEST_SUFFIX: &str = ".request.json";
+
+// Saves and reloads are not interleaved, so a reload never reads a save half written
+// nor a change that is not saved yet
+static SYNC: Mutex<()> = Mutex::new(());
+
+/// Holds off reloads from when a command changes a workspace's collections or
+/// environments until it has saved them, so that a reload in between does not replace
+/// the change with the files as they were. Taken with `lock`.
+pub struct SyncGuard {
+    _guard: MutexGuard<'static, ()>,
+}
+
+/// Takes the lock `save` needs; hold it across the change to the database as well.
+pub fn lock() -> SyncGuard {
+    SyncGuard {
+        _guard: SYNC.lock().unwrap(),
+    }
+}
+
+/// Contents of the files of a workspace directory, by path relative to it.
+///
+/// The layout is:
+///
+/// ```text
+/// pingit.json                                  format version and collection order
+/// collections/<collection>/collection.json     name and order of the entries
+/// collections/<collection>/<name>.request.json one file per request
+/// collections/<collection>/<folder>/folder.json
+/// environments/<environment>.json              secret values are left empty
+/// ```
+///
+/// Secrets, both in environments and in the auth of requests, are only kept in the
+/// database.
+///
+/// Other files in the directory are neither read nor removed.
+type Files = BTreeMap<PathBuf, String>;
+
+// pingit.json
+#[derive(Debug, Serialize, Deserialize)]
+struct WorkspaceFile {
+    format: String,
+    version: u32,
+    /// Directory names of the collections, in order
+    #[serde(default)]
+    collections: Vec<String>,
+}
+
+// collection.json and folder.json
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct FolderFile {
+    #[serde(default)]
+    id: Option<String>,
+    name: String,
+    /// Names of the files and folders in the directory, in order; entries that are not
+    /// listed, such as those added by hand, come after them sorted by name
+    #[serde(default)]
+    order: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct RequestFile {
+    #[serde(default)]
+    id: Option<String>,
+    name: String,
+    method: String,
+    url: String,
+    #[serde(default)]
+    params: Vec<KeyValue>,
+    #[serde(default)]
+    headers: Vec<KeyValue>,
+    #[serde(default = "no_body")]
+    body_type: String,
+    /// Written as a list of lines when it has several, so that diffs show the lines changed
+    #[serde(default, with = "lines")]
+    body_content: String,
+    #[serde(default)]
+    settings: RequestSettings,
+    #[serde(default)]
+    auth: RequestAuth,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct EnvironmentFile {
+    #[serde(default)]
+    id: Option<String>,
+    name: String,
+    #[serde(default)]
+    variables: Vec<EnvironmentVariable>,
+}
+
+fn no_body() -> String {
+    "none".to_string()
+}
+
+mod lines {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(text: &str, serializer: S) -> Result<S::Ok, S::Error> {
+        if text.contains('\n') {
+            serializer.collect_seq(text.split('\n'))
+        } else {
+            serializer.serialize_str(text)
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<String, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Text {
+            Line(String),
+            Lines(Vec<String>),
+        }
+
+        Ok(match Text::deserialize(deserializer)? {
+            Text::Line(line) => line,
+            Text::Lines(lines) => lines.join("\n"),
+        })
+    }
+}
+
+/// Binds a workspace to a directory. A directory that already holds a workspace, such
+/// as a git checkout, replaces the workspace's collections and environments; otherwise
+/// they are written to it.
+pub fn bind(db: &Database, workspace_id: &str, dir: &Path) -> Result<Workspace, String> {
+    std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    let dir = std::fs::canonicalize(dir).map_err(|e| e.to_string())?;
+    let _sync = SYNC.lock().unwrap();
+
+    let current = read(&dir)?;
+    if current.contains_key(Path::new(WORKSPACE_FILE)) {
+        load(db, workspace_id, &current)?;
+    }
+    let workspace = db.set_workspace_directory(workspace_id, Some(&dir.to_string_lossy()))?;
+    write(&dir, &current, &render(db, workspace_id, &current)?)?;
+
+    Ok(workspace)
+}
+
+/// Writes a workspace's collections and environments to its directory, if it has one.
+///
+/// Called after every change to them, so the directory always matches the database.
+pub fn save(_sync: &SyncGuard, db: &Database, workspace_id: &str) -> Result<(), String> {
+    let Some(dir) = directory(db, workspace_id)? else {
+        return Ok(());
+    };
+
+    let current = read(&dir)?;
+    write(&dir, &current, &render(db, workspace_id, &current)?)
+}
+
+/// `save` for the workspace of a collection.
+pub fn save_collection(sync: &SyncGuard, db: &Database, collection_id: &str) -> Result<(), String> {
+    match db.get_collection(collection_id)? {
+        Some(collection) => save(sync, db, &collection.workspace_id),
+        None => Ok(()),
+    }
+}
+
+/// Reads a workspace's collections and environments back from its directory after its
+/// files changed. Returns whether anything was different from the database.
+///
+/// Nothing changes when a file cannot be read, as during a merge with conflicts.
+pub fn reload(db: &Database, workspace_id: &str) -> Result<bool, String> {
+    let Some(dir) = directory(db, workspace_id)? else {
+        return Ok(false);
+    };
+    let _sync = SYNC.lock().unwrap();
+
+    let current = read(&dir)?;
+    if render(db, workspace_id, &current)? == current {
+        return Ok(false);
+    }
+    if !current.contains_key(Path::new(WORKSPACE_FILE)) {
+        return Err(format!(
+            "{} is missing from {}",
+            WORKSPACE_FILE,
+            dir.display()
+        ));
+    }
+
+    load(db, workspace_id, &current)?;
+    // Files written by hand get their ids, and keys in the usual order
+    write(&dir, &current, &render(db, workspace_id, &current)?)?;
+
+    Ok(true)
+}
+
+fn directory(db: &Database, workspace_id: &str) -> Result<Option<PathBuf>, String> {
+    Ok(db
+        .get_workspace(workspace_id)?
+        .and_then(|workspace| workspace.directory)
+        .map(PathBuf::from))
+}
+
+// The files of the directory that belong to the workspace
+fn read(dir: &Path) -> Result<Files, String> {
+    let mut files = Files::new();
+
+    let read_file = |path: &Path, files: &mut Files| -> Result<(), String> {
+        let content = std::fs::read_to_string(dir.join(path))
+            .map_err(|e| format!("{}: {}", path.display(), e))?;
+        files.insert(path.to_path_buf(), content);
+        Ok(())
+    };
+
+    if dir.join(WORKSPACE_FILE).is_file() {
+        read_file(Path::new(WORKSPACE_FILE), &mut files)?;
+    }
+    for path in list(dir, Path::new(ENVIRONMENTS_DIR), false)? {
+        if path.extension().is_some_and(|ext| ext == "json") {
+            read_file(&path, &mut files)?;
+        }
+    }
+    for path in list(dir, Path::new(COLLECTIONS_DIR), true)? {
+        let file_name = path
+            .file_name()
+            .map(|name| name.to_string_lossy())
+            .unwrap_or_default();
+        // Components below `collections`, counting the file itself
+        let depth = path.components().count() - 1;
+        let ours = match file_name.as_ref() {
+            COLLECTION_FILE => depth == 2,
+            FOLDER_FILE => depth > 2,
+            name => name.ends_with(REQUEST_SUFFIX) && depth >= 2,
+        };
+        if ours {
+            read_file(&path, &mut files)?;
+        }
+    }
+
+    Ok(files)
+}
+
+// Files below `relative`, skipping hidden entries such as `.git`
+fn list(dir: &Path, relative: &Path, recursive: bool) -> Result<Vec<PathBuf>, String> {
+    let entries = match std::fs::read_dir(dir.join(relative)) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e.to_string()),
+    };
+
+    let mut paths = Vec::new();
+    for entry in entries {
+        let entry = entry.map_err(|e| e.to_string())?;
+        if entry.file_name().to_string_lossy().starts_with('.') {
+            continue;
+        }
+        let path = relative.join(entry.file_name());
+        let file_type = entry.file_type().map_err(|e| e.to_string())?;
+        if file_type.is_dir() {
+            if recursive {
+                paths.extend(list(dir, &path, true)?);
+            }
+        } else {
+            paths.push(path);
+        }
+    }
+    Ok(paths)
+}
+
+// Writes the files that changed and removes those no longer part of the workspace,
+// along with the folders they leave empty
+fn write(dir: &Path, current: &Files, files: &Files) -> Result<(), String> {
+    for (path, content) in files {
+        if current.get(path) == Some(content) {
+            continue;
+        }
+        let path = dir.join(path);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+        std::fs::write(&path, content).map_err(|e| e.to_string())?;
+    }
+
+    for path in current.keys().filter(|path| !files.contains_key(*path)) {
+        let path = dir.join(path);
+        match std::fs::remove_file(&path) {
+            Ok(()) => {}
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => return Err(e.to_string()),
+        }
+        let mut parent = path.parent();
+        while let Some(folder) = parent.filter(|folder| *folder != dir) {
+            // Fails, and stops, at the first folder that is not empty
+            if std::fs::remove_dir(folder).is_err() {
+                break;
+            }
+            parent = folder.parent();
+        }
+    }
+
+    Ok(())
+}
+
+// Pretty-printed with sorted keys, so that a file only changes where its request did
+fn to_file<T: Serialize>(value: &T) -> Result<String, String> {
+    let value = sort_keys(serde_json::to_value(value).map_err(|e| e.to_string())?);
+    let mut text = serde_json::to_string_pretty(&value).map_err(|e| e.to_string())?;
+    text.push('\n');
+    Ok(text)
+}
+
+fn sort_keys(value: Value) -> Value {
+    match value {
+        Value::Object(map) => {
+            let mut entries: Vec<(String, Value)> = map.into_iter().collect();
+            entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+            Value::Object(
+                entries
+                    .into_iter()
+                    .map(|(key, value)| (key, sort_keys(value)))
+                    .collect::<Map<_, _>>(),
+            )
+        }
+        Value::Array(values) => Value::Array(values.into_iter().map(sort_keys).collect()),
+        value => value,
+    }
+}
+
+fn parse<T: for<'de> Deserialize<'de>>(path: &Path, content: &str) -> Result<T, String> {
+    serde_json::from_str(content).map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+// Lowercase words joined by dashes, so that names are the same on every file system
+fn slug(name: &str, fallback: &str) -> String {
+    let mut slug = String::new();
+    for c in name.trim().chars() {
+        if c.is_alphanumeric() {
+            slug.extend(c.to_lowercase());
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    let slug = slug.trim_end_matches('-');
+    if slug.is_empty() {
+        fallback.to_string()
+    } else {
+        slug.to_string()
+    }
+}
+
+// The files a workspace is written as. Items keep the file names they already have,
+// even when renamed or moved, so that history in git follows them.
+fn render(db: &Database, workspace_id: &str, current: &Files) -> Result<Files, String> {
+    let mut renderer = Renderer {
+        paths: paths_by_id(current),
+        files: Files::new(),
+    };
+
+    let mut taken = HashSet::new();
+    let mut order = Vec::new();
+    for collection in db.get_collections(workspace_id)? {
+        let name = renderer.entry_name(&collection.id, &collection.name, "", &mut taken);
+        let dir = Path::new(COLLECTIONS_DIR).join(&name);
+        let items = db.get_collection_items(&collection.id)?;
+        let entries = renderer.children(&dir, &items, None)?;
+        renderer.add(
+            dir.join(COLLECTION_FILE),
+            &FolderFile {
+                id: Some(collection.id.clone()),
+                name: collection.name.clone(),
+                order: entries,
+            },
+        )?;
+        order.push(name);
+    }
+    renderer.add(
+        PathBuf::from(WORKSPACE_FILE),
+        &WorkspaceFile {
+            format: FORMAT.to_string(),
+            version: VERSION,
+            collections: order,
+        },
+    )?;
+
+    let mut taken = HashSet::new();
+    for environment in db.get_environments(Some(workspace_id))? {
+        let name = renderer.entry_name(&environment.id, &environment.name, ".json", &mut taken);
+        let variables = environment
+            .variables
+            .into_iter()
+            .map(|variable| EnvironmentVariable {
+                value: if variable.secret {
+                    String::new()
+                } else {
+                    variable.value
+                },
+                ..variable
+            })
+            .collect();
+        renderer.add(
+            Path::new(ENVIRONMENTS_DIR).join(name),
+            &EnvironmentFile {
+                id: Some(environment.id),
+                name: environment.name,
+                variables,
+            },
+        )?;
+    }
+
+    Ok(renderer.files)
+}
+
+struct Renderer {
+    /// Where each collection, folder, request and environment is on disk now
+    paths: HashMap<String, PathBuf>,
+    files: Files,
+}
+
+impl Renderer {
+    fn add<T: Serialize>(&mut self, path: PathBuf, value: &T) -> Result<(), String> {
+        self.files.insert(path, to_file(value)?);
+        Ok(())
+    }
+
+    // Writes the items under `parent_id`, returning their entry names in order
+    fn children(
+        &mut self,
+        dir: &Path,
+        items: &[CollectionItem],
+        parent_id: Option<&str>,
+    ) -> Result<Vec<String>, String> {
+        let mut taken = HashSet::from([COLLECTION_FILE.to_string(), FOLDER_FILE.to_string()]);
+        let mut order = Vec::new();
+
+        // Items are sorted by position
+        for item in items.iter().filter(|i| i.parent_id.as_deref() == parent_id) {
+            if item.kind == KIND_FOLDER {
+                let name = self.entry_name(&item.id, &item.name, "", &mut taken);
+                let folder = dir.join(&name);
+                let entries = self.children(&folder, items, Some(&item.id))?;
+                self.add(
+                    folder.join(FOLDER_FILE),
+                    &FolderFile {
+                        id: Some(item.id.clone()),
+                        name: item.name.clone(),
+                        order: entries,
+                    },
+                )?;
+                order.push(name);
+            } else {
+                let name = self.entry_name(&item.id, &item.name, REQUEST_SUFFIX, &mut taken);
+                let state = item.to_tab_state();
+                self.add(
+                    dir.join(&name),
+                    &RequestFile {
+                        id: Some(item.id.clone()),
+                        name: item.name.clone(),
+                        method: state.method,
+                        url: state.url,
+                        params: state.params,
+                        headers: state.headers,
+                        body_type: state.body_type,
+                        body_content: state.body_content,
+                        settings: state.settings,
+                        auth: state.auth.without_secrets(),
+                    },
+                )?;
+                order.push(name);
+            }
+        }
+
+        Ok(order)
+    }
+
+    // The file or folder name for `id` among the entries already `taken` in its directory
+    fn entry_name(
+        &self,
+        id: &str,
+        name: &str,
+        suffix: &str,
+        taken: &mut HashSet<String>,
+    ) -> String {
+        let existing = self
+            .paths
+            .get(id)
+            .map(|path| file_name(path))
+            .filter(|existing| existing.ends_with(suffix));
+
+        let candidate = match existing {
+            Some(existing) if !taken.contains(&existing.to_lowercase()) => existing,
+            _ => {
+                let base = slug(
+                    name,
+                    if suffix.is_empty() {
+                        "folder"
+                    } else {
+                        "request"
+                    },
+                );
+                let mut candidate = format!("{}{}", base, suffix);
+                let mut n = 2;
+                while taken.contains(&candidate.to_lowercase()) {
+                    candidate = format!("{}-{}{}", base, n, suffix);
+                    n += 1;
+                }
+                candidate
+            }
+        };
+        taken.insert(candidate.to_lowercase());
+        candidate
+    }
+}
+
+// Ids found in the files, with the path of the file or folder that holds each
+fn paths_by_id(files: &Files) -> HashMap<String, PathBuf> {
+    #[derive(Deserialize)]
+    struct Id {
+        id: Option<String>,
+    }
+
+    let mut paths = HashMap::new();
+    for (path, content) in files {
+        let Some(id) = serde_json::from_str::<Id>(content).ok().and_then(|f| f.id) else {
+            continue;
+        };
+        let name = file_name(path);
+        let path = if name == COLLECTION_FILE || name == FOLDER_FILE {
+            path.parent().map(Path::to_path_buf).unwrap_or_default()
+        } else {
+            path.clone()
+        };
+        paths.entry(id).or_insert(path);
+    }
+    paths
+}
+
+// Replaces the workspace's collections and environments with those of the files
+fn load(db: &Database, workspace_id: &str, files: &Files) -> Result<(), String> {
+    let workspace: WorkspaceFile = parse(
+        Path::new(WORKSPACE_FILE),
+        files
+            .get(Path::new(WORKSPACE_FILE))
+            .map_or("", String::as_str),
+    )?;
+    if workspace.format != FORMAT {
+        return Err(format!("{} is not a PingIT workspace", WORKSPACE_FILE));
+    }
+    if workspace.version > VERSION {
+        return Err(format!(
+            "The workspace was written by a newer version of PingIT (format version {})",
+            workspace.version
+        ));
+    }
+
+    let mut loader = Loader {
+        files,
+        ids: HashSet::new(),
+        foreign: foreign_ids(db, workspace_id)?,
+        now: std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_millis() as i64,
+    };
+
+    let existing: HashMap<String, Collection> = db
+        .get_collections(workspace_id)?
+        .into_iter()
+        .map(|collection| (collection.id.clone(), collection))
+        .collect();
+    let mut existing_items = HashMap::new();
+    for id in existing.keys() {
+        for item in db.get_collection_items(id)? {
+            existing_items.insert(item.id.clone(), item);
+        }
+    }
+
+    let collections_dir = Path::new(COLLECTIONS_DIR);
+    let mut collections = Vec::new();
+    for (position, name) in loader
+        .entries(collections_dir, &workspace.collections)
+        .into_iter()
+        .enumerate()
+    {
+        let dir = collections_dir.join(&name);
+        let file = loader.folder(&dir.join(COLLECTION_FILE), &name)?;
+        let id = loader.id(file.id);
+        let previous = existing.get(&id);
+
+        let mut items = Vec::new();
+        loader.items(&dir, &id, None, &file.order, &existing_items, &mut items)?;
+        collections.push((
+            Collection {
+                workspace_id: workspace_id.to_string(),
+                name: file.name.clone(),
+                position: position as i32,
+                // A link to a .http file only exists in this database
+                file_path: previous.and_then(|c| c.file_path.clone()),
+                created_at: previous.map_or(loader.now, |c| c.created_at),
+                updated_at: previous
+                    .filter(|c| c.name == file.name)
+                    .map_or(loader.now, |c| c.updated_at),
+                id,
+            },
+            items,
+        ));
+    }
+
+    let existing: HashMap<String, Environment> = db
+        .get_environments(Some(workspace_id))?
+        .into_iter()
+        .map(|environment| (environment.id.clone(), environment))
+        .collect();
+    let mut environments = Vec::new();
+    for (path, content) in files
+        .iter()
+        .filter(|(path, _)| path.starts_with(ENVIRONMENTS_DIR))
+    {
+        let file: EnvironmentFile = parse(path, content)?;
+        let id = loader.id(file.id);
+        let previous = existing.get(&id);
+
+        // Secret values are only kept in the database
+        let variables: Vec<EnvironmentVariable> = file
+            .variables
+            .into_iter()
+            .map(|variable| {
+                let kept = previous
+                    .filter(|_| variable.secret && variable.value.is_empty())
+                    .and_then(|e| e.variables.iter().find(|v| v.key == variable.key))
+                    .map(|v| v.value.clone());
+                EnvironmentVariable {
+                    value: kept.unwrap_or(variable.value),
+                    ..variable
+                }
+            })
+            .collect();
+        let unchanged = previous.is_some_and(|e| {
+            e.name == file.name
+                && serde_json::to_value(&e.variables).ok() == serde_json::to_value(&variables).ok()
+        });
+
+        environments.push(Environment {
+            workspace_id: Some(workspace_id.to_string()),
+            name: file.name,
+            variables,
+            is_active: previous.is_some_and(|e| e.is_active),
+            created_at: previous.map_or(loader.now, |e| e.created_at),
+            updated_at: match previous {
+                Some(e) if unchanged => e.updated_at,
+                _ => loader.now,
+            },
+            id,
+        });
+    }
+
+    db.replace_collections_and_environments(workspace_id, &collections, &environments)
+}
+
+// Ids used by other workspaces, which a copied directory may repeat
+fn foreign_ids(db: &Database, workspace_id: &str) -> Result<HashSet<String>, String> {
+    let mut ids = HashSet::new();
+    for workspace in db.get_all_workspaces()? {
+        if workspace.id == workspace_id {
+            continue;
+        }
+        for collection in db.get_collections(&workspace.id)? {
+            ids.extend(
+                db.get_collection_items(&collection.id)?
+                    .into_iter()
+                    .map(|item| item.id),
+            );
+            ids.insert(collection.id);
+        }
+        ids.extend(
+            db.get_environments(Some(&workspace.id))?
+                .into_iter()
+                .map(|environment| environment.id),
+        );
+    }
+    Ok(ids)
+}
+
+struct Loader<'a> {
+    files: &'a Files,
+    /// Ids given out so far
+    ids: HashSet<String>,
+    foreign: HashSet<String>,
+    now: i64,
+}
+
+impl Loader<'_> {
+    // The id in a file, or a new one when it has none or repeats another
+    fn id(&mut self, id: Option<String>) -> String {
+        let id = id
+            .filter(|id| !id.is_empty() && !self.ids.contains(id) && !self.foreign.contains(id))
+            .unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+        self.ids.insert(id.clone());
+        id
+    }
+
+    // collection.json or folder.json; a folder created by hand may have none
+    fn folder(&self, path: &Path, name: &str) -> Result<FolderFile, String> {
+        match self.files.get(path) {
+            Some(content) => parse(path, content),
+            None => Ok(FolderFile {
+                name: name.to_string(),
+                ..Default::default()
+            }),
+        }
+    }
+
+    // Names of the requests and folders in `dir`, listed ones first
+    fn entries(&self, dir: &Path, order: &[String]) -> Vec<String> {
+        let mut names = BTreeSet::new();
+        for path in self.files.keys() {
+            let Ok(rest) = path.strip_prefix(dir) else {
+                continue;
+            };
+            let mut components = rest.components();
+            let first = components
+                .next()
+                .map(|c| c.as_os_str().to_string_lossy().into_owned());
+            match (first, components.next()) {
+                // A folder, holding files further down
+                (Some(name), Some(_)) => {
+                    names.insert(name);
+                }
+                (Some(name), None) if name.ends_with(REQUEST_SUFFIX) => {
+                    names.insert(name);
+                }
+                _ => {}
+            }
+        }
+
+        let mut entries: Vec<String> = order
+            .iter()
+            .filter(|name| names.remove(*name))
+            .cloned()
+            .collect();
+        entries.extend(names);
+        entries
+    }
+
+    fn items(
+        &mut self,
+        dir: &Path,
+        collection_id: &str,
+        parent_id: Option<&str>,
+        order: &[String],
+        existing: &HashMap<String, CollectionItem>,
+        items: &mut Vec<CollectionItem>,
+    ) -> Result<(), String> {
+        for (position, name) in self.entries(dir, order).into_iter().enumerate() {
+            let path = dir.join(&name);
+
+            let (item, children) = if name.ends_with(REQUEST_SUFFIX) {
+                let file: RequestFile = parse(&path, &self.files[&path])?;
+                let item = CollectionItem {
+                    id: self.id(file.id),
+                    collection_id: collection_id.to_string(),
+                    parent_id: parent_id.map(str::to_string),
+                    kind: KIND_REQUEST.to_string(),
+                    name: file.name,
+                    position: position as i32,
+                    method: Some(file.method),
+                    url: Some(file.url),
+                    params: Some(file.params),
+                    headers: Some(file.headers),
+                    body_type: Some(file.body_type),
+                    body_content: Some(file.body_content),
+                    settings: Some(file.settings),
+                    auth: Some(file.auth),
+                    created_at: self.now,
+                    updated_at: self.now,
+                };
+                (item, None)
+            } else {
+                let file = self.folder(&path.join(FOLDER_FILE), &name)?;
+                let item = CollectionItem {
+                    id: self.id(file.id),
+                    collection_id: collection_id.to_string(),
+                    parent_id: parent_id.map(str::to_string),
+                    kind: KIND_FOLDER.to_string(),
+                    name: file.name,
+                    position: position as i32,
+                    method: None,
+                    url: None,
+                    params: None,
+                    headers: None,
+                    body_type: None,
+                    body_content: None,
+                    settings: None,
+                    auth: None,
+                    created_at: self.now,
+                    updated_at: self.now,
+                };
+                (item, Some(file.order))
+            };
+
+            let mut item = item;
+            if let Some(previous) = existing.get(&item.id) {
+                if let (Some(auth), Some(kept)) = (&mut item.auth, &previous.auth) {
+                    *auth = std::mem::take(auth).with_secrets_from(kept);
+                }
+                item.created_at = previous.created_at;
+                if same_content(previous, &item) {
+                    item.updated_at = previous.updated_at;
+                }
+            }
+            let id = item.id.clone();
+            items.push(item);
+
+            if let Some(order) = children {
+                self.items(&path, collection_id, Some(&id), &order, existing, items)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+// Whether an item is unchanged, leaving aside where it is
+fn same_content(a: &CollectionItem, b: &CollectionItem) -> bool {
+    let content = |item: &CollectionItem| {
+        (item.kind == KIND_REQUEST).then(|| serde_json::to_value(item.to_tab_state()).ok())
+    };
+    a.kind == b.kind && a.name == b.name && content(a) == content(b)
+}
@@ -0,0 +1,119 @@
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::db::Database;
+
+/// Emitted after a workspace was reloaded because its files changed.
+pub const CHANGED_EVENT: &str = "workspace-directory-changed";
+/// Emitted when the files of a workspace could not be reloaded; it keeps its last state.
+pub const ERROR_EVENT: &str = "workspace-directory-error";
+
+// Changes this close together are reloaded at once, so that a `git pull` touching
+// many files is read when it is done
+const DEBOUNCE: Duration = Duration::from_millis(300);
+
+lazy_static::lazy_static! {
+    static ref WATCHERS: Mutex<HashMap<String, RecommendedWatcher>> = Mutex::new(HashMap::new());
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct DirectoryEvent {
+    workspace_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+/// Reloads the workspaces bound to a directory, for changes made while the app was
+/// closed, and watches them from then on.
+pub fn start(app: &AppHandle) {
+    let db = app.state::<Database>();
+    let Ok(workspaces) = db.get_all_workspaces() else {
+        return;
+    };
+
+    for workspace in workspaces {
+        let Some(directory) = workspace.directory else {
+            continue;
+        };
+        // A directory that cannot be read now, such as one on a drive that is not
+        // mounted, keeps the workspace as it was
+        let _ = super::reload(&db, &workspace.id);
+        let _ = watch(app, &workspace.id, Path::new(&directory));
+    }
+}
+
+/// Reloads a workspace whenever the files in its directory change, replacing any
+/// watcher it had.
+pub fn watch(app: &AppHandle, workspace_id: &str, dir: &Path) -> Result<(), String> {
+    let (tx, rx) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(tx).map_err(|e| e.to_string())?;
+    watcher
+        .watch(dir, RecursiveMode::Recursive)
+        .map_err(|e| e.to_string())?;
+    // Dropping a replaced watcher closes its channel, which ends its thread
+    WATCHERS
+        .lock()
+        .unwrap()
+        .insert(workspace_id.to_string(), watcher);
+
+    let app = app.clone();
+    let workspace_id = workspace_id.to_string();
+    std::thread::spawn(move || {
+        while let Ok(event) = rx.recv() {
+            let mut relevant = is_relevant(&event);
+            loop {
+                match rx.recv_timeout(DEBOUNCE) {
+                    Ok(event) => relevant |= is_relevant(&event),
+                    Err(RecvTimeoutError::Timeout) => break,
+                    Err(RecvTimeoutError::Disconnected) => return,
+                }
+            }
+            if !relevant {
+                continue;
+            }
+
+            // Saves from the app itself leave the files as the database has them
+            let (name, error) = match super::reload(&app.state::<Database>(), &workspace_id) {
+                Ok(false) => continue,
+                Ok(true) => (CHANGED_EVENT, None),
+                Err(e) => (ERROR_EVENT, Some(e)),
+            };
+            let _ = app.emit(
+                name,
+                DirectoryEvent {
+                    workspace_id: workspace_id.clone(),
+                    error,
+                },
+            );
+        }
+    });
+
+    Ok(())
+}
+
+/// Stops watching a workspace's directory.
+pub fn unwatch(workspace_id: &str) {
+    WATCHERS.lock().unwrap().remove(workspace_id);
+}
+
+// Changes inside `.git` alone, such as a fetch, leave the workspace files as they are
+fn is_relevant(event: &notify::Result<notify::Event>) -> bool {
+    match event {
+        Ok(event) => {
+            event.paths.is_empty()
+                || event
+                    .paths
+                    .iter()
+                    .any(|path| !path.components().any(|c| c.as_os_str() == ".git"))
+        }
+        // Events may have been missed; check the files
+        Err(_) => true,
+    }
+}
@@ -0,0 +1,1116 @@
+use std::fmt;
+use std::path::Path;
+
+use crate::commands::http::parse_form_fields;
+use crate::db::environment::EnvironmentVariable;
+use crate::db::request::{
+    extract_path, ApiKeyLocation, FormField, FormFieldType, KeyValue, RequestAuth,
+};
+use crate::db::tab::TabState;
+use crate::import::{form_body, key_value, query_params, ImportIssue};
+
+const SEPARATOR: &str = "###";
+const METHODS: [&str; 9] = [
+    "GET", "POST", "PUT", "PATCH", "DELETE", "HEAD", "OPTIONS", "CONNECT", "TRACE",
+];
+const BOUNDARY: &str = "PingITFormBoundary";
+
+/// A `.http` / `.rest` file, as used by the VS Code REST Client and the JetBrains
+/// HTTP Client.
+///
+/// The text of every request is kept as written, so that saving rewrites only the
+/// requests that changed and leaves comments, variables and spacing untouched.
+#[derive(Debug, Clone)]
+pub struct HttpFile {
+    blocks: Vec<Block>,
+    line_ending: &'static str,
+    final_newline: bool,
+}
+
+// The text between two `###` lines
+#[derive(Debug, Clone)]
+struct Block {
+    /// `None` for the text before the first `###`
+    separator: Option<String>,
+    /// Comments, variables and `# @name`-style directives before the request line
+    leading: Vec<String>,
+    request: Option<Request>,
+    /// Response handlers, and the blank lines before the next block
+    trailing: Vec<String>,
+}
+
+// Whether a header is enabled, its name and its value
+type Header = (bool, String, String);
+
+#[derive(Debug, Clone)]
+struct Request {
+    name: String,
+    state: TabState,
+    /// From the request line to the end of the body
+    lines: Vec<String>,
+    /// The request line and its `?`/`&` query continuation lines
+    target: Vec<String>,
+    /// Header lines as written, with the header read from them
+    header_lines: Vec<(Header, String)>,
+    /// The Authorization line `state.auth` was read from, and its place among the headers
+    auth_line: Option<(usize, String)>,
+    body: Vec<String>,
+}
+
+/// Parses a `.http` file; `dir` is the directory it is in, which `< ./file` bodies are
+/// relative to.
+pub fn parse(text: &str, dir: &Path) -> HttpFile {
+    let mut blocks = Vec::new();
+    let mut separator = None;
+    let mut lines = Vec::new();
+    for line in text.lines() {
+        if line.starts_with(SEPARATOR) {
+            if separator.is_some() || !lines.is_empty() {
+                blocks.push(Block::parse(
+                    separator.take(),
+                    std::mem::take(&mut lines),
+                    dir,
+                ));
+            }
+            separator = Some(line.to_string());
+        } else {
+            lines.push(line.to_string());
+        }
+    }
+    if separator.is_some() || !lines.is_empty() {
+        blocks.push(Block::parse(separator, lines, dir));
+    }
+
+    HttpFile {
+        blocks,
+        line_ending: if text.contains("\r\n") { "\r\n" } else { "\n" },
+        final_newline: text.is_empty() || text.ends_with('\n'),
+    }
+}
+
+impl HttpFile {
+    /// The requests in the file, with their names.
+    pub fn requests(&self) -> impl Iterator<Item = (&str, &TabState)> {
+        self.blocks
+            .iter()
+            .filter_map(|block| block.request.as_ref())
+            .map(|request| (request.name.as_str(), &request.state))
+    }
+
+    /// `@name = value` definitions, in the order they first appear; later definitions
+    /// of a name replace earlier values.
+    pub fn variables(&self) -> Vec<EnvironmentVariable> {
+        let mut variables: Vec<EnvironmentVariable> = Vec::new();
+        for line in self.blocks.iter().flat_map(|block| &block.leading) {
+            let Some((key, value)) = variable(line) else {
+                continue;
+            };
+            match variables.iter_mut().find(|v| v.key == key) {
+                Some(existing) => existing.value = value.to_string(),
+                None => variables.push(EnvironmentVariable {
+                    key: key.to_string(),
+                    value: value.to_string(),
+                    enabled: true,
+                    secret: false,
+                }),
+            }
+        }
+        variables
+    }
+
+    /// Parts of the file the app cannot run, keyed by request name.
+    pub fn unsupported(&self) -> Vec<ImportIssue> {
+        let mut issues = Vec::new();
+        for block in &self.blocks {
+            let Some(request) = &block.request else {
+                continue;
+            };
+            let issue = |message: &str| ImportIssue {
+                location: request.name.clone(),
+                message: message.to_string(),
+            };
+            if block.trailing.iter().any(|line| is_handler(line)) {
+                issues.push(issue("Response handler"));
+            }
+            if request.lines.iter().any(|line| line.contains("{{$")) {
+                issues.push(issue("Dynamic variables such as {{$guid}}"));
+            }
+            if request.lines.iter().any(|line| line.contains(".response.")) {
+                issues.push(issue("Variables from the response of another request"));
+            }
+        }
+        issues
+    }
+
+    /// Brings the file in line with `requests`, given as names and states in their new
+    /// order. Requests are matched to those in the file by name, then by position; only
+    /// new and changed ones are written out again. Returns what could not be written.
+    ///
+    /// `synced` names the requests in the file when it was last read or written. Other
+    /// requests in the file were added to it since, and are kept rather than taken for
+    /// ones deleted in the app; without it, every request is one the app knows about.
+    pub fn update(
+        &mut self,
+        requests: &[(String, TabState)],
+        synced: Option<&[String]>,
+        dir: &Path,
+    ) -> Vec<ImportIssue> {
+        // Comment-only blocks stay with the request before them
+        let mut head = Vec::new();
+        let mut groups: Vec<Option<Vec<Block>>> = Vec::new();
+        for block in std::mem::take(&mut self.blocks) {
+            match groups.last_mut() {
+                _ if block.request.is_some() => groups.push(Some(vec![block])),
+                Some(Some(group)) => group.push(block),
+                _ => head.push(block),
+            }
+        }
+        let name_of = |group: &Option<Vec<Block>>| {
+            group
+                .as_ref()
+                .and_then(|g| g[0].request.as_ref())
+                .map(|r| r.name.clone())
+        };
+
+        let mut synced = synced.map(<[String]>::to_vec);
+        let added: Vec<bool> = groups
+            .iter()
+            .map(|group| {
+                let (Some(names), Some(name)) = (&mut synced, name_of(group)) else {
+                    return false;
+                };
+                match names.iter().position(|n| *n == name) {
+                    Some(i) => {
+                        names.remove(i);
+                        false
+                    }
+                    None => true,
+                }
+            })
+            .collect();
+
+        let mut matches: Vec<Option<usize>> = vec![None; requests.len()];
+        let mut used = vec![false; groups.len()];
+        for (i, (name, _)) in requests.iter().enumerate() {
+            if let Some(g) =
+                (0..groups.len()).find(|g| !used[*g] && name_of(&groups[*g]).as_ref() == Some(name))
+            {
+                matches[i] = Some(g);
+                used[g] = true;
+            }
+        }
+        let mut unused = (0..groups.len())
+            .filter(|g| !used[*g] && !added[*g])
+            .collect::<Vec<_>>();
+        unused.reverse();
+        for found in matches.iter_mut().filter(|m| m.is_none()) {
+            *found = unused.pop();
+            if let Some(g) = *found {
+                used[g] = true;
+            }
+        }
+
+        // Requests added to the file stay after the request they followed
+        let mut kept: Vec<(Option<usize>, usize)> = (0..groups.len())
+            .filter(|g| !used[*g] && added[*g])
+            .map(|g| ((0..g).rev().find(|before| used[*before]), g))
+            .collect();
+
+        let mut issues = Vec::new();
+        // Original index of the request written last; `None` after a new one
+        let mut previous = None;
+        let mut at_start = true;
+        self.blocks = head;
+        for (_, g) in kept.extract_if(.., |(after, _)| after.is_none()) {
+            let Some(group) = groups[g].take() else {
+                continue;
+            };
+            issues.push(added_issue(&group));
+            self.push(group, Some(g), &mut previous, &mut at_start);
+        }
+        for ((name, state), found) in requests.iter().zip(matches) {
+            let mut group = match found.and_then(|g| groups[g].take()) {
+                Some(group) => group,
+                None => vec![Block {
+                    separator: Some(SEPARATOR.to_string()),
+                    leading: Vec::new(),
+                    request: None,
+                    trailing: Vec::new(),
+                }],
+            };
+            for message in group[0].update(name, state, dir) {
+                issues.push(ImportIssue {
+                    location: name.clone(),
+                    message,
+                });
+            }
+            self.push(group, found, &mut previous, &mut at_start);
+
+            let Some(found) = found else {
+                continue;
+            };
+            for (_, g) in kept.extract_if(.., |(after, _)| *after == Some(found)) {
+                let Some(group) = groups[g].take() else {
+                    continue;
+                };
+                issues.push(added_issue(&group));
+                self.push(group, Some(g), &mut previous, &mut at_start);
+            }
+        }
+
+        issues
+    }
+
+    // Appends a request's blocks; `found` is its index in the file as read
+    fn push(
+        &mut self,
+        mut group: Vec<Block>,
+        found: Option<usize>,
+        previous: &mut Option<usize>,
+        at_start: &mut bool,
+    ) {
+        // Keep a blank line before the `###` of requests that were added or moved
+        let in_place = match found {
+            Some(0) => *at_start,
+            Some(g) => *previous == Some(g - 1),
+            None => false,
+        };
+        if !in_place {
+            if let Some(last) = self.blocks.last_mut() {
+                if last.last_line().is_some_and(|line| !line.trim().is_empty()) {
+                    last.trailing.push(String::new());
+                }
+            }
+        }
+        if group[0].separator.is_none() && !self.blocks.is_empty() {
+            group[0].separator = Some(SEPARATOR.to_string());
+        }
+        *previous = found;
+        *at_start = false;
+        self.blocks.extend(group);
+    }
+}
+
+fn added_issue(group: &[Block]) -> ImportIssue {
+    ImportIssue {
+        location: group
+            .first()
+            .and_then(|block| block.request.as_ref())
+            .map(|request| request.name.clone())
+            .unwrap_or_default(),
+        message: "Added to the file outside the app; kept in the file, open it again to edit it"
+            .to_string(),
+    }
+}
+
+impl fmt::Display for HttpFile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let lines: Vec<&String> = self.blocks.iter().flat_map(Block::lines).collect();
+        for (i, line) in lines.iter().enumerate() {
+            if i > 0 {
+                f.write_str(self.line_ending)?;
+            }
+            f.write_str(line)?;
+        }
+        if self.final_newline && !lines.is_empty() {
+            f.write_str(self.line_ending)?;
+        }
+        Ok(())
+    }
+}
+
+impl Block {
+    fn parse(separator: Option<String>, lines: Vec<String>, dir: &Path) -> Block {
+        let mut lines = lines.into_iter().peekable();
+        let mut leading = Vec::new();
+        while let Some(line) = lines.next_if(|line| {
+            let line = line.trim();
+            line.is_empty() || line.starts_with('@') || comment(line).is_some()
+        }) {
+            leading.push(line);
+        }
+        let rest: Vec<String> = lines.collect();
+        if rest.is_empty() {
+            return Block {
+                separator,
+                leading,
+                request: None,
+                trailing: Vec::new(),
+            };
+        }
+
+        // Response handlers end the request; blank lines before them are not body
+        let mut end = rest
+            .iter()
+            .position(|line| is_handler(line))
+            .unwrap_or(rest.len());
+        while end > 1 && rest[end - 1].trim().is_empty() {
+            end -= 1;
+        }
+        let trailing = rest[end..].to_vec();
+        let lines = rest[..end].to_vec();
+
+        let mut block = Block {
+            separator,
+            leading,
+            request: None,
+            trailing,
+        };
+        // A handler with no request line before it, as in a half-written block
+        if !lines.is_empty() {
+            block.request = Some(block.read_request(lines, dir));
+        }
+        block
+    }
+
+    fn read_request(&self, lines: Vec<String>, dir: &Path) -> Request {
+        let mut state = TabState {
+            method: "GET".to_string(),
+            url: String::new(),
+            params: Vec::new(),
+            headers: Vec::new(),
+            body_type: "none".to_string(),
+            body_content: String::new(),
+            settings: Default::default(),
+            auth: RequestAuth::None,
+            is_dirty: false,
+        };
+
+        let target_len = 1 + lines[1..]
+            .iter()
+            .take_while(|line| line.trim_start().starts_with(['?', '&']))
+            .count();
+        let target = lines[..target_len].to_vec();
+        let (method, url) = request_line(&target.iter().map(|l| l.trim()).collect::<String>());
+        state.method = method;
+        state.url = url;
+        state.params = query_params(&state.url);
+
+        let mut header_lines = Vec::new();
+        let mut auth_line = None;
+        let mut i = target_len;
+        while i < lines.len() && !lines[i].trim().is_empty() {
+            let line = lines[i].trim();
+            i += 1;
+            // Disabled headers are commented out
+            let (enabled, line) = match comment(line) {
+                Some(rest) => (false, rest.trim()),
+                None => (true, line),
+            };
+            let Some((key, value)) = line.split_once(':') else {
+                continue;
+            };
+            let (key, value) = (key.trim(), value.trim());
+            if !enabled && !is_token(key) {
+                continue;
+            }
+            // Credentials written in the clear are encoded by the clients that read the file
+            if enabled && key.eq_ignore_ascii_case("authorization") {
+                if let Some(auth) = credentials(value) {
+                    state.auth = auth;
+                    auth_line = Some((state.headers.len(), lines[i - 1].clone()));
+                    continue;
+                }
+            }
+            state.headers.push(KeyValue {
+                enabled,
+                ..key_value(key, value.to_string())
+            });
+            header_lines.push((
+                (enabled, key.to_string(), value.to_string()),
+                lines[i - 1].clone(),
+            ));
+        }
+
+        let body: Vec<String> = lines.get(i + 1..).unwrap_or_default().to_vec();
+        let boundary = state
+            .headers
+            .iter()
+            .position(|h| h.enabled && h.key.eq_ignore_ascii_case("content-type"))
+            .and_then(|at| Some((at, boundary(&state.headers[at].value)?)));
+        let form = boundary.and_then(|(at, boundary)| {
+            let fields = form_fields(&body, &boundary, dir)?;
+            Some((at, form_body(fields)))
+        });
+        match (body.as_slice(), form) {
+            ([], _) => {}
+            (_, Some((at, content))) => {
+                // The app sets the Content-Type of forms itself, with its own boundary
+                state.headers.remove(at);
+                if let Some((auth_at, _)) = &mut auth_line {
+                    if *auth_at > at {
+                        *auth_at -= 1;
+                    }
+                }
+                state.body_type = "form-data".to_string();
+                state.body_content = content;
+            }
+            ([line], None) if line.starts_with('<') => {
+                state.body_type = "binary".to_string();
+                state.body_content = file_path(line, dir);
+            }
+            (lines, None) => {
+                state.body_type = "raw".to_string();
+                state.body_content = lines.join("\n");
+            }
+        }
+
+        for line in &self.leading {
+            match directive(line) {
+                Some(("no-redirect", _)) => state.settings.redirect.follow = false,
+                Some(("no-cookie-jar", _)) => state.settings.disable_cookie_jar = true,
+                _ => {}
+            }
+        }
+
+        Request {
+            name: self.explicit_name().unwrap_or_else(|| default_name(&state)),
+            state,
+            lines,
+            target,
+            header_lines,
+            auth_line,
+            body,
+        }
+    }
+
+    // `# @name` wins over the text after `###`
+    fn explicit_name(&self) -> Option<String> {
+        self.leading
+            .iter()
+            .find_map(|line| match directive(line) {
+                Some(("name", name)) if !name.is_empty() => Some(name.to_string()),
+                _ => None,
+            })
+            .or_else(|| {
+                let title = self.separator.as_deref()?.trim_start_matches('#').trim();
+                (!title.is_empty()).then(|| title.to_string())
+            })
+    }
+
+    fn lines(&self) -> impl Iterator<Item = &String> {
+        self.separator
+            .iter()
+            .chain(&self.leading)
+            .chain(self.request.iter().flat_map(|r| &r.lines))
+            .chain(&self.trailing)
+    }
+
+    fn last_line(&self) -> Option<&String> {
+        self.lines().last()
+    }
+
+    // Rewrites the block for a request that was added or may have changed
+    fn update(&mut self, name: &str, state: &TabState, dir: &Path) -> Vec<String> {
+        let mut issues = Vec::new();
+        if self.request.as_ref().is_none_or(|r| r.name != name) {
+            self.rename(name, state);
+        }
+        self.set_directive("no-redirect", !state.settings.redirect.follow);
+        self.set_directive("no-cookie-jar", state.settings.disable_cookie_jar);
+
+        if self.request.as_ref().is_none_or(|r| !same(&r.state, state)) {
+            let lines = render(self.request.as_ref(), state, dir, &mut issues);
+            // Parse what was written, so the next save can reuse its lines
+            let mut request = self.read_request(lines, dir);
+            request.name = name.to_string();
+            request.state = state.clone();
+            self.request = Some(request);
+        } else if let Some(request) = &mut self.request {
+            request.name = name.to_string();
+        }
+        issues
+    }
+
+    fn rename(&mut self, name: &str, state: &TabState) {
+        if let Some(line) = self
+            .leading
+            .iter_mut()
+            .find(|line| matches!(directive(line), Some(("name", _))))
+        {
+            let at = line.find("@name").unwrap_or_default();
+            *line = format!("{}@name {}", &line[..at], name);
+            return;
+        }
+        let titled = self
+            .separator
+            .as_deref()
+            .is_some_and(|s| !s.trim_start_matches('#').trim().is_empty());
+        // Requests named after their method and path need no name in the file
+        if !titled && name == default_name(state) {
+            return;
+        }
+        if self.separator.is_some() {
+            self.separator = Some(format!("{} {}", SEPARATOR, name));
+        } else {
+            let at = self.directive_position();
+            self.leading.insert(at, format!("# @name {}", name));
+        }
+    }
+
+    fn set_directive(&mut self, name: &str, on: bool) {
+        let found = self
+            .leading
+            .iter()
+            .position(|line| directive(line).is_some_and(|(d, _)| d == name));
+        match (found, on) {
+            (Some(i), false) => {
+                self.leading.remove(i);
+            }
+            (None, true) => {
+                let at = self.directive_position();
+                self.leading.insert(at, format!("# @{}", name));
+            }
+            _ => {}
+        }
+    }
+
+    // Directives go right above the request line, before any blank lines
+    fn directive_position(&self) -> usize {
+        self.leading.len()
+            - self
+                .leading
+                .iter()
+                .rev()
+                .take_while(|line| line.trim().is_empty())
+                .count()
+    }
+}
+
+// Lines from the request line to the end of the body, reusing what is unchanged
+fn render(
+    old: Option<&Request>,
+    state: &TabState,
+    dir: &Path,
+    issues: &mut Vec<String>,
+) -> Vec<String> {
+    let mut lines = match old {
+        Some(old) if old.state.method == state.method && old.state.url == state.url => {
+            old.target.clone()
+        }
+        _ => vec![format!("{} {}", state.method.to_uppercase(), state.url)],
+    };
+
+    let mut headers = headers(state);
+    let mut auth = match old.and_then(|o| o.auth_line.clone()) {
+        Some(line) if old.is_some_and(|o| o.state.auth == state.auth) => Some(line),
+        _ => auth_header(&state.auth, issues).map(|line| (headers.len(), line)),
+    };
+
+    let content = state.body_content.as_str();
+    if !content.is_empty() {
+        let content_type = match state.body_type.as_str() {
+            "json" => Some("application/json".to_string()),
+            "x-www-form-urlencoded" => Some("application/x-www-form-urlencoded".to_string()),
+            "form-data" => Some(format!("multipart/form-data; boundary={}", BOUNDARY)),
+            _ => None,
+        };
+        if let Some(content_type) = content_type {
+            default_header(&mut headers, "Content-Type", &content_type);
+        }
+    }
+    let body = match old {
+        _ if content.is_empty() || state.body_type == "none" => Vec::new(),
+        Some(old)
+            if old.state.body_type == state.body_type && old.state.body_content == content =>
+        {
+            old.body.clone()
+        }
+        _ => match state.body_type.as_str() {
+            "binary" => vec![format!("< {}", file_reference(content, dir))],
+            "form-data" => multipart(content, dir),
+            _ => content.lines().map(str::to_string).collect(),
+        },
+    };
+
+    // Headers that are still there keep their spelling and spacing
+    let mut unused: Vec<&(Header, String)> = old.iter().flat_map(|o| &o.header_lines).collect();
+    for (i, header) in headers.into_iter().enumerate() {
+        if let Some((_, line)) = auth.take_if(|(at, _)| *at == i) {
+            lines.push(line);
+        }
+        match unused.iter().position(|(h, _)| *h == header) {
+            Some(found) => lines.push(unused.remove(found).1.clone()),
+            None => {
+                let (enabled, key, value) = header;
+                let comment = if enabled { "" } else { "# " };
+                lines.push(format!("{}{}: {}", comment, key, value));
+            }
+        }
+    }
+    lines.extend(auth.map(|(_, line)| line));
+    if !body.is_empty() {
+        lines.push(String::new());
+        lines.extend(body);
+    }
+    lines
+}
+
+fn multipart(content: &str, dir: &Path) -> Vec<String> {
+    let mut lines = Vec::new();
+    for field in parse_form_fields(content).into_iter().filter(|f| f.enabled) {
+        lines.push(format!("--{}", BOUNDARY));
+        match field.field_type {
+            FormFieldType::Text => {
+                lines.push(format!(
+                    "Content-Disposition: form-data; name=\"{}\"",
+                    field.key
+                ));
+                lines.push(String::new());
+                lines.push(field.value);
+            }
+            FormFieldType::File => {
+                let file_name = field.file_name.unwrap_or_else(|| {
+                    Path::new(field.value.trim())
+                        .file_name()
+                        .map(|name| name.to_string_lossy().into_owned())
+                        .unwrap_or_default()
+                });
+                lines.push(format!(
+                    "Content-Disposition: form-data; name=\"{}\"; filename=\"{}\"",
+                    field.key, file_name
+                ));
+                let content_type = field.content_type.unwrap_or_else(|| {
+                    mime_guess::from_path(field.value.trim())
+                        .first_or_octet_stream()
+                        .to_string()
+                });
+                lines.push(format!("Content-Type: {}", content_type));
+                lines.push(String::new());
+                lines.push(format!("< {}", file_reference(&field.value, dir)));
+            }
+        }
+    }
+    lines.push(format!("--{}--", BOUNDARY));
+    lines
+}
+
+// Authorization as the REST Client and the JetBrains client write it
+fn auth_header(auth: &RequestAuth, issues: &mut Vec<String>) -> Option<String> {
+    match auth {
+        RequestAuth::None => None,
+        RequestAuth::Basic { username, password } => {
+            Some(format!("Authorization: Basic {}:{}", username, password))
+        }
+        RequestAuth::Digest { username, password } => {
+            Some(format!("Authorization: Digest {} {}", username, password))
+        }
+        RequestAuth::Bearer { token } => Some(format!("Authorization: Bearer {}", token)),
+        RequestAuth::ApiKey {
+            key,
+            value,
+            location: ApiKeyLocation::Header,
+        } => Some(format!("{}: {}", key, value)),
+        RequestAuth::ApiKey { .. } => {
+            issues.push("API key in the query; add it to the URL instead".to_string());
+            None
+        }
+        RequestAuth::OAuth2(_) => {
+            issues.push("OAuth 2.0 authorization".to_string());
+            None
+        }
+        RequestAuth::AwsSigV4(_) => {
+            issues.push("AWS Signature Version 4 authorization".to_string());
+            None
+        }
+        RequestAuth::Signature(_) => {
+            issues.push("Signature authorization".to_string());
+            None
+        }
+    }
+}
+
+// `Basic user:password`, `Basic user password` and `Digest user password`; already
+// encoded Basic credentials are left as a header
+fn credentials(value: &str) -> Option<RequestAuth> {
+    let (scheme, rest) = value.split_once(' ')?;
+    let rest = rest.trim();
+    if scheme.eq_ignore_ascii_case("basic") {
+        let (username, password) = rest.split_once(':').or_else(|| rest.split_once(' '))?;
+        Some(RequestAuth::Basic {
+            username: username.to_string(),
+            password: password.trim().to_string(),
+        })
+    } else if scheme.eq_ignore_ascii_case("digest") {
+        let (username, password) = rest.split_once(' ')?;
+        // `Digest username="…", realm=…` is a complete header, not credentials
+        if username.contains('=') {
+            return None;
+        }
+        Some(RequestAuth::Digest {
+            username: username.to_string(),
+            password: password.trim().to_string(),
+        })
+    } else {
+        None
+    }
+}
+
+// Whether saving has to rewrite the request
+fn same(old: &TabState, new: &TabState) -> bool {
+    old.method == new.method
+        && old.url == new.url
+        && headers(old) == headers(new)
+        && old.body_type == new.body_type
+        && same_body(old, new)
+        && old.auth == new.auth
+}
+
+// Forms are compared by the fields that are written, however the app stored them
+fn same_body(old: &TabState, new: &TabState) -> bool {
+    if new.body_type != "form-data" {
+        return old.body_content == new.body_content;
+    }
+    let fields = |state: &TabState| {
+        parse_form_fields(&state.body_content)
+            .into_iter()
+            .filter(|f| f.enabled)
+            .map(|f| (f.key, f.value, f.field_type, f.file_name, f.content_type))
+            .collect::<Vec<_>>()
+    };
+    fields(old) == fields(new)
+}
+
+fn headers(state: &TabState) -> Vec<Header> {
+    state
+        .headers
+        .iter()
+        .map(|h| (h.enabled, h.key.clone(), h.value.clone()))
+        .collect()
+}
+
+fn default_name(state: &TabState) -> String {
+    format!("{} {}", state.method, extract_path(&state.url))
+}
+
+fn default_header(headers: &mut Vec<Header>, name: &str, value: &str) {
+    if !headers
+        .iter()
+        .any(|(enabled, key, _)| *enabled && key.eq_ignore_ascii_case(name))
+    {
+        headers.push((true, name.to_string(), value.to_string()));
+    }
+}
+
+// Files next to the `.http` file are written relative to it
+fn file_reference(path: &str, dir: &Path) -> String {
+    match Path::new(path.trim()).strip_prefix(dir) {
+        Ok(relative) => format!("./{}", relative.to_string_lossy().replace('\\', "/")),
+        Err(_) => path.trim().to_string(),
+    }
+}
+
+// `< path`, or `<@ path` for a file with variables in it, relative to the `.http` file
+fn file_path(line: &str, dir: &Path) -> String {
+    let path = line.trim_start_matches(['<', '@']);
+    let path = path.split_once(' ').map_or(path, |(_, path)| path).trim();
+    dir.join(path.strip_prefix("./").unwrap_or(path))
+        .to_string_lossy()
+        .into_owned()
+}
+
+// The boundary of a `multipart/form-data` Content-Type
+fn boundary(content_type: &str) -> Option<String> {
+    let mut parts = content_type.split(';');
+    if !parts
+        .next()?
+        .trim()
+        .eq_ignore_ascii_case("multipart/form-data")
+    {
+        return None;
+    }
+    parts.find_map(|part| parameter(part, "boundary"))
+}
+
+// `name="value"` in a header value such as Content-Disposition
+fn parameter(part: &str, name: &str) -> Option<String> {
+    let (key, value) = part.trim().split_once('=')?;
+    key.trim()
+        .eq_ignore_ascii_case(name)
+        .then(|| value.trim().trim_matches('"').to_string())
+}
+
+// The fields of a multipart body as `multipart` writes it; `None` when a part has no
+// form field the app can hold, leaving the body as raw text
+fn form_fields(body: &[String], boundary: &str, dir: &Path) -> Option<Vec<FormField>> {
+    let delimiter = format!("--{}", boundary);
+    let end = format!("--{}--", boundary);
+    let mut lines = body.iter().map(|line| line.trim_end());
+    if lines.next()? != delimiter {
+        return None;
+    }
+
+    let mut fields = Vec::new();
+    loop {
+        let (mut name, mut file_name, mut content_type) = (None, None, None);
+        for line in lines.by_ref().take_while(|line| !line.is_empty()) {
+            let (key, value) = line.split_once(':')?;
+            if key.trim().eq_ignore_ascii_case("content-disposition") {
+                let mut parts = value.split(';').skip(1);
+                name = parts.clone().find_map(|part| parameter(part, "name"));
+                file_name = parts.find_map(|part| parameter(part, "filename"));
+            } else if key.trim().eq_ignore_ascii_case("content-type") {
+                content_type = Some(value.trim().to_string());
+            } else {
+                return None;
+            }
+        }
+        let mut content = Vec::new();
+        let last = loop {
+            let line = lines.next()?;
+            if line == delimiter || line == end {
+                break line;
+            }
+            content.push(line);
+        };
+
+        let field = match (file_name, content.as_slice()) {
+            (Some(file_name), [line]) if line.starts_with('<') => {
+                let path = file_path(line, dir);
+                // Only what differs from the file itself is kept
+                let default_name = Path::new(&path)
+                    .file_name()
+                    .map(|name| name.to_string_lossy().into_owned());
+                let default_type = mime_guess::from_path(&path)
+                    .first_or_octet_stream()
+                    .to_string();
+                FormField {
+                    key: name?,
+                    value: path,
+                    enabled: true,
+                    description: None,
+                    field_type: FormFieldType::File,
+                    file_name: (Some(&file_name) != default_name.as_ref()).then_some(file_name),
+                    content_type: content_type.filter(|t| *t != default_type),
+                }
+            }
+            (None, content) if content_type.is_none() => FormField {
+                key: name?,
+                value: content.join("\n"),
+                enabled: true,
+                description: None,
+                field_type: FormFieldType::Text,
+                file_name: None,
+                content_type: None,
+            },
+            _ => return None,
+        };
+        fields.push(field);
+
+        if last == end {
+            break;
+        }
+    }
+    lines.all(|line| line.is_empty()).then_some(fields)
+}
+
+// A header name, which a commented-out header starts with
+fn is_token(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "!#$%&'*+-.^_`|~".contains(c))
+}
+
+// `METHOD url HTTP/1.1`, where only the URL is required
+fn request_line(line: &str) -> (String, String) {
+    let line = line.trim();
+    let (method, rest) = match line.split_once(char::is_whitespace) {
+        Some((method, rest)) if METHODS.contains(&method.to_uppercase().as_str()) => {
+            (method.to_uppercase(), rest.trim())
+        }
+        _ => ("GET".to_string(), line),
+    };
+    let url = match rest.rsplit_once(char::is_whitespace) {
+        Some((url, version)) if version.starts_with("HTTP/") => url.trim(),
+        _ => rest,
+    };
+    (method, url.to_string())
+}
+
+fn comment(line: &str) -> Option<&str> {
+    line.strip_prefix('#').or_else(|| line.strip_prefix("//"))
+}
+
+// `# @name login` or `// @no-redirect`
+fn directive(line: &str) -> Option<(&str, &str)> {
+    let rest = comment(line.trim())?.trim().strip_prefix('@')?;
+    Some(
+        rest.split_once(char::is_whitespace)
+            .map_or((rest, ""), |(name, value)| (name, value.trim())),
+    )
+}
+
+// `@host = example.com`
+fn variable(line: &str) -> Option<(&str, &str)> {
+    let (key, value) = line.trim().strip_prefix('@')?.split_once('=')?;
+    Some((key.trim(), value.trim()))
+}
+
+// JetBrains `> {% script %}`, `> handler.js` and `<> previous-response.json` lines
+fn is_handler(line: &str) -> bool {
+    line.starts_with("> ") || line.starts_with(">>") || line.starts_with("<> ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FILE: &str = "@host = https://api.example.com
+
+### List users
+GET {{host}}/users?page=1
+Accept: application/json
+# X-Debug: 1
+
+###
+# @name create
+POST {{host}}/users
+Content-Type: application/json
+
+{
+  \"name\": \"Ada\"
+}
+
+> {% client.global.set(\"id\", response.body.id); %}
+
+### Upload
+POST {{host}}/avatar
+Content-Type: multipart/form-data; boundary=PingITFormBoundary
+
+--PingITFormBoundary
+Content-Disposition: form-data; name=\"user\"
+
+ada
+--PingITFormBoundary
+Content-Disposition: form-data; name=\"avatar\"; filename=\"me.png\"
+Content-Type: image/png
+
+< ./avatar.png
+--PingITFormBoundary--
+";
+
+    fn dir() -> &'static Path {
+        Path::new("/api")
+    }
+
+    fn requests(file: &HttpFile) -> Vec<(String, TabState)> {
+        file.requests()
+            .map(|(name, state)| (name.to_string(), state.clone()))
+            .collect()
+    }
+
+    #[test]
+    fn file_is_written_back_as_read() {
+        let file = parse(FILE, dir());
+        assert_eq!(file.to_string(), FILE);
+
+        let mut saved = file.clone();
+        assert!(saved.update(&requests(&file), None, dir()).is_empty());
+        assert_eq!(saved.to_string(), FILE);
+
+        let crlf = FILE.replace('\n', "\r\n");
+        assert_eq!(parse(&crlf, dir()).to_string(), crlf);
+    }
+
+    #[test]
+    fn editing_one_request_rewrites_only_its_block() {
+        let mut file = parse(FILE, dir());
+        let mut edited = requests(&file);
+        edited[1].1.body_content = "{\"name\": \"Grace\"}".to_string();
+        file.update(&edited, None, dir());
+
+        let expected = FILE.replace("{\n  \"name\": \"Ada\"\n}", "{\"name\": \"Grace\"}");
+        assert_eq!(file.to_string(), expected);
+    }
+
+    #[test]
+    fn multipart_bodies_are_read_back_as_form_fields() {
+        let file = parse(FILE, dir());
+        let (_, upload) = &requests(&file)[2];
+        assert_eq!(upload.body_type, "form-data");
+        assert!(upload.headers.is_empty());
+
+        let fields = parse_form_fields(&upload.body_content);
+        assert_eq!(fields.len(), 2);
+        assert_eq!(
+            (fields[0].key.as_str(), fields[0].value.as_str()),
+            ("user", "ada")
+        );
+        assert_eq!(fields[1].field_type, FormFieldType::File);
+        assert_eq!(fields[1].value, "/api/avatar.png");
+        assert_eq!(fields[1].file_name.as_deref(), Some("me.png"));
+        assert_eq!(fields[1].content_type, None);
+
+        // Written from the app, the form reads back the same
+        let mut written = parse("", dir());
+        written.update(&requests(&file)[2..], None, dir());
+        let reread = parse(&written.to_string(), dir());
+        assert_eq!(requests(&reread)[0].1.body_type, "form-data");
+        assert!(same(&requests(&reread)[0].1, upload));
+    }
+
+    #[test]
+    fn disabled_headers_are_commented_out() {
+        let file = parse(FILE, dir());
+        let (_, list) = &requests(&file)[0];
+        assert_eq!(list.headers.len(), 2);
+        assert!(list.headers[0].enabled);
+        assert!(!list.headers[1].enabled);
+        assert_eq!(
+            (list.headers[1].key.as_str(), list.headers[1].value.as_str()),
+            ("X-Debug", "1")
+        );
+
+        let mut state = list.clone();
+        state.headers[0].enabled = false;
+        state.headers[1].enabled = true;
+        let mut written = parse("", dir());
+        written.update(&[("List users".to_string(), state)], None, dir());
+        let text = written.to_string();
+        assert!(
+            text.contains("# Accept: application/json\nX-Debug: 1\n"),
+            "{}",
+            text
+        );
+    }
+
+    #[test]
+    fn requests_added_to_the_file_are_kept() {
+        let synced: Vec<String> = requests(&parse(FILE, dir()))
+            .into_iter()
+            .map(|(name, _)| name)
+            .collect();
+        let on_disk = FILE.replace(
+            "###\n# @name create",
+            "### Health\nGET {{host}}/health\n\n###\n# @name create",
+        );
+        let mut file = parse(&on_disk, dir());
+
+        // "create" was deleted in the app and "Health" added on disk
+        let mut kept = requests(&file);
+        kept.retain(|(name, _)| name != "create" && name != "Health");
+        let issues = file.update(&kept, Some(&synced), dir());
+
+        let text = file.to_string();
+        assert!(
+            text.contains("### Health\nGET {{host}}/health\n"),
+            "{}",
+            text
+        );
+        assert!(!text.contains("@name create"), "{}", text);
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].location, "Health");
+    }
+
+    #[test]
+    fn handlers_without_a_request_are_kept_as_text() {
+        let text = "### \n> {% client.test() %}\n\n###\n<> previous.json\n";
+        let mut file = parse(text, dir());
+        assert_eq!(file.requests().count(), 0);
+        assert_eq!(file.to_string(), text);
+
+        assert!(file.update(&[], None, dir()).is_empty());
+        assert_eq!(file.to_string(), text);
+    }
+}
@@ -0,0 +1,793 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::db::environment::EnvironmentVariable;
+use crate::db::oauth::{OAuth2ClientAuth, OAuth2Config, OAuth2GrantType};
+use crate::db::request::{
+    ApiKeyLocation, AwsCredentials, FormField, FormFieldType, KeyValue, RequestAuth,
+};
+use crate::db::tab::TabState;
+
+use super::{
+    form_body, key_value, Imported, ImportedCollection, ImportedEnvironment, ImportedItem,
+};
+
+const METHODS: [&str; 9] = [
+    "get", "post", "put", "delete", "patch", "options", "head", "connect", "trace",
+];
+
+// Blocks whose content is free text rather than `key: value` lines
+const TEXT_BLOCKS: [&str; 9] = [
+    "body:json",
+    "body:text",
+    "body:xml",
+    "body:sparql",
+    "body:graphql",
+    "body:graphql:vars",
+    "script:pre-request",
+    "script:post-response",
+    "tests",
+];
+
+/// Reads a Bruno collection from its directory (the one holding `bruno.json`).
+pub fn read(path: &Path) -> Result<Imported, String> {
+    let dir = match path.file_name() {
+        Some(name) if name == "bruno.json" => path.parent().unwrap_or(path),
+        _ => path,
+    };
+    let config = fs::read_to_string(dir.join("bruno.json"))
+        .map_err(|_| format!("{} is not a Bruno collection", dir.display()))?;
+    let config: serde_json::Value = serde_json::from_str(&config).map_err(|e| e.to_string())?;
+    let name = config["name"]
+        .as_str()
+        .filter(|name| !name.is_empty())
+        .map(str::to_string)
+        .unwrap_or_else(|| file_stem(dir));
+
+    let mut reader = Reader {
+        root: dir.to_path_buf(),
+        imported: Imported {
+            name: name.clone(),
+            ..Default::default()
+        },
+        variables: Vec::new(),
+    };
+
+    let collection = match dir.join("collection.bru") {
+        path if path.is_file() => reader.file(&path)?,
+        _ => Vec::new(),
+    };
+    reader.scripts(&collection, "");
+    reader.variables.extend(
+        pairs(&collection, "vars:pre-request")
+            .into_iter()
+            .map(|pair| variable(pair.key, pair.value, pair.enabled, false)),
+    );
+    let defaults = Defaults {
+        headers: pairs(&collection, "headers"),
+        auth: reader
+            .folder_auth(&collection, "")
+            .unwrap_or(RequestAuth::None),
+    };
+    let items = reader.items(dir, "", &defaults)?;
+    reader.environments(&dir.join("environments"))?;
+
+    let Reader {
+        mut imported,
+        variables,
+        ..
+    } = reader;
+    imported.collections.push(ImportedCollection {
+        name: name.clone(),
+        items,
+    });
+    if !variables.is_empty() {
+        imported
+            .environments
+            .push(ImportedEnvironment { name, variables });
+    }
+    Ok(imported)
+}
+
+/// A block of a `.bru` file, such as `headers { ... }` or `body:json { ... }`.
+struct Block {
+    name: String,
+    lines: Vec<String>,
+}
+
+// A folder or request file with its `seq`
+type Entry = (Option<f64>, PathBuf, Vec<Block>);
+
+// Headers and auth that folders and the collection apply to the requests inside them
+#[derive(Clone)]
+struct Defaults {
+    headers: Vec<KeyValue>,
+    auth: RequestAuth,
+}
+
+struct Reader {
+    root: PathBuf,
+    imported: Imported,
+    /// Collection variables, plus the path parameters of every request
+    variables: Vec<EnvironmentVariable>,
+}
+
+impl Reader {
+    fn file(&self, path: &Path) -> Result<Vec<Block>, String> {
+        let content = fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+        Ok(parse_blocks(&content))
+    }
+
+    // Folders first, then requests, each in Bruno's `seq` order
+    fn items(
+        &mut self,
+        dir: &Path,
+        parent: &str,
+        defaults: &Defaults,
+    ) -> Result<Vec<ImportedItem>, String> {
+        let mut folders: Vec<Entry> = Vec::new();
+        let mut requests: Vec<Entry> = Vec::new();
+        let entries = fs::read_dir(dir).map_err(|e| format!("{}: {}", dir.display(), e))?;
+        for entry in entries.filter_map(Result::ok) {
+            let path = entry.path();
+            let file_name = entry.file_name().to_string_lossy().to_string();
+            if file_name.starts_with('.') || file_name == "node_modules" {
+                continue;
+            }
+            if path.is_dir() {
+                if parent.is_empty() && file_name == "environments" {
+                    continue;
+                }
+                let folder = match path.join("folder.bru") {
+                    path if path.is_file() => self.file(&path)?,
+                    _ => Vec::new(),
+                };
+                folders.push((seq(&folder), path, folder));
+            } else if file_name.ends_with(".bru")
+                && file_name != "folder.bru"
+                && file_name != "collection.bru"
+            {
+                let blocks = self.file(&path)?;
+                requests.push((seq(&blocks), path, blocks));
+            }
+        }
+        let order = |a: &Entry, b: &Entry| {
+            let (seq_a, seq_b) = (a.0.unwrap_or(f64::MAX), b.0.unwrap_or(f64::MAX));
+            seq_a.total_cmp(&seq_b).then_with(|| a.1.cmp(&b.1))
+        };
+        folders.sort_by(order);
+        requests.sort_by(order);
+
+        let mut items = Vec::new();
+        for (_, path, folder) in folders {
+            let name = meta(&folder, "name").unwrap_or_else(|| file_stem(&path));
+            let location = join(parent, &name);
+            self.scripts(&folder, &location);
+            let defaults = Defaults {
+                headers: merge(&defaults.headers, pairs(&folder, "headers")),
+                auth: self
+                    .folder_auth(&folder, &location)
+                    .unwrap_or_else(|| defaults.auth.clone()),
+            };
+            let children = self.items(&path, &location, &defaults)?;
+            items.push(ImportedItem::Folder {
+                name,
+                items: children,
+            });
+        }
+        for (_, path, blocks) in requests {
+            let name = meta(&blocks, "name").unwrap_or_else(|| file_stem(&path));
+            let location = join(parent, &name);
+            match meta(&blocks, "type").as_deref() {
+                Some("http") | Some("graphql") | None => {}
+                Some(other) => {
+                    self.imported
+                        .skip(&location, format!("Request of type \"{}\"", other));
+                    continue;
+                }
+            }
+            let state = self.request(&blocks, defaults, &location);
+            items.push(ImportedItem::Request {
+                name,
+                state: Box::new(state),
+            });
+        }
+        Ok(items)
+    }
+
+    fn request(&mut self, blocks: &[Block], defaults: &Defaults, location: &str) -> TabState {
+        let mut state = TabState::default();
+        let Some(method_block) = blocks.iter().find(|b| METHODS.contains(&b.name.as_str())) else {
+            self.imported
+                .skip(location, "Request without a method block");
+            return state;
+        };
+        let http = pairs(blocks, &method_block.name);
+        let field = |name: &str| {
+            http.iter()
+                .find(|pair| pair.key == name)
+                .map(|pair| pair.value.clone())
+                .unwrap_or_default()
+        };
+
+        state.method = method_block.name.to_ascii_uppercase();
+        state.url = field("url");
+        state.params = pairs(blocks, "params:query");
+        state.headers = merge(&defaults.headers, pairs(blocks, "headers"));
+
+        // `:name` path parameters become `{{name}}` collection variables
+        for param in pairs(blocks, "params:path") {
+            let (base, rest) = match state.url.find(['?', '#']) {
+                Some(index) => state.url.split_at(index),
+                None => (state.url.as_str(), ""),
+            };
+            let base = base
+                .split('/')
+                .map(|segment| match segment.strip_prefix(':') {
+                    Some(name) if name == param.key => format!("{{{{{}}}}}", name),
+                    _ => segment.to_string(),
+                })
+                .collect::<Vec<_>>()
+                .join("/");
+            state.url = format!("{}{}", base, rest);
+            if !self.variables.iter().any(|v| v.key == param.key) {
+                self.variables
+                    .push(variable(param.key, param.value, true, false));
+            }
+        }
+
+        self.body(blocks, &field("body"), &mut state, location);
+        state.auth = match field("auth").as_str() {
+            "" | "inherit" => defaults.auth.clone(),
+            mode => self.auth(blocks, mode, location),
+        };
+
+        self.scripts(blocks, location);
+        for (name, label) in [
+            ("vars:pre-request", "Request variables"),
+            ("vars:post-response", "Variables set from the response"),
+            ("assert", "Assertions"),
+        ] {
+            if !pairs(blocks, name).is_empty() {
+                self.imported.skip(location, label);
+            }
+        }
+        state
+    }
+
+    fn body(&mut self, blocks: &[Block], mode: &str, state: &mut TabState, location: &str) {
+        let (body_type, content_type) = match mode {
+            "" | "none" => return,
+            "json" => ("raw", None),
+            "text" => ("raw", Some("text/plain")),
+            "xml" => ("raw", Some("application/xml")),
+            "sparql" => ("raw", Some("application/sparql-query")),
+            "graphql" => ("raw", None),
+            "formUrlEncoded" => ("x-www-form-urlencoded", None),
+            "multipartForm" => ("form-data", None),
+            other => {
+                self.imported
+                    .skip(location, format!("Body mode \"{}\"", other));
+                return;
+            }
+        };
+        state.body_type = body_type.to_string();
+        state.body_content = match mode {
+            "graphql" => {
+                let variables = text(blocks, "body:graphql:vars");
+                let mut payload = serde_json::json!({ "query": text(blocks, "body:graphql") });
+                if let Ok(variables) = serde_json::from_str::<serde_json::Value>(&variables) {
+                    payload["variables"] = variables;
+                }
+                serde_json::to_string_pretty(&payload).unwrap_or_default()
+            }
+            "formUrlEncoded" => url::form_urlencoded::Serializer::new(String::new())
+                .extend_pairs(
+                    pairs(blocks, "body:form-urlencoded")
+                        .iter()
+                        .filter(|f| f.enabled)
+                        .map(|f| (&f.key, &f.value)),
+                )
+                .finish(),
+            "multipartForm" => {
+                let form = pairs(blocks, "body:multipart-form")
+                    .into_iter()
+                    .map(|pair| self.form_field(pair, location))
+                    .collect();
+                form_body(form)
+            }
+            _ => text(blocks, &format!("body:{}", mode)),
+        };
+        if let Some(content_type) = content_type {
+            if !state
+                .headers
+                .iter()
+                .any(|h| h.key.eq_ignore_ascii_case("content-type"))
+            {
+                state
+                    .headers
+                    .push(key_value("Content-Type", content_type.to_string()));
+            }
+        }
+    }
+
+    // `@file(path)` values are files, relative to the collection; several are separated by `|`
+    fn form_field(&mut self, pair: KeyValue, location: &str) -> FormField {
+        let file = pair
+            .value
+            .strip_prefix("@file(")
+            .and_then(|rest| rest.strip_suffix(')'))
+            .map(str::to_string);
+        let value = match &file {
+            Some(paths) => {
+                let mut paths = paths.split('|').filter(|p| !p.is_empty());
+                let first = paths.next().unwrap_or_default();
+                if paths.next().is_some() {
+                    self.imported.skip(
+                        location,
+                        format!("All but the first file of form field \"{}\"", pair.key),
+                    );
+                }
+                match first {
+                    "" => String::new(),
+                    path => self.root.join(path).to_string_lossy().to_string(),
+                }
+            }
+            None => pair.value,
+        };
+        FormField {
+            key: pair.key,
+            value,
+            enabled: pair.enabled,
+            description: None,
+            field_type: match file {
+                Some(_) => FormFieldType::File,
+                None => FormFieldType::Text,
+            },
+            file_name: None,
+            content_type: None,
+        }
+    }
+
+    // Folders and the collection name their mode in `auth { mode: ... }`; `None` inherits
+    fn folder_auth(&mut self, blocks: &[Block], location: &str) -> Option<RequestAuth> {
+        let mode = pairs(blocks, "auth")
+            .into_iter()
+            .find(|pair| pair.key == "mode")
+            .map(|pair| pair.value)?;
+        match mode.as_str() {
+            "inherit" => None,
+            mode => Some(self.auth(blocks, mode, location)),
+        }
+    }
+
+    fn auth(&mut self, blocks: &[Block], mode: &str, location: &str) -> RequestAuth {
+        let settings = pairs(blocks, &format!("auth:{}", mode));
+        let field = |name: &str| {
+            settings
+                .iter()
+                .find(|pair| pair.key == name)
+                .map(|pair| pair.value.clone())
+                .unwrap_or_default()
+        };
+
+        match mode {
+            "none" => RequestAuth::None,
+            "basic" => RequestAuth::Basic {
+                username: field("username"),
+                password: field("password"),
+            },
+            "digest" => RequestAuth::Digest {
+                username: field("username"),
+                password: field("password"),
+            },
+            "bearer" => RequestAuth::Bearer {
+                token: field("token"),
+            },
+            "apikey" => RequestAuth::ApiKey {
+                key: field("key"),
+                value: field("value"),
+                location: match field("placement").as_str() {
+                    "queryparams" => ApiKeyLocation::Query,
+                    _ => ApiKeyLocation::Header,
+                },
+            },
+            "awsv4" => {
+                if !field("profileName").is_empty() {
+                    self.imported
+                        .skip(location, "AWS credentials from a named profile");
+                }
+                RequestAuth::AwsSigV4(AwsCredentials {
+                    access_key_id: field("accessKeyId"),
+                    secret_access_key: field("secretAccessKey"),
+                    session_token: field("sessionToken"),
+                    region: field("region"),
+                    service: field("service"),
+                })
+            }
+            "oauth2" => {
+                let grant_type = match field("grant_type").as_str() {
+                    "" | "client_credentials" => OAuth2GrantType::ClientCredentials,
+                    "password" => OAuth2GrantType::Password,
+                    "authorization_code" => OAuth2GrantType::AuthorizationCode,
+                    other => {
+                        self.imported
+                            .skip(location, format!("OAuth 2.0 grant type \"{}\"", other));
+                        return RequestAuth::None;
+                    }
+                };
+                RequestAuth::OAuth2(OAuth2Config {
+                    grant_type,
+                    token_url: field("access_token_url"),
+                    client_id: field("client_id"),
+                    client_secret: field("client_secret"),
+                    client_auth: match field("credentials_placement").as_str() {
+                        "body" => OAuth2ClientAuth::Body,
+                        _ => OAuth2ClientAuth::Basic,
+                    },
+                    scope: field("scope"),
+                    username: field("username"),
+                    password: field("password"),
+                    auth_url: field("authorization_url"),
+                    ..Default::default()
+                })
+            }
+            other => {
+                self.imported
+                    .skip(location, format!("Authorization type \"{}\"", other));
+                RequestAuth::None
+            }
+        }
+    }
+
+    fn scripts(&mut self, blocks: &[Block], location: &str) {
+        for (name, label) in [
+            ("script:pre-request", "Pre-request script"),
+            ("script:post-response", "Post-response script"),
+            ("tests", "Tests"),
+        ] {
+            if !text(blocks, name).trim().is_empty() {
+                self.imported.skip(location, label);
+            }
+        }
+    }
+
+    // Secret values live outside the collection, so they are imported empty
+    fn environments(&mut self, dir: &Path) -> Result<(), String> {
+        let Ok(entries) = fs::read_dir(dir) else {
+            return Ok(());
+        };
+        let mut paths: Vec<PathBuf> = entries
+            .filter_map(Result::ok)
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == "bru"))
+            .collect();
+        paths.sort();
+
+        for path in paths {
+            let blocks = self.file(&path)?;
+            let mut variables: Vec<EnvironmentVariable> = pairs(&blocks, "vars")
+                .into_iter()
+                .map(|pair| variable(pair.key, pair.value, pair.enabled, false))
+                .collect();
+            for line in blocks
+                .iter()
+                .filter(|b| b.name == "vars:secret")
+                .flat_map(|b| &b.lines)
+            {
+                let name = line.trim().trim_end_matches(',');
+                let (name, enabled) = match name.strip_prefix('~') {
+                    Some(name) => (name, false),
+                    None => (name, true),
+                };
+                if !name.is_empty() {
+                    variables.push(variable(name.to_string(), String::new(), enabled, true));
+                }
+            }
+            self.imported.environments.push(ImportedEnvironment {
+                name: file_stem(&path),
+                variables,
+            });
+        }
+        Ok(())
+    }
+}
+
+// Blocks open with `name {` or `name [` and close with `}` or `]` at the start of a line;
+// their content is indented by two spaces
+fn parse_blocks(content: &str) -> Vec<Block> {
+    let mut blocks = Vec::new();
+    let mut current: Option<Block> = None;
+    for line in content.lines() {
+        match current.as_mut() {
+            None => {
+                let header = line.trim_end();
+                if let Some(name) = header
+                    .strip_suffix('{')
+                    .or_else(|| header.strip_suffix('['))
+                    .map(str::trim)
+                    .filter(|name| !name.is_empty() && !name.contains(' '))
+                {
+                    current = Some(Block {
+                        name: name.to_string(),
+                        lines: Vec::new(),
+                    });
+                }
+            }
+            Some(_) if line == "}" || line == "]" => blocks.extend(current.take()),
+            Some(block) => block
+                .lines
+                .push(line.strip_prefix("  ").unwrap_or(line).to_string()),
+        }
+    }
+    blocks
+}
+
+// `key: value` lines of a block, `~` marking disabled ones
+fn pairs(blocks: &[Block], name: &str) -> Vec<KeyValue> {
+    if TEXT_BLOCKS.contains(&name) {
+        return Vec::new();
+    }
+    blocks
+        .iter()
+        .filter(|b| b.name == name)
+        .flat_map(|b| &b.lines)
+        .filter_map(|line| {
+            let (key, value) = line.split_once(':')?;
+            let key = key.trim();
+            let (key, enabled) = match key.strip_prefix('~') {
+                Some(key) => (key, false),
+                None => (key, true),
+            };
+            let key = key.trim_matches('"');
+            (!key.is_empty()).then(|| KeyValue {
+                enabled,
+                ..key_value(key, value.trim().to_string())
+            })
+        })
+        .collect()
+}
+
+fn text(blocks: &[Block], name: &str) -> String {
+    blocks
+        .iter()
+        .find(|b| b.name == name)
+        .map(|b| b.lines.join("\n"))
+        .unwrap_or_default()
+}
+
+fn meta(blocks: &[Block], name: &str) -> Option<String> {
+    pairs(blocks, "meta")
+        .into_iter()
+        .find(|pair| pair.key == name)
+        .map(|pair| pair.value)
+        .filter(|value| !value.is_empty())
+}
+
+fn seq(blocks: &[Block]) -> Option<f64> {
+    meta(blocks, "seq").and_then(|seq| seq.parse().ok())
+}
+
+// Request headers replace inherited ones with the same name
+fn merge(inherited: &[KeyValue], own: Vec<KeyValue>) -> Vec<KeyValue> {
+    let mut headers: Vec<KeyValue> = inherited
+        .iter()
+        .filter(|h| !own.iter().any(|o| o.key.eq_ignore_ascii_case(&h.key)))
+        .cloned()
+        .collect();
+    headers.extend(own);
+    headers
+}
+
+fn variable(key: String, value: String, enabled: bool, secret: bool) -> EnvironmentVariable {
+    EnvironmentVariable {
+        key,
+        value,
+        enabled,
+        secret,
+    }
+}
+
+fn join(parent: &str, name: &str) -> String {
+    match parent {
+        "" => name.to_string(),
+        _ => format!("{} / {}", parent, name),
+    }
+}
+
+fn file_stem(path: &Path) -> String {
+    path.file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const REQUEST: &str = r#"meta {
+  name: Get posts
+  type: http
+  seq: 2
+}
+
+get {
+  url: {{host}}/users/:id/posts/:postId?expand=1
+  body: multipartForm
+  auth: inherit
+}
+
+params:query {
+  expand: 1
+  ~debug: true
+}
+
+params:path {
+  id: 42
+  postId: 7
+}
+
+headers {
+  Accept: application/json
+  ~X-Debug: 1
+}
+
+body:multipart-form {
+  note: hello
+  avatar: @file(images/a.png)
+  docs: @file(a.pdf|b.pdf)
+  ~old: @file(old.txt)
+}
+
+body:json {
+  {
+    "key": "value: with a colon"
+  }
+}
+
+vars:secret [
+  token,
+  ~legacy
+]
+"#;
+
+    fn reader() -> Reader {
+        Reader {
+            root: PathBuf::from("/collection"),
+            imported: Imported::default(),
+            variables: Vec::new(),
+        }
+    }
+
+    fn entries(pairs: &[KeyValue]) -> Vec<(&str, &str, bool)> {
+        pairs
+            .iter()
+            .map(|p| (p.key.as_str(), p.value.as_str(), p.enabled))
+            .collect()
+    }
+
+    #[test]
+    fn blocks() {
+        let blocks = parse_blocks(REQUEST);
+        let names: Vec<&str> = blocks.iter().map(|b| b.name.as_str()).collect();
+        assert_eq!(
+            names,
+            [
+                "meta",
+                "get",
+                "params:query",
+                "params:path",
+                "headers",
+                "body:multipart-form",
+                "body:json",
+                "vars:secret",
+            ]
+        );
+        assert_eq!(meta(&blocks, "name").as_deref(), Some("Get posts"));
+        assert_eq!(seq(&blocks), Some(2.0));
+        // Text blocks keep their lines, colons included, and have no pairs
+        assert_eq!(
+            text(&blocks, "body:json"),
+            "{\n  \"key\": \"value: with a colon\"\n}"
+        );
+        assert!(pairs(&blocks, "body:json").is_empty());
+        assert_eq!(blocks[7].lines, ["token,", "~legacy"]);
+    }
+
+    #[test]
+    fn tilde_disables_pairs() {
+        let blocks = parse_blocks(REQUEST);
+        assert_eq!(
+            entries(&pairs(&blocks, "params:query")),
+            [("expand", "1", true), ("debug", "true", false)]
+        );
+        assert_eq!(
+            entries(&pairs(&blocks, "headers")),
+            [
+                ("Accept", "application/json", true),
+                ("X-Debug", "1", false)
+            ]
+        );
+    }
+
+    #[test]
+    fn request() {
+        let mut reader = reader();
+        let defaults = Defaults {
+            headers: vec![key_value("Accept", "*/*".to_string())],
+            auth: RequestAuth::Bearer {
+                token: "{{token}}".to_string(),
+            },
+        };
+        let state = reader.request(&parse_blocks(REQUEST), &defaults, "Users / Get posts");
+
+        assert_eq!(state.method, "GET");
+        assert_eq!(state.url, "{{host}}/users/{{id}}/posts/{{postId}}?expand=1");
+        let variables: Vec<_> = reader
+            .variables
+            .iter()
+            .map(|v| (v.key.as_str(), v.value.as_str()))
+            .collect();
+        assert_eq!(variables, [("id", "42"), ("postId", "7")]);
+        // The request's own header replaces the inherited one
+        assert_eq!(
+            entries(&state.headers),
+            [
+                ("Accept", "application/json", true),
+                ("X-Debug", "1", false)
+            ]
+        );
+        assert_eq!(state.auth, defaults.auth);
+
+        assert_eq!(state.body_type, "form-data");
+        let form: Vec<FormField> = serde_json::from_str(&state.body_content).unwrap();
+        let fields: Vec<_> = form
+            .iter()
+            .map(|f| (f.key.as_str(), f.value.clone(), f.enabled, f.field_type))
+            .collect();
+        let file = |path: &str| {
+            Path::new("/collection")
+                .join(path)
+                .to_string_lossy()
+                .to_string()
+        };
+        assert_eq!(
+            fields,
+            [
+                ("note", "hello".to_string(), true, FormFieldType::Text),
+                ("avatar", file("images/a.png"), true, FormFieldType::File),
+                ("docs", file("a.pdf"), true, FormFieldType::File),
+                ("old", file("old.txt"), false, FormFieldType::File),
+            ]
+        );
+
+        let skipped: Vec<_> = reader
+            .imported
+            .skipped
+            .iter()
+            .map(|i| (i.location.as_str(), i.message.as_str()))
+            .collect();
+        assert_eq!(
+            skipped,
+            [(
+                "Users / Get posts",
+                "All but the first file of form field \"docs\""
+            )]
+        );
+    }
+
+    #[test]
+    fn path_parameters_only_replace_whole_segments() {
+        let mut reader = reader();
+        let blocks = parse_blocks(
+            "post {\n  url: https://example.com/:id/:identity#:id\n}\n\nparams:path {\n  id: 1\n}\n",
+        );
+        let defaults = Defaults {
+            headers: Vec::new(),
+            auth: RequestAuth::None,
+        };
+        let state = reader.request(&blocks, &defaults, "Create");
+        assert_eq!(state.method, "POST");
+        assert_eq!(state.url, "https://example.com/{{id}}/:identity#:id");
+    }
+}
@@ -0,0 +1,866 @@
+use serde::Serialize;
+
+use crate::db::request::{FormField, FormFieldType, KeyValue, RequestAuth};
+use crate::db::tab::TabState;
+
+use super::{form_body, key_value, query_params, ImportIssue};
+
+// Options whose value is consumed but not reflected in the imported request
+const IGNORED_WITH_VALUE: &[&str] = &[
+    "cacert",
+    "capath",
+    "cert",
+    "cert-type",
+    "ciphers",
+    "config",
+    "connect-to",
+    "cookie-jar",
+    "dump-header",
+    "interface",
+    "key",
+    "key-type",
+    "limit-rate",
+    "noproxy",
+    "output",
+    "pass",
+    "pinnedpubkey",
+    "proxy",
+    "proxy-header",
+    "proxy-user",
+    "resolve",
+    "retry",
+    "retry-delay",
+    "retry-max-time",
+    "stderr",
+    "tls-max",
+    "trace",
+    "trace-ascii",
+    "unix-socket",
+    "write-out",
+];
+
+#[derive(Default)]
+struct Parsed {
+    method: Option<String>,
+    url: Option<String>,
+    headers: Vec<KeyValue>,
+    data: Vec<String>,
+    data_file: Option<String>,
+    json: bool,
+    form: Vec<FormField>,
+    user: Option<String>,
+    digest: bool,
+    bearer: Option<String>,
+    get: bool,
+    head: bool,
+    upload_file: Option<String>,
+    /// From --url-query, appended to the URL whether or not -G is given
+    query: Vec<String>,
+    state: TabState,
+    skipped: Vec<ImportIssue>,
+}
+
+/// A curl command as the state of a new tab, and the options it could not carry over.
+#[derive(Debug, Clone, Serialize)]
+pub struct CurlImport {
+    pub state: TabState,
+    pub skipped: Vec<ImportIssue>,
+}
+
+/// Turns a curl invocation, as copied from API docs or browser devtools, into the state
+/// of a new tab. TLS options such as `-k` are accepted but not applied, since TLS is
+/// configured per host; `-k` is reported in `skipped`. `--compressed` is ignored.
+pub fn parse(command: &str) -> Result<CurlImport, String> {
+    let mut args = tokenize(command)?.into_iter();
+
+    match args.next() {
+        Some(program)
+            if program == "curl" || program.ends_with("/curl") || program == "curl.exe" => {}
+        _ => return Err("Not a curl command".to_string()),
+    }
+
+    let mut parsed = Parsed::default();
+    // Unlike the app, curl stays on the first response unless given -L
+    parsed.state.settings.redirect.follow = false;
+    let mut options_done = false;
+
+    while let Some(arg) = args.next() {
+        if options_done || !arg.starts_with('-') || arg == "-" {
+            parsed.url.get_or_insert(arg);
+            continue;
+        }
+        if arg == "--" {
+            options_done = true;
+            continue;
+        }
+
+        if let Some(name) = arg.strip_prefix("--") {
+            if takes_value(name) {
+                let value = args
+                    .next()
+                    .ok_or_else(|| format!("Missing value for --{}", name))?;
+                apply_option(&mut parsed, name, value)?;
+            } else {
+                apply_flag(&mut parsed, name);
+            }
+            continue;
+        }
+
+        // Short options can be combined (`-sSL`) and take their value attached or separately
+        let flags = &arg[1..];
+        for (i, flag) in flags.char_indices() {
+            let Some(name) = short_name(flag) else {
+                continue;
+            };
+            if !takes_value(name) {
+                apply_flag(&mut parsed, name);
+                continue;
+            }
+
+            let attached = &flags[i + flag.len_utf8()..];
+            let value = if attached.is_empty() {
+                args.next()
+                    .ok_or_else(|| format!("Missing value for -{}", flag))?
+            } else {
+                attached.to_string()
+            };
+            apply_option(&mut parsed, name, value)?;
+            break;
+        }
+    }
+
+    finish(parsed)
+}
+
+fn short_name(flag: char) -> Option<&'static str> {
+    Some(match flag {
+        'X' => "request",
+        'H' => "header",
+        'd' => "data",
+        'F' => "form",
+        'u' => "user",
+        'k' => "insecure",
+        'L' => "location",
+        'G' => "get",
+        'I' => "head",
+        'A' => "user-agent",
+        'e' => "referer",
+        'b' => "cookie",
+        'T' => "upload-file",
+        'm' => "max-time",
+        'r' => "range",
+        'o' => "output",
+        'x' => "proxy",
+        'w' => "write-out",
+        'E' => "cert",
+        'c' => "cookie-jar",
+        'K' => "config",
+        'U' => "proxy-user",
+        'D' => "dump-header",
+        _ => return None,
+    })
+}
+
+// Unknown options are assumed to be flags
+fn takes_value(name: &str) -> bool {
+    matches!(
+        name,
+        "request"
+            | "header"
+            | "data"
+            | "data-ascii"
+            | "data-binary"
+            | "data-raw"
+            | "data-urlencode"
+            | "json"
+            | "form"
+            | "form-string"
+            | "user"
+            | "user-agent"
+            | "referer"
+            | "cookie"
+            | "url"
+            | "url-query"
+            | "upload-file"
+            | "max-time"
+            | "connect-timeout"
+            | "max-redirs"
+            | "oauth2-bearer"
+            | "range"
+    ) || IGNORED_WITH_VALUE.contains(&name)
+}
+
+fn apply_flag(parsed: &mut Parsed, name: &str) {
+    let redirect = &mut parsed.state.settings.redirect;
+    match name {
+        "location" => redirect.follow = true,
+        // Sends the credentials to every host it is redirected to
+        "location-trusted" => {
+            redirect.follow = true;
+            redirect.strip_auth_cross_origin = false;
+        }
+        "get" => parsed.get = true,
+        "head" => parsed.head = true,
+        "digest" => parsed.digest = true,
+        "insecure" => skip(
+            parsed,
+            "-k",
+            "Certificate checks stay on; turn them off for the host in its TLS settings",
+        ),
+        _ => {}
+    }
+}
+
+fn skip(parsed: &mut Parsed, option: &str, message: &str) {
+    if !parsed.skipped.iter().any(|issue| issue.location == option) {
+        parsed.skipped.push(ImportIssue {
+            location: option.to_string(),
+            message: message.to_string(),
+        });
+    }
+}
+
+fn apply_option(parsed: &mut Parsed, name: &str, value: String) -> Result<(), String> {
+    match name {
+        "request" => parsed.method = Some(value.to_uppercase()),
+        "header" => {
+            if let Some(header) = parse_header(&value) {
+                parsed.headers.push(header);
+            }
+        }
+        "data" | "data-ascii" | "data-binary" => match value.strip_prefix('@') {
+            Some(path) => parsed.data_file = Some(path.to_string()),
+            None => parsed.data.push(value),
+        },
+        "data-raw" => parsed.data.push(value),
+        "data-urlencode" => parsed.data.push(url_encode_data(&value)),
+        "json" => {
+            parsed.json = true;
+            match value.strip_prefix('@') {
+                Some(path) => parsed.data_file = Some(path.to_string()),
+                None => parsed.data.push(value),
+            }
+        }
+        "form" => parsed.form.push(parse_form_field(&value, false)?),
+        "form-string" => parsed.form.push(parse_form_field(&value, true)?),
+        "user" => parsed.user = Some(value),
+        "oauth2-bearer" => parsed.bearer = Some(value),
+        "user-agent" => parsed.headers.push(key_value("User-Agent", value)),
+        "referer" => parsed.headers.push(key_value("Referer", value)),
+        // Without `=` the value names a cookie file, which cannot be imported
+        "cookie" if value.contains('=') => parsed.headers.push(key_value("Cookie", value)),
+        "range" => parsed
+            .headers
+            .push(key_value("Range", format!("bytes={}", value))),
+        "url" => {
+            parsed.url.get_or_insert(value);
+        }
+        "url-query" => parsed.query.push(value),
+        "upload-file" => parsed.upload_file = Some(value),
+        "max-time" => parsed.state.settings.timeouts.total_ms = Some(seconds_to_ms(&value)?),
+        "connect-timeout" => {
+            parsed.state.settings.timeouts.connect_ms = Some(seconds_to_ms(&value)?)
+        }
+        "max-redirs" => {
+            // -1 means unlimited in curl
+            parsed.state.settings.redirect.max_redirects = match value.trim().parse::<i64>() {
+                Ok(max) if max < 0 => u32::MAX,
+                Ok(max) => max.try_into().unwrap_or(u32::MAX),
+                Err(_) => return Err(format!("Invalid --max-redirs value: {}", value)),
+            };
+        }
+        _ => {}
+    }
+
+    Ok(())
+}
+
+fn finish(parsed: Parsed) -> Result<CurlImport, String> {
+    let Parsed {
+        method,
+        url,
+        mut headers,
+        mut data,
+        data_file,
+        json,
+        form,
+        user,
+        digest,
+        bearer,
+        get,
+        head,
+        upload_file,
+        mut query,
+        mut state,
+        skipped,
+    } = parsed;
+
+    let mut url = url.ok_or("No URL found in the curl command")?;
+    // curl assumes http:// when the scheme is left out
+    if !url.contains("://") && !url.starts_with("{{") {
+        url = format!("http://{}", url);
+    }
+
+    // -G sends the data as query parameters instead of a body
+    if get {
+        query.append(&mut data);
+    }
+    if !query.is_empty() {
+        let separator = if url.contains('?') { '&' } else { '?' };
+        url = format!("{}{}{}", url, separator, query.join("&"));
+    }
+
+    if json {
+        for (name, value) in [
+            ("Content-Type", "application/json"),
+            ("Accept", "application/json"),
+        ] {
+            if !headers.iter().any(|h| h.key.eq_ignore_ascii_case(name)) {
+                headers.push(key_value(name, value.to_string()));
+            }
+        }
+    }
+
+    let upload = upload_file.is_some();
+    let has_body = upload || data_file.is_some() || !form.is_empty() || !data.is_empty();
+    if !form.is_empty() {
+        state.body_type = "form-data".to_string();
+        state.body_content = form_body(form);
+    } else if let Some(path) = upload_file.or(data_file) {
+        state.body_type = "binary".to_string();
+        state.body_content = path;
+    } else if !data.is_empty() {
+        let content_type = headers
+            .iter()
+            .find(|h| h.key.eq_ignore_ascii_case("content-type"))
+            .map(|h| h.value.to_ascii_lowercase());
+        // curl sends -d data as a form unless another content type is given
+        state.body_type = match content_type {
+            Some(ct) if !ct.contains("application/x-www-form-urlencoded") => "raw",
+            _ => "x-www-form-urlencoded",
+        }
+        .to_string();
+        state.body_content = data.join("&");
+    }
+
+    state.method = match method {
+        Some(method) => method,
+        None if head => "HEAD".to_string(),
+        None if upload => "PUT".to_string(),
+        None if has_body => "POST".to_string(),
+        None => "GET".to_string(),
+    };
+
+    state.auth = match (user, bearer) {
+        (Some(user), _) => {
+            let (username, password) = user.split_once(':').unwrap_or((&user, ""));
+            let (username, password) = (username.to_string(), password.to_string());
+            if digest {
+                RequestAuth::Digest { username, password }
+            } else {
+                RequestAuth::Basic { username, password }
+            }
+        }
+        (None, Some(token)) => RequestAuth::Bearer { token },
+        (None, None) => RequestAuth::None,
+    };
+
+    state.params = query_params(&url);
+    state.url = url;
+    state.headers = headers;
+    Ok(CurlImport { state, skipped })
+}
+
+// Splits shell words the way bash would, including backslash-continued lines,
+// single and double quotes, and the `$'...'` quoting used by browser devtools.
+fn tokenize(command: &str) -> Result<Vec<String>, String> {
+    let mut args = Vec::new();
+    let mut current = String::new();
+    let mut in_word = false;
+    let mut chars = command.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => match chars.next() {
+                Some('\n') => {}
+                Some('\r') if chars.peek() == Some(&'\n') => {
+                    chars.next();
+                }
+                Some(escaped) => {
+                    current.push(escaped);
+                    in_word = true;
+                }
+                None => {}
+            },
+            // Line continuation of commands copied for the Windows command prompt
+            '^' if matches!(chars.peek(), Some('\n' | '\r')) => {
+                while matches!(chars.peek(), Some('\n' | '\r')) {
+                    chars.next();
+                }
+            }
+            '\'' => {
+                in_word = true;
+                loop {
+                    match chars.next() {
+                        Some('\'') => break,
+                        Some(c) => current.push(c),
+                        None => return Err("Unterminated single quote".to_string()),
+                    }
+                }
+            }
+            '"' => {
+                in_word = true;
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some(c @ ('"' | '\\' | '$' | '`')) => current.push(c),
+                            Some('\n') => {}
+                            Some(c) => {
+                                current.push('\\');
+                                current.push(c);
+                            }
+                            None => return Err("Unterminated double quote".to_string()),
+                        },
+                        Some(c) => current.push(c),
+                        None => return Err("Unterminated double quote".to_string()),
+                    }
+                }
+            }
+            '$' if chars.peek() == Some(&'\'') => {
+                chars.next();
+                in_word = true;
+                ansi_c_quoted(&mut chars, &mut current)?;
+            }
+            c if c.is_whitespace() => {
+                if in_word {
+                    args.push(std::mem::take(&mut current));
+                    in_word = false;
+                }
+            }
+            c => {
+                current.push(c);
+                in_word = true;
+            }
+        }
+    }
+
+    if in_word {
+        args.push(current);
+    }
+    Ok(args)
+}
+
+fn ansi_c_quoted(
+    chars: &mut std::iter::Peekable<std::str::Chars<'_>>,
+    out: &mut String,
+) -> Result<(), String> {
+    loop {
+        let c = chars.next().ok_or("Unterminated $'...' string")?;
+        match c {
+            '\'' => return Ok(()),
+            '\\' => {
+                let escaped = chars.next().ok_or("Unterminated $'...' string")?;
+                match escaped {
+                    'n' => out.push('\n'),
+                    't' => out.push('\t'),
+                    'r' => out.push('\r'),
+                    'e' | 'E' => out.push('\u{1b}'),
+                    '0' => out.push('\0'),
+                    'x' => out.push(hex_escape(chars, 2)?),
+                    'u' => out.push(hex_escape(chars, 4)?),
+                    'U' => out.push(hex_escape(chars, 8)?),
+                    other => out.push(other),
+                }
+            }
+            c => out.push(c),
+        }
+    }
+}
+
+fn hex_escape(
+    chars: &mut std::iter::Peekable<std::str::Chars<'_>>,
+    max_digits: usize,
+) -> Result<char, String> {
+    let mut digits = String::new();
+    while digits.len() < max_digits {
+        match chars.peek() {
+            Some(c) if c.is_ascii_hexdigit() => digits.push(*c),
+            _ => break,
+        }
+        chars.next();
+    }
+    u32::from_str_radix(&digits, 16)
+        .ok()
+        .and_then(char::from_u32)
+        .ok_or_else(|| format!("Invalid escape sequence: \\{}", digits))
+}
+
+// `Name: value`, or `Name;` for an empty value. `Name:` alone removes a header in curl.
+fn parse_header(value: &str) -> Option<KeyValue> {
+    if let Some((name, value)) = value.split_once(':') {
+        let value = value.trim();
+        return (!name.trim().is_empty() && !value.is_empty())
+            .then(|| key_value(name.trim(), value.to_string()));
+    }
+    value
+        .strip_suffix(';')
+        .filter(|name| !name.trim().is_empty())
+        .map(|name| key_value(name.trim(), String::new()))
+}
+
+// `content`, `=content` and `name=content` forms of --data-urlencode
+fn url_encode_data(value: &str) -> String {
+    let encode = |s: &str| url::form_urlencoded::byte_serialize(s.as_bytes()).collect::<String>();
+    match value.split_once('=') {
+        Some(("", content)) => encode(content),
+        Some((name, content)) => format!("{}={}", name, encode(content)),
+        None => encode(value),
+    }
+}
+
+// `name=value`, `name=@path;type=...;filename=...` or `name=<path`
+fn parse_form_field(value: &str, literal: bool) -> Result<FormField, String> {
+    let (key, content) = value
+        .split_once('=')
+        .ok_or_else(|| format!("Invalid form field: {}", value))?;
+    let mut field = FormField {
+        key: key.to_string(),
+        value: content.to_string(),
+        enabled: true,
+        description: None,
+        field_type: FormFieldType::Text,
+        file_name: None,
+        content_type: None,
+    };
+    if literal {
+        return Ok(field);
+    }
+
+    let Some(path) = content
+        .strip_prefix('@')
+        .or_else(|| content.strip_prefix('<'))
+    else {
+        return Ok(field);
+    };
+    let mut parts = path.split(';');
+    field.value = parts.next().unwrap_or_default().to_string();
+    field.field_type = FormFieldType::File;
+    for part in parts {
+        match part.split_once('=') {
+            Some(("type", content_type)) => field.content_type = Some(content_type.to_string()),
+            Some(("filename", name)) => field.file_name = Some(name.trim_matches('"').to_string()),
+            _ => {}
+        }
+    }
+    Ok(field)
+}
+
+fn seconds_to_ms(value: &str) -> Result<u64, String> {
+    value
+        .trim()
+        .parse::<f64>()
+        .ok()
+        .filter(|seconds| *seconds >= 0.0)
+        .map(|seconds| (seconds * 1000.0).round() as u64)
+        .ok_or_else(|| format!("Invalid timeout: {}", value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn header_value<'a>(state: &'a TabState, name: &str) -> Option<&'a str> {
+        state
+            .headers
+            .iter()
+            .find(|h| h.key.eq_ignore_ascii_case(name))
+            .map(|h| h.value.as_str())
+    }
+
+    fn param_pairs(state: &TabState) -> Vec<(&str, &str)> {
+        state
+            .params
+            .iter()
+            .map(|p| (p.key.as_str(), p.value.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn chrome_copy_as_curl() {
+        let state = parse(
+            r#"curl 'https://api.example.com/v1/items?page=2&sort=desc' \
+  -H 'accept: application/json, text/plain, */*' \
+  -H 'accept-language: en-US,en;q=0.9' \
+  -H 'authorization: Bearer eyJhbGciOi' \
+  -H 'content-type: application/json' \
+  -H $'cookie: session=abc; theme=dark' \
+  --data-raw $'{"name":"O\'Brien","tags":["a","b"]}' \
+  --compressed"#,
+        )
+        .unwrap()
+        .state;
+
+        assert_eq!(state.method, "POST");
+        assert_eq!(
+            state.url,
+            "https://api.example.com/v1/items?page=2&sort=desc"
+        );
+        assert_eq!(param_pairs(&state), [("page", "2"), ("sort", "desc")]);
+        assert_eq!(state.headers.len(), 5);
+        assert_eq!(
+            header_value(&state, "accept"),
+            Some("application/json, text/plain, */*")
+        );
+        assert_eq!(
+            header_value(&state, "cookie"),
+            Some("session=abc; theme=dark")
+        );
+        assert_eq!(state.body_type, "raw");
+        assert_eq!(state.body_content, r#"{"name":"O'Brien","tags":["a","b"]}"#);
+        assert!(matches!(state.auth, RequestAuth::None));
+        // Without -L curl does not follow redirects
+        assert!(!state.settings.redirect.follow);
+    }
+
+    #[test]
+    fn firefox_form_post() {
+        let state = parse(
+            "curl 'https://example.com/api/login' -X POST -H 'User-Agent: Mozilla/5.0 (X11; Linux x86_64)' \
+             -H 'Content-Type: application/x-www-form-urlencoded' --data-raw 'user=jo&pass=s%26cret'",
+        )
+        .unwrap()
+        .state;
+
+        assert_eq!(state.method, "POST");
+        assert_eq!(
+            header_value(&state, "user-agent"),
+            Some("Mozilla/5.0 (X11; Linux x86_64)")
+        );
+        assert_eq!(state.body_type, "x-www-form-urlencoded");
+        assert_eq!(state.body_content, "user=jo&pass=s%26cret");
+    }
+
+    #[test]
+    fn stripe_docs_basic_auth_and_repeated_data() {
+        let state = parse(
+            r#"curl https://api.stripe.com/v1/charges \
+  -u sk_test_4eC39HqLyjWDarjtT1zdp7dc: \
+  -d amount=2000 \
+  -d currency=usd \
+  -d "description=My First Test Charge (created for API docs)""#,
+        )
+        .unwrap()
+        .state;
+
+        assert_eq!(state.method, "POST");
+        assert_eq!(state.body_type, "x-www-form-urlencoded");
+        assert_eq!(
+            state.body_content,
+            "amount=2000&currency=usd&description=My First Test Charge (created for API docs)"
+        );
+        match state.auth {
+            RequestAuth::Basic { username, password } => {
+                assert_eq!(username, "sk_test_4eC39HqLyjWDarjtT1zdp7dc");
+                assert_eq!(password, "");
+            }
+            other => panic!("expected basic auth, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn github_docs_get_with_redirects() {
+        let state = parse(
+            r#"curl -L \
+  -H "Accept: application/vnd.github+json" \
+  -H "Authorization: Bearer <YOUR-TOKEN>" \
+  -H "X-GitHub-Api-Version: 2022-11-28" \
+  https://api.github.com/repos/OWNER/REPO/issues"#,
+        )
+        .unwrap()
+        .state;
+
+        assert_eq!(state.method, "GET");
+        assert_eq!(state.url, "https://api.github.com/repos/OWNER/REPO/issues");
+        assert_eq!(state.headers.len(), 3);
+        assert_eq!(state.body_type, "none");
+        assert!(state.settings.redirect.follow);
+    }
+
+    #[test]
+    fn multipart_upload() {
+        let state = parse(
+            r#"curl -F "file=@/tmp/photo.png;type=image/png;filename=me.png" -F 'caption=hello world' \
+  https://upload.example.com/media"#,
+        )
+        .unwrap()
+        .state;
+
+        assert_eq!(state.method, "POST");
+        assert_eq!(state.body_type, "form-data");
+        let fields: Vec<FormField> = serde_json::from_str(&state.body_content).unwrap();
+        assert_eq!(fields.len(), 2);
+        assert_eq!(fields[0].key, "file");
+        assert_eq!(fields[0].value, "/tmp/photo.png");
+        assert_eq!(fields[0].field_type, FormFieldType::File);
+        assert_eq!(fields[0].content_type.as_deref(), Some("image/png"));
+        assert_eq!(fields[0].file_name.as_deref(), Some("me.png"));
+        assert_eq!(fields[1].value, "hello world");
+        assert_eq!(fields[1].field_type, FormFieldType::Text);
+    }
+
+    #[test]
+    fn text_only_form_stays_url_encoded() {
+        let state = parse("curl -F a=1 --form-string 'b=@literal' https://x.test/")
+            .unwrap()
+            .state;
+
+        assert_eq!(state.body_type, "form-data");
+        assert_eq!(state.body_content, "a=1&b=%40literal");
+    }
+
+    #[test]
+    fn get_moves_data_to_query() {
+        let state = parse(
+            r#"curl -G https://api.example.com/search --data-urlencode "q=rust lang" -d limit=5"#,
+        )
+        .unwrap()
+        .state;
+
+        assert_eq!(state.method, "GET");
+        assert_eq!(
+            state.url,
+            "https://api.example.com/search?q=rust+lang&limit=5"
+        );
+        assert_eq!(param_pairs(&state), [("q", "rust lang"), ("limit", "5")]);
+        assert_eq!(state.body_type, "none");
+    }
+
+    #[test]
+    fn combined_short_flags_and_attached_values() {
+        let CurlImport { state, skipped } =
+            parse("curl -sSLkX PUT -H'X-Trace: 1' -d@payload.json example.com:8080/things/1")
+                .unwrap();
+
+        assert_eq!(state.method, "PUT");
+        assert_eq!(state.url, "http://example.com:8080/things/1");
+        assert_eq!(header_value(&state, "x-trace"), Some("1"));
+        assert_eq!(state.body_type, "binary");
+        assert_eq!(state.body_content, "payload.json");
+        assert!(state.settings.redirect.follow);
+        assert!(state.settings.redirect.strip_auth_cross_origin);
+        assert_eq!(skipped.len(), 1);
+        assert_eq!(skipped[0].location, "-k");
+    }
+
+    #[test]
+    fn location_trusted_keeps_credentials_on_redirects() {
+        let CurlImport { state, skipped } =
+            parse("curl --location-trusted -u admin:secret https://x.test/files").unwrap();
+
+        assert!(state.settings.redirect.follow);
+        assert!(!state.settings.redirect.strip_auth_cross_origin);
+        assert!(skipped.is_empty());
+    }
+
+    #[test]
+    fn digest_head_and_timeouts() {
+        let state = parse(
+            "curl --digest -u 'admin:p@ss:word' --max-time 2.5 --connect-timeout 1 -I http://localhost/status",
+        )
+        .unwrap()
+        .state;
+
+        assert_eq!(state.method, "HEAD");
+        assert_eq!(state.settings.timeouts.total_ms, Some(2500));
+        assert_eq!(state.settings.timeouts.connect_ms, Some(1000));
+        match state.auth {
+            RequestAuth::Digest { username, password } => {
+                assert_eq!(username, "admin");
+                assert_eq!(password, "p@ss:word");
+            }
+            other => panic!("expected digest auth, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn json_option_sets_headers() {
+        let state = parse(r#"curl --json '{"a":1}' https://x.test/api"#)
+            .unwrap()
+            .state;
+
+        assert_eq!(state.method, "POST");
+        assert_eq!(state.body_type, "raw");
+        assert_eq!(state.body_content, r#"{"a":1}"#);
+        assert_eq!(
+            header_value(&state, "content-type"),
+            Some("application/json")
+        );
+        assert_eq!(header_value(&state, "accept"), Some("application/json"));
+    }
+
+    #[test]
+    fn upload_file_puts() {
+        let state = parse("curl -T ./backup.tar.gz https://files.example.com/backups/")
+            .unwrap()
+            .state;
+
+        assert_eq!(state.method, "PUT");
+        assert_eq!(state.body_type, "binary");
+        assert_eq!(state.body_content, "./backup.tar.gz");
+    }
+
+    #[test]
+    fn double_quote_escapes_and_ignored_options() {
+        let state = parse(
+            r#"curl -o out.json -w "%{http_code}" "https://x.test/q?a=\"b\"" -H "X-Cost: \$5" -H 'Accept:' -H 'X-Empty;'"#,
+        )
+        .unwrap()
+        .state;
+
+        assert_eq!(state.url, r#"https://x.test/q?a="b""#);
+        assert_eq!(header_value(&state, "x-cost"), Some("$5"));
+        assert_eq!(header_value(&state, "accept"), None);
+        assert_eq!(header_value(&state, "x-empty"), Some(""));
+    }
+
+    #[test]
+    fn windows_line_continuation() {
+        let state = parse("curl \"https://x.test/\" ^\r\n  -H \"Accept: */*\" ^\r\n  --compressed")
+            .unwrap()
+            .state;
+
+        assert_eq!(state.url, "https://x.test/");
+        assert_eq!(header_value(&state, "accept"), Some("*/*"));
+    }
+
+    #[test]
+    fn keeps_variables() {
+        let state = parse("curl {{baseUrl}}/users -H 'Authorization: Bearer {{token}}'")
+            .unwrap()
+            .state;
+
+        assert_eq!(state.url, "{{baseUrl}}/users");
+        assert_eq!(
+            header_value(&state, "authorization"),
+            Some("Bearer {{token}}")
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_commands() {
+        assert_eq!(
+            parse("wget http://x.test").unwrap_err(),
+            "Not a curl command"
+        );
+        assert_eq!(
+            parse("curl -H 'Accept: */*'").unwrap_err(),
+            "No URL found in the curl command"
+        );
+        assert_eq!(
+            parse("curl 'https://x.test").unwrap_err(),
+            "Unterminated single quote"
+        );
+        assert_eq!(parse("curl -X").unwrap_err(), "Missing value for -X");
+    }
+}
@@ -0,0 +1,549 @@
+use base64::Engine;
+use cookie::time::format_description::well_known::Rfc3339;
+use cookie::time::OffsetDateTime;
+use serde_json::Value;
+
+use crate::db::import::ImportBatch;
+use crate::db::request::{
+    extract_path, CreateRequestInput, FormField, FormFieldType, KeyValue, SavedRequest,
+};
+use crate::db::tab::TabState;
+use crate::db::workspace::Workspace;
+use crate::db::Database;
+use crate::net::connection::ConnectionInfo;
+use crate::net::timing::Timings;
+
+use super::{
+    form_body, key_value, query_params, string, ImportIssue, ImportReport, Imported,
+    ImportedCollection, ImportedItem,
+};
+
+// Set again by the client for the URL and body it sends
+const DERIVED_HEADERS: [&str; 2] = ["host", "content-length"];
+
+/// The requests of a HAR 1.2 archive, oldest first, ready to be added to history.
+#[derive(Debug)]
+pub struct Har {
+    /// Title of the first page, or the name of the tool that wrote the archive
+    pub name: String,
+    /// Entries with an empty `workspace_id`, filled in when they are stored
+    pub entries: Vec<CreateRequestInput>,
+    pub skipped: Vec<ImportIssue>,
+}
+
+/// Reads a HAR archive as saved by browser devtools, proxies or [`crate::export::har`].
+pub fn parse(content: &str) -> Result<Har, String> {
+    let archive: Value = serde_json::from_str(content).map_err(|e| e.to_string())?;
+    let Some(entries) = archive["log"]["entries"].as_array() else {
+        return Err("Not a HAR archive".to_string());
+    };
+
+    let name = [
+        &archive["log"]["pages"][0]["title"],
+        &archive["log"]["creator"]["name"],
+    ]
+    .into_iter()
+    .map(string)
+    .find(|name| !name.is_empty())
+    .unwrap_or_else(|| "HAR".to_string());
+
+    let mut har = Har {
+        name,
+        entries: Vec::new(),
+        skipped: Vec::new(),
+    };
+    for entry in entries {
+        if let Some(input) = har.entry(entry) {
+            har.entries.push(input);
+        }
+    }
+    har.entries.sort_by_key(|entry| entry.created_at);
+
+    Ok(har)
+}
+
+/// Adds the entries to a workspace's history, or to a new workspace named after the
+/// archive when `workspace_id` is `None`. Nothing is added when any entry fails.
+pub fn store(db: &Database, workspace_id: Option<&str>, har: Har) -> Result<ImportReport, String> {
+    let mut batch = ImportBatch::default();
+    let workspace_id = match workspace_id {
+        Some(id) => id.to_string(),
+        None => {
+            let workspace = Workspace::new(&har.name);
+            let id = workspace.id.clone();
+            batch.workspace = Some(workspace);
+            id
+        }
+    };
+
+    let history = har.entries.len();
+    for mut entry in har.entries {
+        entry.workspace_id = workspace_id.clone();
+        batch.history.push(SavedRequest::new(entry));
+    }
+    db.insert_import(&workspace_id, batch)?;
+
+    Ok(ImportReport {
+        workspace_id,
+        collection_ids: Vec::new(),
+        environment_ids: Vec::new(),
+        folders: 0,
+        requests: 0,
+        history,
+        skipped: har.skipped,
+    })
+}
+
+impl Har {
+    /// The requests as a single collection, without their responses.
+    pub fn into_collection(self) -> Imported {
+        let items = self
+            .entries
+            .into_iter()
+            .map(|entry| ImportedItem::Request {
+                name: format!("{} {}", entry.method, extract_path(&entry.url)),
+                state: Box::new(TabState {
+                    method: entry.method,
+                    url: entry.url,
+                    params: entry.params.unwrap_or_default(),
+                    headers: entry.headers.unwrap_or_default(),
+                    body_type: entry.body_type.unwrap_or_else(|| "none".to_string()),
+                    body_content: entry.body_content.unwrap_or_default(),
+                    settings: Default::default(),
+                    auth: Default::default(),
+                    is_dirty: false,
+                }),
+            })
+            .collect();
+
+        Imported {
+            name: self.name.clone(),
+            collections: vec![ImportedCollection {
+                name: self.name,
+                items,
+            }],
+            environments: Vec::new(),
+            skipped: self.skipped,
+        }
+    }
+
+    fn skip(&mut self, location: &str, message: impl Into<String>) {
+        self.skipped.push(ImportIssue {
+            location: location.to_string(),
+            message: message.into(),
+        });
+    }
+
+    fn entry(&mut self, entry: &Value) -> Option<CreateRequestInput> {
+        let request = &entry["request"];
+        let method = request["method"].as_str().unwrap_or("GET").to_uppercase();
+        let url = string(&request["url"]);
+        let location = format!("{} {}", method, url);
+        if !url.starts_with("http://") && !url.starts_with("https://") {
+            // data:, blob: and WebSocket entries have no request to replay
+            self.skip(&location, "Only HTTP(S) requests can be imported");
+            return None;
+        }
+
+        let mut headers = pairs(&request["headers"]);
+        if !has_header(&headers, "cookie") {
+            let cookies: Vec<String> = request["cookies"]
+                .as_array()
+                .map_or(&[][..], Vec::as_slice)
+                .iter()
+                .map(|c| format!("{}={}", string(&c["name"]), string(&c["value"])))
+                .collect();
+            if !cookies.is_empty() {
+                headers.push(key_value("Cookie", cookies.join("; ")));
+            }
+        }
+
+        let params = match request["queryString"].as_array() {
+            Some(query) if !query.is_empty() => query
+                .iter()
+                .map(|q| key_value(&string(&q["name"]), string(&q["value"])))
+                .collect(),
+            _ => query_params(&url),
+        };
+
+        let (body_type, body_content) = self.body(&request["postData"], &mut headers, &location);
+
+        let mut input = CreateRequestInput {
+            workspace_id: String::new(),
+            method,
+            url,
+            params: Some(params),
+            headers: Some(headers),
+            body_type: Some(body_type),
+            body_content: Some(body_content),
+            response_status: None,
+            response_status_text: None,
+            response_headers: None,
+            response_body: None,
+            response_body_encoding: None,
+            response_time_ms: None,
+            response_size_bytes: None,
+            response_timings: None,
+            response_redirects: None,
+            response_connection: None,
+            auth: None,
+            created_at: entry["startedDateTime"]
+                .as_str()
+                .and_then(parse_time)
+                .map(|started| (started.unix_timestamp_nanos() / 1_000_000) as i64),
+        };
+
+        // Browsers record blocked and failed requests with status 0
+        let response = &entry["response"];
+        let status = response["status"].as_i64().unwrap_or(0);
+        if status > 0 {
+            let mut response_headers = pairs(&response["headers"]);
+            if !has_header(&response_headers, "set-cookie") {
+                response_headers.extend(set_cookies(&response["cookies"]));
+            }
+
+            let content = &response["content"];
+            let text = string(&content["text"]);
+            // Binary bodies stay base64-encoded; text is stored as is
+            let (body, encoding) = if content["encoding"] != "base64" {
+                (text, None)
+            } else if let Some(decoded) = decoded(&text) {
+                (decoded, None)
+            } else {
+                (text, Some("base64".to_string()))
+            };
+            let size = [&content["size"], &response["bodySize"]]
+                .into_iter()
+                .filter_map(Value::as_i64)
+                .find(|size| *size >= 0);
+
+            let time = entry["time"].as_f64().unwrap_or(0.0).max(0.0);
+            let http_version = http_version(&string(&response["httpVersion"]));
+            let address = string(&entry["serverIPAddress"]);
+
+            input.response_status = Some(status as i32);
+            input.response_status_text = Some(string(&response["statusText"]));
+            input.response_headers = Some(response_headers);
+            input.response_size_bytes = Some(size.unwrap_or(body.len() as i64));
+            input.response_body = Some(body);
+            input.response_body_encoding = encoding;
+            input.response_time_ms = Some(time.round() as i64);
+            input.response_timings = timings(&entry["timings"], time);
+            input.response_redirects = serde_json::from_value(entry["_redirects"].clone()).ok();
+            if !address.is_empty() || !http_version.is_empty() {
+                input.response_connection = Some(ConnectionInfo {
+                    remote_address: Some(address).filter(|a| !a.is_empty()),
+                    http_version,
+                    tls: None,
+                });
+            }
+        }
+
+        Some(input)
+    }
+
+    fn body(
+        &mut self,
+        post: &Value,
+        headers: &mut Vec<KeyValue>,
+        location: &str,
+    ) -> (String, String) {
+        if !post.is_object() {
+            return ("none".to_string(), String::new());
+        }
+        let mime = string(&post["mimeType"]);
+        let mime = mime
+            .split(';')
+            .next()
+            .unwrap_or_default()
+            .trim()
+            .to_lowercase();
+        let params = post["params"].as_array().map_or(&[][..], Vec::as_slice);
+
+        let mut text = string(&post["text"]);
+        if post["encoding"] == "base64" {
+            match decoded(&text) {
+                Some(decoded) => text = decoded,
+                None => {
+                    self.skip(location, "Binary request body");
+                    return ("none".to_string(), String::new());
+                }
+            }
+        }
+
+        // The client sets its own Content-Type for these, with a fresh multipart boundary;
+        // JSON and other text stays raw, sent with the Content-Type it was captured with
+        let body_type = match mime.as_str() {
+            "multipart/form-data" if !params.is_empty() => "form-data",
+            "application/x-www-form-urlencoded" => "x-www-form-urlencoded",
+            _ => "raw",
+        };
+        if body_type != "raw" {
+            headers.retain(|h| !h.key.eq_ignore_ascii_case("content-type"));
+        }
+
+        let content = match body_type {
+            "form-data" => {
+                let mut form = Vec::new();
+                for param in params {
+                    let key = string(&param["name"]);
+                    let file_name = string(&param["fileName"]);
+                    let is_file = !file_name.is_empty();
+                    if is_file {
+                        self.skip(
+                            location,
+                            format!(
+                                "Contents of file \"{}\" in form field \"{}\"",
+                                file_name, key
+                            ),
+                        );
+                    }
+                    form.push(FormField {
+                        key,
+                        value: if is_file {
+                            String::new()
+                        } else {
+                            string(&param["value"])
+                        },
+                        enabled: true,
+                        description: None,
+                        field_type: if is_file {
+                            FormFieldType::File
+                        } else {
+                            FormFieldType::Text
+                        },
+                        file_name: Some(file_name).filter(|_| is_file),
+                        content_type: Some(string(&param["contentType"])).filter(|t| !t.is_empty()),
+                    });
+                }
+                form_body(form)
+            }
+            "x-www-form-urlencoded" if text.is_empty() => {
+                url::form_urlencoded::Serializer::new(String::new())
+                    .extend_pairs(
+                        params
+                            .iter()
+                            .map(|p| (string(&p["name"]), string(&p["value"]))),
+                    )
+                    .finish()
+            }
+            _ => text,
+        };
+
+        (body_type.to_string(), content)
+    }
+}
+
+fn pairs(value: &Value) -> Vec<KeyValue> {
+    value
+        .as_array()
+        .map_or(&[][..], Vec::as_slice)
+        .iter()
+        .map(|h| key_value(&string(&h["name"]), string(&h["value"])))
+        // HTTP/2 pseudo-headers such as `:authority` are part of the request line
+        .filter(|h| !h.key.starts_with(':'))
+        .filter(|h| !DERIVED_HEADERS.contains(&h.key.to_lowercase().as_str()))
+        .collect()
+}
+
+// Text of a base64-encoded body, or `None` when it is binary
+fn decoded(text: &str) -> Option<String> {
+    let bytes = base64::engine::general_purpose::STANDARD
+        .decode(text.trim())
+        .ok()?;
+    String::from_utf8(bytes).ok()
+}
+
+fn has_header(headers: &[KeyValue], name: &str) -> bool {
+    headers.iter().any(|h| h.key.eq_ignore_ascii_case(name))
+}
+
+// Some tools list response cookies without their Set-Cookie headers
+fn set_cookies(cookies: &Value) -> Vec<KeyValue> {
+    cookies
+        .as_array()
+        .map_or(&[][..], Vec::as_slice)
+        .iter()
+        .map(|c| {
+            let mut cookie = cookie::Cookie::new(string(&c["name"]), string(&c["value"]));
+            if let Some(path) = c["path"].as_str() {
+                cookie.set_path(path.to_string());
+            }
+            if let Some(domain) = c["domain"].as_str() {
+                cookie.set_domain(domain.to_string());
+            }
+            if let Some(expires) = c["expires"].as_str().and_then(parse_time) {
+                cookie.set_expires(expires);
+            }
+            cookie.set_http_only(c["httpOnly"].as_bool());
+            cookie.set_secure(c["secure"].as_bool());
+            key_value("Set-Cookie", cookie.to_string())
+        })
+        .collect()
+}
+
+fn timings(timings: &Value, time: f64) -> Option<Timings> {
+    if !timings.is_object() {
+        return None;
+    }
+    // -1 marks a phase that does not apply, such as DNS on a reused connection
+    let phase = |name: &str| timings[name].as_f64().filter(|ms| *ms >= 0.0);
+    let ssl = phase("ssl");
+
+    Some(Timings {
+        dns_ms: phase("dns"),
+        // HAR counts the TLS handshake as part of connecting
+        tcp_ms: phase("connect").map(|connect| (connect - ssl.unwrap_or(0.0)).max(0.0)),
+        tls_ms: ssl,
+        ttfb_ms: phase("send").unwrap_or(0.0) + phase("wait").unwrap_or(0.0),
+        download_ms: phase("receive").unwrap_or(0.0),
+        total_ms: time,
+    })
+}
+
+// Browsers write versions such as `http/2.0` or `h3`; history uses `HTTP/2`
+fn http_version(version: &str) -> String {
+    match version.to_lowercase().as_str() {
+        "" | "unknown" => String::new(),
+        "h2" | "http/2" | "http/2.0" => "HTTP/2".to_string(),
+        "h3" | "http/3" | "http/3.0" => "HTTP/3".to_string(),
+        other => other.to_uppercase(),
+    }
+}
+
+// HAR dates are RFC 3339; some tools leave out the offset, which is taken as UTC
+fn parse_time(value: &str) -> Option<OffsetDateTime> {
+    let value = value.trim();
+    OffsetDateTime::parse(value, &Rfc3339)
+        .or_else(|_| OffsetDateTime::parse(&format!("{}Z", value), &Rfc3339))
+        .ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn header_value<'a>(headers: &'a [KeyValue], name: &str) -> Option<&'a str> {
+        headers
+            .iter()
+            .find(|h| h.key.eq_ignore_ascii_case(name))
+            .map(|h| h.value.as_str())
+    }
+
+    fn saved_request() -> SavedRequest {
+        let input: CreateRequestInput = serde_json::from_value(serde_json::json!({
+            "workspace_id": "w",
+            "method": "GET",
+            "url": "https://example.com/logo.png",
+            "headers": [{ "key": "Accept", "value": "image/png", "enabled": true }],
+            "body_type": "none",
+            "body_content": "",
+            "response_status": 200,
+            "response_status_text": "OK",
+            "response_headers": [
+                { "key": "Content-Type", "value": "image/png", "enabled": true },
+                {
+                    "key": "Set-Cookie",
+                    "value": "session=abc; Path=/; Domain=example.com; Expires=Tue, 01 Jan 2030 00:00:00 GMT; HttpOnly; Secure",
+                    "enabled": true
+                }
+            ],
+            "response_body": "iVBORw0KGgoAAP8=",
+            "response_body_encoding": "base64",
+            "response_time_ms": 21,
+            "response_size_bytes": 11,
+            "response_timings": {
+                "dns_ms": 1.5,
+                "tcp_ms": 2.0,
+                "tls_ms": 3.25,
+                "ttfb_ms": 10.0,
+                "download_ms": 4.0,
+                "total_ms": 21.0
+            },
+            "response_redirects": [{
+                "url": "http://example.com/logo.png",
+                "status": 301,
+                "headers": [{ "key": "Location", "value": "https://example.com/logo.png", "enabled": true }]
+            }],
+            "created_at": 1714557600123i64
+        }))
+        .unwrap();
+        SavedRequest::new(input)
+    }
+
+    #[test]
+    fn exported_archive_reads_back() {
+        let archive = crate::export::har::export(&[saved_request()]).unwrap();
+        let value: Value = serde_json::from_str(&archive).unwrap();
+        let entry = &value["log"]["entries"][0];
+        assert_eq!(entry["startedDateTime"], "2024-05-01T10:00:00.123Z");
+        assert_eq!(
+            entry["response"]["cookies"][0]["expires"],
+            "2030-01-01T00:00:00Z"
+        );
+
+        let har = parse(&archive).unwrap();
+        assert!(har.skipped.is_empty());
+        let [input] = &har.entries[..] else {
+            panic!("expected one entry, got {}", har.entries.len());
+        };
+        assert_eq!(input.created_at, Some(1714557600123));
+        assert_eq!(input.url, "https://example.com/logo.png");
+        assert_eq!(input.response_status, Some(200));
+        assert_eq!(input.response_body.as_deref(), Some("iVBORw0KGgoAAP8="));
+        assert_eq!(input.response_body_encoding.as_deref(), Some("base64"));
+        assert_eq!(input.response_size_bytes, Some(11));
+
+        let timings = input.response_timings.as_ref().unwrap();
+        assert_eq!(timings.dns_ms, Some(1.5));
+        assert_eq!(timings.tcp_ms, Some(2.0));
+        assert_eq!(timings.tls_ms, Some(3.25));
+        assert_eq!(timings.ttfb_ms, 10.0);
+        assert_eq!(timings.download_ms, 4.0);
+        assert_eq!(timings.total_ms, 21.0);
+
+        let response_headers = input.response_headers.as_deref().unwrap();
+        assert_eq!(
+            header_value(response_headers, "set-cookie"),
+            Some("session=abc; Path=/; Domain=example.com; Expires=Tue, 01 Jan 2030 00:00:00 GMT; HttpOnly; Secure")
+        );
+
+        let redirects = input.response_redirects.as_deref().unwrap();
+        assert_eq!(redirects.len(), 1);
+        assert_eq!(redirects[0].url, "http://example.com/logo.png");
+        assert_eq!(redirects[0].status, 301);
+        assert_eq!(
+            header_value(&redirects[0].headers, "location"),
+            Some("https://example.com/logo.png")
+        );
+    }
+
+    #[test]
+    fn cookies_without_set_cookie_headers_keep_their_expiry() {
+        let mut value: Value =
+            serde_json::from_str(&crate::export::har::export(&[saved_request()]).unwrap()).unwrap();
+        let response = &mut value["log"]["entries"][0]["response"];
+        response["headers"] = serde_json::json!([]);
+
+        let har = parse(&value.to_string()).unwrap();
+        let response_headers = har.entries[0].response_headers.as_deref().unwrap();
+        let set_cookie = header_value(response_headers, "set-cookie").unwrap();
+        let cookie = cookie::Cookie::parse(set_cookie).unwrap();
+        assert_eq!(cookie.value(), "abc");
+        assert_eq!(cookie.path(), Some("/"));
+        assert_eq!(cookie.http_only(), Some(true));
+        assert_eq!(
+            cookie.expires_datetime().map(|t| t.unix_timestamp()),
+            Some(1893456000)
+        );
+    }
+
+    #[test]
+    fn start_times_without_an_offset_are_utc() {
+        let utc = |value| parse_time(value).map(|t| t.unix_timestamp_nanos() / 1_000_000);
+        assert_eq!(utc("2024-05-01T10:00:00.123Z"), Some(1714557600123));
+        assert_eq!(utc("2024-05-01T12:00:00.123+02:00"), Some(1714557600123));
+        assert_eq!(utc("2024-05-01T10:00:00.123"), Some(1714557600123));
+        assert_eq!(utc("yesterday"), None);
+    }
+}
@@ -0,0 +1,591 @@
+use serde_json::Value;
+
+use crate::db::environment::EnvironmentVariable;
+use crate::db::oauth::{OAuth2ClientAuth, OAuth2Config, OAuth2GrantType};
+use crate::db::request::{
+    ApiKeyLocation, AwsCredentials, FormField, FormFieldType, KeyValue, RequestAuth,
+};
+use crate::db::tab::TabState;
+
+use super::{
+    form_body, key_value, query_params, string, Imported, ImportedCollection, ImportedEnvironment,
+    ImportedItem,
+};
+
+const TEMPLATE_TAGS: &str = "Template tags such as {% response %} or {% uuid %}";
+
+/// Reads an Insomnia v4 JSON export. Every Insomnia workspace in it becomes a collection.
+pub fn parse(content: &str) -> Result<Imported, String> {
+    let export: Value = serde_json::from_str(content).map_err(|e| e.to_string())?;
+    if export["_type"] != "export" || export["__export_format"] != 4 {
+        return Err("Not an Insomnia v4 export".to_string());
+    }
+    let resources = export["resources"]
+        .as_array()
+        .map_or(&[][..], Vec::as_slice);
+
+    let mut reader = Reader {
+        resources,
+        imported: Imported::default(),
+    };
+    for workspace in resources.iter().filter(|r| r["_type"] == "workspace") {
+        let name = string(&workspace["name"]);
+        if reader.imported.name.is_empty() {
+            reader.imported.name = name.clone();
+        }
+        let items = reader.items(id(workspace), "", &RequestAuth::None);
+        reader
+            .imported
+            .collections
+            .push(ImportedCollection { name, items });
+        reader.environments(id(workspace));
+    }
+    if reader.imported.name.is_empty() {
+        reader.imported.name = "Insomnia".to_string();
+    }
+
+    let mut skipped: Vec<(&str, usize)> = Vec::new();
+    for resource in resources {
+        let label = match resource["_type"].as_str() {
+            Some("cookie_jar")
+                if resource["cookies"]
+                    .as_array()
+                    .is_some_and(|c| !c.is_empty()) =>
+            {
+                "cookie jar"
+            }
+            Some("grpc_request") => "gRPC request",
+            Some("websocket_request") => "WebSocket request",
+            Some("api_spec") if !string(&resource["contents"]).is_empty() => "API design document",
+            Some("unit_test_suite") => "unit test suite",
+            _ => continue,
+        };
+        match skipped.iter_mut().find(|(existing, _)| *existing == label) {
+            Some((_, count)) => *count += 1,
+            None => skipped.push((label, 1)),
+        }
+    }
+    for (label, count) in skipped {
+        reader.imported.skip("", format!("{} {}(s)", count, label));
+    }
+
+    Ok(reader.imported)
+}
+
+struct Reader<'a> {
+    resources: &'a [Value],
+    imported: Imported,
+}
+
+impl<'a> Reader<'a> {
+    // Children of a workspace or folder, in the order Insomnia shows them
+    fn children(&self, parent_id: &str) -> Vec<&'a Value> {
+        let mut children: Vec<&'a Value> = self
+            .resources
+            .iter()
+            .filter(|r| r["parentId"] == parent_id)
+            .collect();
+        children.sort_by(|a, b| {
+            let key = |r: &Value| r["metaSortKey"].as_f64().unwrap_or_default();
+            key(a).total_cmp(&key(b))
+        });
+        children
+    }
+
+    fn items(&mut self, parent_id: &str, parent: &str, auth: &RequestAuth) -> Vec<ImportedItem> {
+        let mut items = Vec::new();
+        for resource in self.children(parent_id) {
+            let kind = resource["_type"].as_str().unwrap_or_default();
+            if kind != "request_group" && kind != "request" {
+                continue;
+            }
+            let name = resource["name"]
+                .as_str()
+                .filter(|name| !name.is_empty())
+                .unwrap_or("Untitled")
+                .to_string();
+            let location = if parent.is_empty() {
+                name.clone()
+            } else {
+                format!("{} / {}", parent, name)
+            };
+
+            for (field, label) in [
+                ("preRequestScript", "Pre-request script"),
+                ("afterResponseScript", "After-response script"),
+            ] {
+                if !string(&resource[field]).trim().is_empty() {
+                    self.imported.skip(&location, label);
+                }
+            }
+            let auth = self
+                .auth(&resource["authentication"], &location)
+                .unwrap_or_else(|| auth.clone());
+
+            if kind == "request_group" {
+                if resource["environment"]
+                    .as_object()
+                    .is_some_and(|e| !e.is_empty())
+                {
+                    self.imported.skip(&location, "Folder environment");
+                }
+                let children = self.items(id(resource), &location, &auth);
+                items.push(ImportedItem::Folder {
+                    name,
+                    items: children,
+                });
+            } else {
+                let state = self.request(resource, auth);
+                if serde_json::to_string(&state).is_ok_and(|s| s.contains("{%")) {
+                    self.imported.skip(&location, TEMPLATE_TAGS);
+                }
+                items.push(ImportedItem::Request {
+                    name,
+                    state: Box::new(state),
+                });
+            }
+        }
+        items
+    }
+
+    fn request(&self, request: &Value, auth: RequestAuth) -> TabState {
+        let mut state = TabState {
+            method: string(&request["method"]).to_ascii_uppercase(),
+            auth,
+            ..Default::default()
+        };
+        if state.method.is_empty() {
+            state.method = "GET".to_string();
+        }
+
+        // Insomnia keeps query parameters next to the URL rather than in it
+        let mut url = template(&string(&request["url"]));
+        let parameters = pairs(&request["parameters"]);
+        let query = url::form_urlencoded::Serializer::new(String::new())
+            .extend_pairs(
+                parameters
+                    .iter()
+                    .filter(|p| p.enabled)
+                    .map(|p| (&p.key, &p.value)),
+            )
+            .finish();
+        if !query.is_empty() {
+            url.push(if url.contains('?') { '&' } else { '?' });
+            url.push_str(&query);
+        }
+        state.params = query_params(&url);
+        state
+            .params
+            .extend(parameters.into_iter().filter(|p| !p.enabled));
+        state.url = url;
+        state.headers = pairs(&request["headers"]);
+
+        let body = &request["body"];
+        let mime_type = body["mimeType"].as_str().unwrap_or_default();
+        match mime_type {
+            "application/x-www-form-urlencoded" => {
+                let fields = pairs(&body["params"]);
+                state.body_type = "x-www-form-urlencoded".to_string();
+                state.body_content = url::form_urlencoded::Serializer::new(String::new())
+                    .extend_pairs(
+                        fields
+                            .iter()
+                            .filter(|f| f.enabled)
+                            .map(|f| (&f.key, &f.value)),
+                    )
+                    .finish();
+            }
+            "multipart/form-data" => {
+                let mut form = Vec::new();
+                for param in body["params"].as_array().into_iter().flatten() {
+                    let is_file = param["type"] == "file";
+                    form.push(FormField {
+                        key: template(&string(&param["name"])),
+                        value: match is_file {
+                            true => string(&param["fileName"]),
+                            false => template(&string(&param["value"])),
+                        },
+                        enabled: param["disabled"] != true,
+                        description: param["description"]
+                            .as_str()
+                            .filter(|d| !d.is_empty())
+                            .map(str::to_string),
+                        field_type: match is_file {
+                            true => FormFieldType::File,
+                            false => FormFieldType::Text,
+                        },
+                        file_name: None,
+                        content_type: None,
+                    });
+                }
+                state.body_type = "form-data".to_string();
+                state.body_content = form_body(form);
+            }
+            "application/octet-stream" => {
+                state.body_type = "binary".to_string();
+                state.body_content = string(&body["fileName"]);
+            }
+            // GraphQL bodies are stored as the JSON payload already
+            _ if body.get("text").is_some() => {
+                state.body_type = "raw".to_string();
+                state.body_content = template(&string(&body["text"]));
+            }
+            _ => {}
+        }
+
+        match request["settingFollowRedirects"].as_str() {
+            Some("off") => state.settings.redirect.follow = false,
+            Some("on") => state.settings.redirect.follow = true,
+            _ => {}
+        }
+        if request["settingSendCookies"] == false && request["settingStoreCookies"] == false {
+            state.settings.disable_cookie_jar = true;
+        }
+
+        state
+    }
+
+    // `None` when the request or folder inherits the auth of its parent
+    fn auth(&mut self, auth: &Value, location: &str) -> Option<RequestAuth> {
+        let kind = auth["type"].as_str().filter(|kind| !kind.is_empty())?;
+        if auth["disabled"] == true {
+            return Some(RequestAuth::None);
+        }
+        let field = |name: &str| template(&string(&auth[name]));
+
+        Some(match kind {
+            "none" => RequestAuth::None,
+            "basic" => RequestAuth::Basic {
+                username: field("username"),
+                password: field("password"),
+            },
+            "digest" => RequestAuth::Digest {
+                username: field("username"),
+                password: field("password"),
+            },
+            "bearer" => {
+                let token = field("token");
+                let prefix = field("prefix");
+                // A custom prefix only fits in a plain Authorization header
+                if prefix.is_empty() || prefix.eq_ignore_ascii_case("bearer") {
+                    RequestAuth::Bearer { token }
+                } else {
+                    RequestAuth::ApiKey {
+                        key: "Authorization".to_string(),
+                        value: format!("{} {}", prefix, token),
+                        location: ApiKeyLocation::Header,
+                    }
+                }
+            }
+            "apikey" => {
+                let (key, value) = (field("key"), field("value"));
+                let placement = match auth["addTo"].as_str() {
+                    Some("queryParams") => ApiKeyLocation::Query,
+                    Some("cookie") => {
+                        self.imported.skip(location, "API key sent in a cookie");
+                        return Some(RequestAuth::None);
+                    }
+                    _ => ApiKeyLocation::Header,
+                };
+                RequestAuth::ApiKey {
+                    key,
+                    value,
+                    location: placement,
+                }
+            }
+            "iam" => RequestAuth::AwsSigV4(AwsCredentials {
+                access_key_id: field("accessKeyId"),
+                secret_access_key: field("secretAccessKey"),
+                session_token: field("sessionToken"),
+                region: field("region"),
+                service: field("service"),
+            }),
+            "oauth2" => {
+                let grant_type = match auth["grantType"].as_str() {
+                    Some("client_credentials") | None => OAuth2GrantType::ClientCredentials,
+                    Some("password") => OAuth2GrantType::Password,
+                    Some("authorization_code") => OAuth2GrantType::AuthorizationCode,
+                    Some(other) => {
+                        self.imported
+                            .skip(location, format!("OAuth 2.0 grant type \"{}\"", other));
+                        return Some(RequestAuth::None);
+                    }
+                };
+                RequestAuth::OAuth2(OAuth2Config {
+                    grant_type,
+                    token_url: field("accessTokenUrl"),
+                    client_id: field("clientId"),
+                    client_secret: field("clientSecret"),
+                    client_auth: match auth["credentialsInBody"] == true {
+                        true => OAuth2ClientAuth::Body,
+                        false => OAuth2ClientAuth::Basic,
+                    },
+                    scope: field("scope"),
+                    username: field("username"),
+                    password: field("password"),
+                    auth_url: field("authorizationUrl"),
+                    ..Default::default()
+                })
+            }
+            other => {
+                self.imported
+                    .skip(location, format!("Authorization type \"{}\"", other));
+                RequestAuth::None
+            }
+        })
+    }
+
+    // The base environment of a workspace, merged into each of its sub-environments
+    fn environments(&mut self, workspace_id: &str) {
+        let Some(base) = self
+            .children(workspace_id)
+            .into_iter()
+            .find(|r| r["_type"] == "environment")
+        else {
+            return;
+        };
+        let base_variables = self.variables(base);
+        let subs: Vec<&Value> = self
+            .children(id(base))
+            .into_iter()
+            .filter(|r| r["_type"] == "environment")
+            .collect();
+
+        if subs.is_empty() {
+            if !base_variables.is_empty() {
+                self.imported.environments.push(ImportedEnvironment {
+                    name: string(&base["name"]),
+                    variables: base_variables,
+                });
+            }
+            return;
+        }
+        for sub in subs {
+            let mut variables = base_variables.clone();
+            for variable in self.variables(sub) {
+                match variables.iter_mut().find(|v| v.key == variable.key) {
+                    Some(existing) => *existing = variable,
+                    None => variables.push(variable),
+                }
+            }
+            self.imported.environments.push(ImportedEnvironment {
+                name: string(&sub["name"]),
+                variables,
+            });
+        }
+    }
+
+    // Nested objects are flattened to the dotted names templates use for them
+    fn variables(&mut self, environment: &Value) -> Vec<EnvironmentVariable> {
+        let mut flat = Vec::new();
+        flatten("", &environment["data"], &mut flat);
+        if flat.iter().any(|(_, value)| value.contains("{%")) {
+            let location = format!("Environment {}", string(&environment["name"]));
+            self.imported.skip(&location, TEMPLATE_TAGS);
+        }
+        flat.into_iter()
+            .map(|(key, value)| EnvironmentVariable {
+                key,
+                value: template(&value),
+                enabled: true,
+                secret: false,
+            })
+            .collect()
+    }
+}
+
+// Headers, query parameters and form fields
+fn pairs(pairs: &Value) -> Vec<KeyValue> {
+    let mut converted = Vec::new();
+    for pair in pairs.as_array().into_iter().flatten() {
+        let name = template(&string(&pair["name"]));
+        if name.is_empty() {
+            continue;
+        }
+        converted.push(KeyValue {
+            value: template(&string(&pair["value"])),
+            enabled: pair["disabled"] != true,
+            description: pair["description"]
+                .as_str()
+                .filter(|d| !d.is_empty())
+                .map(str::to_string),
+            ..key_value(&name, String::new())
+        });
+    }
+    converted
+}
+
+// `{{ _.name }}` becomes `{{name}}`
+fn template(text: &str) -> String {
+    let mut converted = String::new();
+    let mut rest = text;
+    while let Some(start) = rest.find("{{") {
+        let Some(end) = rest[start..].find("}}") else {
+            break;
+        };
+        let name = rest[start + 2..start + end].trim();
+        let name = name.strip_prefix("_.").unwrap_or(name);
+        converted.push_str(&rest[..start]);
+        converted.push_str(&format!("{{{{{}}}}}", name));
+        rest = &rest[start + end + 2..];
+    }
+    converted.push_str(rest);
+    converted
+}
+
+fn flatten(prefix: &str, value: &Value, flat: &mut Vec<(String, String)>) {
+    match value {
+        Value::Object(object) => {
+            for (key, value) in object {
+                let key = match prefix {
+                    "" => key.clone(),
+                    _ => format!("{}.{}", prefix, key),
+                };
+                flatten(&key, value, flat);
+            }
+        }
+        _ if !prefix.is_empty() => flat.push((prefix.to_string(), string(value))),
+        _ => {}
+    }
+}
+
+fn id(resource: &Value) -> &str {
+    resource["_id"].as_str().unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXPORT: &str = r#"{
+      "_type": "export",
+      "__export_format": 4,
+      "resources": [
+        { "_id": "wrk_1", "_type": "workspace", "name": "Shop" },
+        {
+          "_id": "env_base", "_type": "environment", "parentId": "wrk_1", "name": "Base Environment",
+          "data": { "baseUrl": "https://api.example.com", "token": "base-token", "retry": { "count": 3 } }
+        },
+        {
+          "_id": "env_prod", "_type": "environment", "parentId": "env_base", "name": "Production",
+          "metaSortKey": 2, "data": {}
+        },
+        {
+          "_id": "env_staging", "_type": "environment", "parentId": "env_base", "name": "Staging",
+          "metaSortKey": 1, "data": { "baseUrl": "https://staging.example.com", "debug": true }
+        },
+        {
+          "_id": "fld_1", "_type": "request_group", "parentId": "wrk_1", "name": "Orders",
+          "metaSortKey": 1, "authentication": { "type": "bearer", "token": "{{ _.token }}" }
+        },
+        {
+          "_id": "req_1", "_type": "request", "parentId": "fld_1", "name": "List orders",
+          "method": "get", "url": "{{ _.baseUrl }}/orders",
+          "parameters": [
+            { "name": "page", "value": "2" },
+            { "name": "debug", "value": "1", "disabled": true }
+          ],
+          "headers": [{ "name": "Accept", "value": "application/json" }],
+          "authentication": {}
+        }
+      ]
+    }"#;
+
+    fn variables(environment: &ImportedEnvironment) -> Vec<(&str, &str)> {
+        environment
+            .variables
+            .iter()
+            .map(|v| (v.key.as_str(), v.value.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn sub_environments_extend_the_base() {
+        let imported = parse(EXPORT).unwrap();
+        let environments: Vec<_> = imported
+            .environments
+            .iter()
+            .map(|e| (e.name.as_str(), variables(e)))
+            .collect();
+        assert_eq!(
+            environments,
+            [
+                (
+                    "Staging",
+                    vec![
+                        ("baseUrl", "https://staging.example.com"),
+                        ("token", "base-token"),
+                        ("retry.count", "3"),
+                        ("debug", "true"),
+                    ]
+                ),
+                (
+                    "Production",
+                    vec![
+                        ("baseUrl", "https://api.example.com"),
+                        ("token", "base-token"),
+                        ("retry.count", "3"),
+                    ]
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn base_environment_alone() {
+        let mut export: Value = serde_json::from_str(EXPORT).unwrap();
+        let resources = export["resources"].as_array_mut().unwrap();
+        resources.retain(|r| r["parentId"] != "env_base");
+
+        let imported = parse(&export.to_string()).unwrap();
+        assert_eq!(imported.environments.len(), 1);
+        assert_eq!(imported.environments[0].name, "Base Environment");
+        assert_eq!(
+            variables(&imported.environments[0]),
+            [
+                ("baseUrl", "https://api.example.com"),
+                ("token", "base-token"),
+                ("retry.count", "3"),
+            ]
+        );
+    }
+
+    #[test]
+    fn requests() {
+        let imported = parse(EXPORT).unwrap();
+        assert_eq!(imported.name, "Shop");
+        assert!(imported.skipped.is_empty());
+
+        let collection = &imported.collections[0];
+        assert_eq!(collection.name, "Shop");
+        let [ImportedItem::Folder { name, items }] = &collection.items[..] else {
+            panic!("expected one folder");
+        };
+        assert_eq!(name, "Orders");
+        let [ImportedItem::Request { name, state }] = &items[..] else {
+            panic!("expected one request");
+        };
+        assert_eq!(name, "List orders");
+        assert_eq!(state.method, "GET");
+        assert_eq!(state.url, "{{baseUrl}}/orders?page=2");
+        let params: Vec<_> = state
+            .params
+            .iter()
+            .map(|p| (p.key.as_str(), p.value.as_str(), p.enabled))
+            .collect();
+        assert_eq!(params, [("page", "2", true), ("debug", "1", false)]);
+        // The request inherits the folder's auth
+        assert_eq!(
+            state.auth,
+            RequestAuth::Bearer {
+                token: "{{token}}".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn rejects_other_files() {
+        assert!(parse(r#"{ "_type": "export", "__export_format": 3 }"#).is_err());
+        assert!(parse("{}").is_err());
+    }
+}
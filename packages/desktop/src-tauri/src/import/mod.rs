@@ -0,0 +1,211 @@
+pub mod bruno;
+pub mod curl;
+pub mod har;
+pub mod insomnia;
+pub mod openapi;
+pub mod postman;
+pub mod workspace;
+
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::db::collection::{Collection, CollectionItem, KIND_FOLDER, KIND_REQUEST};
+use crate::db::environment::{Environment, EnvironmentVariable};
+use crate::db::import::ImportBatch;
+use crate::db::request::{FormField, FormFieldType, KeyValue};
+use crate::db::tab::TabState;
+use crate::db::workspace::Workspace;
+use crate::db::Database;
+use crate::fs_store;
+
+/// Everything read from a file of another tool, before it is written to the database.
+#[derive(Debug, Default)]
+pub struct Imported {
+    /// Name for the workspace created when importing into a new one
+    pub name: String,
+    pub collections: Vec<ImportedCollection>,
+    pub environments: Vec<ImportedEnvironment>,
+    pub skipped: Vec<ImportIssue>,
+}
+
+#[derive(Debug)]
+pub struct ImportedCollection {
+    pub name: String,
+    pub items: Vec<ImportedItem>,
+}
+
+#[derive(Debug)]
+pub enum ImportedItem {
+    Folder {
+        name: String,
+        items: Vec<ImportedItem>,
+    },
+    Request {
+        name: String,
+        state: Box<TabState>,
+    },
+}
+
+#[derive(Debug)]
+pub struct ImportedEnvironment {
+    pub name: String,
+    pub variables: Vec<EnvironmentVariable>,
+}
+
+/// Something in the source that had no equivalent and was left out.
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportIssue {
+    /// Path of the item it was found on, such as `Users / Create user`; empty for the file itself
+    pub location: String,
+    pub message: String,
+}
+
+/// What an import created, and what it had to leave out.
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportReport {
+    pub workspace_id: String,
+    pub collection_ids: Vec<String>,
+    pub environment_ids: Vec<String>,
+    pub folders: usize,
+    pub requests: usize,
+    /// History entries
+    pub history: usize,
+    pub skipped: Vec<ImportIssue>,
+}
+
+impl Imported {
+    pub fn skip(&mut self, location: &str, message: impl Into<String>) {
+        self.skipped.push(ImportIssue {
+            location: location.to_string(),
+            message: message.into(),
+        });
+    }
+}
+
+/// Writes an import into a workspace, or into a new one named after the source when
+/// `workspace_id` is `None`. Nothing is written when any part of it fails.
+pub fn store(
+    db: &Database,
+    workspace_id: Option<&str>,
+    imported: Imported,
+) -> Result<ImportReport, String> {
+    let mut batch = ImportBatch::default();
+    let workspace_id = match workspace_id {
+        Some(id) => id.to_string(),
+        None => {
+            let workspace = Workspace::new(&imported.name);
+            let id = workspace.id.clone();
+            batch.workspace = Some(workspace);
+            id
+        }
+    };
+
+    let mut report = ImportReport {
+        workspace_id,
+        collection_ids: Vec::new(),
+        environment_ids: Vec::new(),
+        folders: 0,
+        requests: 0,
+        history: 0,
+        skipped: imported.skipped,
+    };
+
+    for collection in imported.collections {
+        // Positioned after the workspace's collections when stored
+        let created = Collection::new(&report.workspace_id, &collection.name, 0);
+        let mut items = Vec::new();
+        collect_items(&created.id, None, collection.items, &mut items, &mut report);
+        report.collection_ids.push(created.id.clone());
+        batch.collections.push((created, items));
+    }
+    for environment in imported.environments {
+        let created = Environment::new(
+            Some(&report.workspace_id),
+            &environment.name,
+            environment.variables,
+        );
+        report.environment_ids.push(created.id.clone());
+        batch.environments.push(created);
+    }
+
+    let sync = fs_store::lock();
+    db.insert_import(&report.workspace_id, batch)?;
+    fs_store::save(&sync, db, &report.workspace_id)?;
+
+    Ok(report)
+}
+
+// Flattens the tree into items that refer to their parents, siblings in order
+fn collect_items(
+    collection_id: &str,
+    parent_id: Option<&str>,
+    imported: Vec<ImportedItem>,
+    items: &mut Vec<CollectionItem>,
+    report: &mut ImportReport,
+) {
+    for (position, item) in imported.into_iter().enumerate() {
+        let (kind, name, state, children) = match item {
+            ImportedItem::Folder { name, items } => (KIND_FOLDER, name, None, items),
+            ImportedItem::Request { name, state } => (KIND_REQUEST, name, Some(*state), Vec::new()),
+        };
+        let created = CollectionItem::new(
+            collection_id,
+            parent_id,
+            kind,
+            &name,
+            position as i32,
+            state,
+        );
+        let id = created.id.clone();
+        items.push(created);
+        if kind == KIND_FOLDER {
+            report.folders += 1;
+            collect_items(collection_id, Some(&id), children, items, report);
+        } else {
+            report.requests += 1;
+        }
+    }
+}
+
+pub(crate) fn key_value(key: &str, value: String) -> KeyValue {
+    KeyValue {
+        key: key.to_string(),
+        value,
+        enabled: true,
+        description: None,
+    }
+}
+
+// Forms of enabled text fields stay URL-encoded so the form editor can show them;
+// files and disabled fields need the structured field list
+pub(crate) fn form_body(form: Vec<FormField>) -> String {
+    if form
+        .iter()
+        .all(|f| f.enabled && f.field_type == FormFieldType::Text)
+    {
+        return url::form_urlencoded::Serializer::new(String::new())
+            .extend_pairs(form.iter().map(|f| (&f.key, &f.value)))
+            .finish();
+    }
+    // Strings, flags and a unit enum always serialize
+    serde_json::to_string(&form).expect("form fields serialize to JSON")
+}
+
+pub(crate) fn query_params(url: &str) -> Vec<KeyValue> {
+    let Some((_, query)) = url.split_once('?') else {
+        return Vec::new();
+    };
+    let query = query.split('#').next().unwrap_or_default();
+    url::form_urlencoded::parse(query.as_bytes())
+        .map(|(key, value)| key_value(&key, value.into_owned()))
+        .collect()
+}
+
+// Values that are strings in the UI are not always strings in exported files
+pub(crate) fn string(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        Value::Null => String::new(),
+        other => other.to_string(),
+    }
+}
@@ -0,0 +1,987 @@
+use serde_json::{json, Map, Value};
+
+use crate::db::environment::EnvironmentVariable;
+use crate::db::oauth::{OAuth2Config, OAuth2GrantType};
+use crate::db::request::{ApiKeyLocation, FormField, FormFieldType, KeyValue, RequestAuth};
+use crate::db::tab::TabState;
+
+use super::{
+    form_body, key_value, string, Imported, ImportedCollection, ImportedEnvironment, ImportedItem,
+};
+
+const METHODS: [&str; 8] = [
+    "get", "put", "post", "delete", "options", "head", "patch", "trace",
+];
+
+// Deep enough for real schemas, shallow enough to stop on recursive ones
+const MAX_DEPTH: usize = 8;
+
+/// Reads an OpenAPI 3.x or Swagger 2.0 document, in JSON or YAML.
+pub fn parse(content: &str) -> Result<Imported, String> {
+    let root: Value = match serde_json::from_str(content) {
+        Ok(root) => root,
+        Err(_) => serde_norway::from_str(content).map_err(|e| e.to_string())?,
+    };
+
+    // `swagger: 2.0` without quotes is read as a number
+    let swagger = match (string(&root["openapi"]), string(&root["swagger"])) {
+        (openapi, _) if openapi.starts_with('3') => false,
+        (_, swagger) if swagger.starts_with('2') => true,
+        _ => return Err("Not an OpenAPI 3 or Swagger 2 document".to_string()),
+    };
+
+    let name = root["info"]["title"]
+        .as_str()
+        .filter(|title| !title.trim().is_empty())
+        .unwrap_or("OpenAPI")
+        .to_string();
+    let mut spec = Spec {
+        root: &root,
+        swagger,
+        imported: Imported {
+            name: name.clone(),
+            ..Default::default()
+        },
+        variables: Vec::new(),
+    };
+
+    let items = spec.operations();
+    let environments = spec.environments(&name);
+    let mut imported = spec.imported;
+    imported
+        .collections
+        .push(ImportedCollection { name, items });
+    imported.environments = environments;
+    Ok(imported)
+}
+
+struct Spec<'a> {
+    root: &'a Value,
+    swagger: bool,
+    imported: Imported,
+    /// Path parameters and credentials, added to every server's environment
+    variables: Vec<EnvironmentVariable>,
+}
+
+impl<'a> Spec<'a> {
+    // One request per operation, in a folder per first tag; untagged ones at the root
+    fn operations(&mut self) -> Vec<ImportedItem> {
+        let mut folders: Vec<(String, Vec<ImportedItem>)> = self.root["tags"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|tag| Some((tag["name"].as_str()?.to_string(), Vec::new())))
+            .collect();
+        let mut untagged = Vec::new();
+
+        if self.root["webhooks"]
+            .as_object()
+            .is_some_and(|w| !w.is_empty())
+        {
+            self.imported.skip("", "Webhooks");
+        }
+
+        let root = self.root;
+        let paths = root["paths"].as_object().into_iter().flatten();
+        for (path, path_item) in paths {
+            let path_item = self.resolve(path_item);
+            for method in METHODS {
+                let operation = &path_item[method];
+                if !operation.is_object() {
+                    continue;
+                }
+
+                let name = operation["summary"]
+                    .as_str()
+                    .or(operation["operationId"].as_str())
+                    .map(|name| name.trim().to_string())
+                    .filter(|name| !name.is_empty())
+                    .unwrap_or_else(|| format!("{} {}", method.to_ascii_uppercase(), path));
+                let tag = operation["tags"][0].as_str();
+                let location = match tag {
+                    Some(tag) => format!("{} / {}", tag, name),
+                    None => name.clone(),
+                };
+
+                let state = self.operation(path, path_item, method, operation, &location);
+                let item = ImportedItem::Request {
+                    name,
+                    state: Box::new(state),
+                };
+                match tag {
+                    Some(tag) => match folders.iter_mut().find(|(name, _)| name == tag) {
+                        Some((_, items)) => items.push(item),
+                        None => folders.push((tag.to_string(), vec![item])),
+                    },
+                    None => untagged.push(item),
+                }
+            }
+        }
+
+        folders
+            .into_iter()
+            .filter(|(_, items)| !items.is_empty())
+            .map(|(name, items)| ImportedItem::Folder { name, items })
+            .chain(untagged)
+            .collect()
+    }
+
+    fn operation(
+        &mut self,
+        path: &str,
+        path_item: &'a Value,
+        method: &str,
+        operation: &'a Value,
+        location: &str,
+    ) -> TabState {
+        let mut state = TabState {
+            method: method.to_ascii_uppercase(),
+            auth: self.auth(operation),
+            ..Default::default()
+        };
+
+        let mut body_parameter = None;
+        let mut form_parameters = Vec::new();
+        for parameter in self.parameters(path_item, operation) {
+            let Some(name) = parameter["name"].as_str() else {
+                continue;
+            };
+            let value = self.parameter_value(parameter);
+            // Optional parameters are listed but left out of the request
+            let entry = KeyValue {
+                key: name.to_string(),
+                value: value.clone(),
+                enabled: parameter["required"].as_bool() == Some(true),
+                description: parameter["description"].as_str().map(str::to_string),
+            };
+            match parameter["in"].as_str() {
+                Some("path") => self.variable(name, value, false),
+                Some("query") => state.params.push(entry),
+                // OpenAPI ignores header parameters that the request sets in other ways
+                Some("header")
+                    if !["accept", "content-type", "authorization"]
+                        .contains(&name.to_ascii_lowercase().as_str()) =>
+                {
+                    state.headers.push(entry)
+                }
+                Some("cookie") => self
+                    .imported
+                    .skip(location, format!("Cookie parameter \"{}\"", name)),
+                Some("body") => body_parameter = Some(parameter),
+                Some("formData") => form_parameters.push(parameter),
+                _ => {}
+            }
+        }
+
+        state.url = format!("{{{{baseUrl}}}}{}", self.path(path));
+        let query = state.params.iter().filter(|p| p.enabled);
+        let query = url::form_urlencoded::Serializer::new(String::new())
+            .extend_pairs(query.map(|p| (&p.key, &p.value)))
+            .finish();
+        if !query.is_empty() {
+            state.url.push('?');
+            state.url.push_str(&query);
+        }
+
+        if self.swagger {
+            let consumes = match operation["consumes"].as_array() {
+                Some(consumes) => consumes,
+                None => self.root["consumes"]
+                    .as_array()
+                    .map_or(&[][..], Vec::as_slice),
+            };
+            let consumes: Vec<&str> = consumes.iter().filter_map(Value::as_str).collect();
+            if let Some(parameter) = body_parameter {
+                let media_type = consumes
+                    .iter()
+                    .find(|media_type| media_type.contains("json"))
+                    .or(consumes.first())
+                    .unwrap_or(&"application/json");
+                let example = parameter.get("x-example").cloned();
+                self.body(&mut state, media_type, &parameter["schema"], example);
+            } else if !form_parameters.is_empty() {
+                self.form_parameters(&mut state, &form_parameters, &consumes);
+            }
+        } else {
+            let request_body = self.resolve(&operation["requestBody"]);
+            if let Some(content) = request_body["content"].as_object() {
+                let preferred = [
+                    "application/json",
+                    "application/x-www-form-urlencoded",
+                    "multipart/form-data",
+                ];
+                let media_type = preferred
+                    .iter()
+                    .find(|media_type| content.contains_key(**media_type))
+                    .copied()
+                    .or_else(|| content.keys().next().map(String::as_str));
+                if let Some(media_type) = media_type {
+                    let media = &content[media_type];
+                    let example = self.media_example(media);
+                    self.body(&mut state, media_type, &media["schema"], example);
+                }
+            }
+        }
+
+        if operation["callbacks"]
+            .as_object()
+            .is_some_and(|c| !c.is_empty())
+        {
+            self.imported.skip(location, "Callbacks");
+        }
+        if operation["servers"].is_array() || path_item["servers"].is_array() {
+            self.imported
+                .skip(location, "Servers specific to the operation");
+        }
+
+        state
+    }
+
+    // Path-level parameters, overridden by operation-level ones with the same name and location
+    fn parameters(&self, path_item: &'a Value, operation: &'a Value) -> Vec<&'a Value> {
+        let mut parameters: Vec<&'a Value> = Vec::new();
+        for parameter in [&path_item["parameters"], &operation["parameters"]]
+            .into_iter()
+            .filter_map(Value::as_array)
+            .flatten()
+        {
+            let parameter = self.resolve(parameter);
+            parameters.retain(|p| p["name"] != parameter["name"] || p["in"] != parameter["in"]);
+            parameters.push(parameter);
+        }
+        parameters
+    }
+
+    fn parameter_value(&self, parameter: &Value) -> String {
+        let example = match parameter.get("example").or(parameter.get("x-example")) {
+            Some(example) => example.clone(),
+            None => match parameter["examples"]
+                .as_object()
+                .and_then(|e| e.values().next())
+            {
+                Some(example) => self.resolve(example)["value"].clone(),
+                // Swagger 2 puts the schema keywords on the parameter itself
+                None if parameter.get("schema").is_none() => self.example(parameter),
+                None => self.example(&parameter["schema"]),
+            },
+        };
+        match example {
+            Value::Array(values) => values.iter().map(string).collect::<Vec<_>>().join(","),
+            Value::Object(_) => String::new(),
+            value => string(&value),
+        }
+    }
+
+    // `/pets/{id}` becomes `/pets/{{id}}`, with `id` added to the environments
+    fn path(&mut self, path: &str) -> String {
+        let mut converted = String::new();
+        let mut rest = path;
+        while let Some(start) = rest.find('{') {
+            let Some(end) = rest[start..].find('}') else {
+                break;
+            };
+            let name = &rest[start + 1..start + end];
+            self.variable(name, String::new(), false);
+            converted.push_str(&rest[..start]);
+            converted.push_str(&format!("{{{{{}}}}}", name));
+            rest = &rest[start + end + 1..];
+        }
+        converted.push_str(rest);
+        converted
+    }
+
+    fn media_example(&self, media: &Value) -> Option<Value> {
+        if let Some(example) = media.get("example") {
+            return Some(example.clone());
+        }
+        media["examples"]
+            .as_object()
+            .and_then(|examples| examples.values().next())
+            .map(|example| self.resolve(example)["value"].clone())
+    }
+
+    fn body(&self, state: &mut TabState, media_type: &str, schema: &Value, example: Option<Value>) {
+        let essence = media_type
+            .split(';')
+            .next()
+            .unwrap_or_default()
+            .trim()
+            .to_ascii_lowercase();
+        let example = example.unwrap_or_else(|| self.example(schema));
+        let schema = self.resolve(schema);
+
+        if essence == "multipart/form-data" || essence == "application/x-www-form-urlencoded" {
+            let form: Vec<FormField> = self
+                .properties(schema, 0)
+                .into_iter()
+                .map(|(name, property)| {
+                    let property = self.resolve(property);
+                    let value = match example.get(name) {
+                        Some(value) => value.clone(),
+                        None => self.example(property),
+                    };
+                    form_field(name, &value, property, essence.starts_with("multipart/"))
+                })
+                .collect();
+            set_form(state, form, &essence);
+            return;
+        }
+
+        if is_binary(schema) {
+            state.body_type = "binary".to_string();
+        } else {
+            state.body_type = "raw".to_string();
+            state.body_content = match &example {
+                Value::String(text) => text.clone(),
+                Value::Null => String::new(),
+                value if essence.contains("json") => {
+                    serde_json::to_string_pretty(value).unwrap_or_default()
+                }
+                _ => String::new(),
+            };
+        }
+        // JSON is what the editor assumes for raw bodies without a Content-Type
+        if essence != "application/json" {
+            state
+                .headers
+                .push(key_value("Content-Type", media_type.to_string()));
+        }
+    }
+
+    // Swagger 2 `formData` parameters
+    fn form_parameters(&self, state: &mut TabState, parameters: &[&Value], consumes: &[&str]) {
+        let has_file = parameters.iter().any(|p| p["type"] == "file");
+        let essence = if has_file || consumes.contains(&"multipart/form-data") {
+            "multipart/form-data"
+        } else {
+            "application/x-www-form-urlencoded"
+        };
+        let form = parameters
+            .iter()
+            .filter_map(|parameter| {
+                let name = parameter["name"].as_str()?;
+                let value = Value::String(self.parameter_value(parameter));
+                Some(form_field(name, &value, parameter, has_file))
+            })
+            .collect();
+        set_form(state, form, essence);
+    }
+
+    // A value that satisfies the schema, preferring the examples it gives
+    fn example(&self, schema: &Value) -> Value {
+        self.generate(schema, &mut Vec::new(), 0)
+    }
+
+    // `refs` holds the references being expanded, so recursive schemas stop at the first repeat
+    fn generate(&self, schema: &Value, refs: &mut Vec<String>, depth: usize) -> Value {
+        if depth > MAX_DEPTH {
+            return Value::Null;
+        }
+        let reference = schema["$ref"].as_str();
+        if let Some(reference) = reference {
+            refs.push(reference.to_string());
+        }
+        let value = self.generate_resolved(self.resolve(schema), refs, depth);
+        if reference.is_some() {
+            refs.pop();
+        }
+        value
+    }
+
+    fn generate_resolved(&self, schema: &Value, refs: &mut Vec<String>, depth: usize) -> Value {
+        for keyword in ["example", "default", "const"] {
+            if let Some(example) = schema.get(keyword) {
+                return example.clone();
+            }
+        }
+        for keyword in ["examples", "enum"] {
+            if let Some(example) = schema[keyword].as_array().and_then(|e| e.first()) {
+                return example.clone();
+            }
+        }
+        for keyword in ["oneOf", "anyOf"] {
+            if let Some(first) = schema[keyword].as_array().and_then(|s| s.first()) {
+                return self.generate(first, refs, depth + 1);
+            }
+        }
+        let recursive = |schema: &Value, refs: &[String]| {
+            schema["$ref"]
+                .as_str()
+                .is_some_and(|reference| refs.iter().any(|r| r == reference))
+        };
+
+        match schema_type(schema) {
+            Some("object") => {
+                let mut object = Map::new();
+                for (name, property) in self.properties(schema, 0) {
+                    let read_only = self.resolve(property)["readOnly"].as_bool() == Some(true);
+                    if !read_only && !recursive(property, refs) {
+                        object.insert(name.to_string(), self.generate(property, refs, depth + 1));
+                    }
+                }
+                Value::Object(object)
+            }
+            Some("array") if recursive(&schema["items"], refs) => json!([]),
+            Some("array") => json!([self.generate(&schema["items"], refs, depth + 1)]),
+            Some("string") => Value::String(
+                match schema["format"].as_str() {
+                    Some("date-time") => "2024-01-01T00:00:00Z",
+                    Some("date") => "2024-01-01",
+                    Some("time") => "00:00:00",
+                    Some("email") => "user@example.com",
+                    Some("uuid") => "00000000-0000-0000-0000-000000000000",
+                    Some("uri") | Some("url") => "https://example.com",
+                    Some("hostname") => "example.com",
+                    Some("ipv4") => "127.0.0.1",
+                    Some("ipv6") => "::1",
+                    Some("binary") | Some("byte") => "",
+                    _ => "string",
+                }
+                .to_string(),
+            ),
+            Some("integer") | Some("number") => json!(0),
+            Some("boolean") => json!(true),
+            _ => Value::Null,
+        }
+    }
+
+    // Own properties plus those of `allOf` parts, later ones replacing earlier ones
+    fn properties(&self, schema: &'a Value, depth: usize) -> Vec<(&'a str, &'a Value)> {
+        let schema = self.resolve(schema);
+        let mut properties: Vec<(&'a str, &'a Value)> = Vec::new();
+        if depth > MAX_DEPTH {
+            return properties;
+        }
+
+        let parts = schema["allOf"].as_array().into_iter().flatten();
+        let inherited = parts.flat_map(|part| self.properties(part, depth + 1));
+        let own = schema["properties"].as_object().into_iter().flatten();
+        for (name, property) in inherited.chain(own.map(|(name, p)| (name.as_str(), p))) {
+            properties.retain(|(existing, _)| *existing != name);
+            properties.push((name, property));
+        }
+        properties
+    }
+
+    // The first security requirement of the operation, or of the document when it has none
+    fn auth(&mut self, operation: &Value) -> RequestAuth {
+        let requirements = match operation.get("security") {
+            Some(security) => security,
+            None => &self.root["security"],
+        };
+        // An empty requirement makes authentication optional
+        let Some(requirement) = requirements[0].as_object() else {
+            return RequestAuth::None;
+        };
+        let Some((name, scopes)) = requirement.iter().next() else {
+            return RequestAuth::None;
+        };
+        if requirement.len() > 1 {
+            self.skip_once(format!(
+                "Security requirements combining several schemes; only \"{}\" is used",
+                name
+            ));
+        }
+
+        let schemes = match self.swagger {
+            true => &self.root["securityDefinitions"],
+            false => &self.root["components"]["securitySchemes"],
+        };
+        let scheme = self.resolve(&schemes[name.as_str()]);
+        let scopes = scopes
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(Value::as_str)
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        let kind = scheme["type"].as_str().unwrap_or_default();
+        let http_scheme = scheme["scheme"]
+            .as_str()
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        match (kind, http_scheme.as_str()) {
+            ("basic", _) | ("http", "basic") => RequestAuth::Basic {
+                username: self.credential("username", false),
+                password: self.credential("password", true),
+            },
+            ("http", "bearer") => RequestAuth::Bearer {
+                token: self.credential("token", true),
+            },
+            ("http", "digest") => RequestAuth::Digest {
+                username: self.credential("username", false),
+                password: self.credential("password", true),
+            },
+            ("apiKey", _) => {
+                let location = match scheme["in"].as_str() {
+                    Some("header") => ApiKeyLocation::Header,
+                    Some("query") => ApiKeyLocation::Query,
+                    _ => {
+                        self.skip_once(format!("API key \"{}\" sent in a cookie", name));
+                        return RequestAuth::None;
+                    }
+                };
+                RequestAuth::ApiKey {
+                    key: scheme["name"].as_str().unwrap_or_default().to_string(),
+                    value: self.credential("apiKey", true),
+                    location,
+                }
+            }
+            ("oauth2", _) => self.oauth2(name, scheme, scopes),
+            _ => {
+                self.skip_once(format!("Security scheme \"{}\"", name));
+                RequestAuth::None
+            }
+        }
+    }
+
+    fn oauth2(&mut self, name: &str, scheme: &Value, scope: String) -> RequestAuth {
+        // Swagger 2 describes a single flow on the scheme itself
+        let (grant_type, flow) = if self.swagger {
+            let grant_type = match scheme["flow"].as_str() {
+                Some("application") => Some(OAuth2GrantType::ClientCredentials),
+                Some("password") => Some(OAuth2GrantType::Password),
+                Some("accessCode") => Some(OAuth2GrantType::AuthorizationCode),
+                _ => None,
+            };
+            (grant_type, scheme)
+        } else {
+            let flows = &scheme["flows"];
+            [
+                ("clientCredentials", OAuth2GrantType::ClientCredentials),
+                ("authorizationCode", OAuth2GrantType::AuthorizationCode),
+                ("password", OAuth2GrantType::Password),
+            ]
+            .into_iter()
+            .find(|(flow, _)| flows[*flow].is_object())
+            .map_or((None, &Value::Null), |(flow, grant_type)| {
+                (Some(grant_type), &flows[flow])
+            })
+        };
+        let Some(grant_type) = grant_type else {
+            self.skip_once(format!("OAuth 2.0 implicit flow of \"{}\"", name));
+            return RequestAuth::None;
+        };
+
+        let mut config = OAuth2Config {
+            grant_type,
+            token_url: string(&flow["tokenUrl"]),
+            auth_url: string(&flow["authorizationUrl"]),
+            client_id: self.credential("clientId", false),
+            client_secret: self.credential("clientSecret", true),
+            scope,
+            ..Default::default()
+        };
+        if grant_type == OAuth2GrantType::Password {
+            config.username = self.credential("username", false);
+            config.password = self.credential("password", true);
+        }
+        RequestAuth::OAuth2(config)
+    }
+
+    // Credentials are left for the environment to fill in
+    fn credential(&mut self, name: &str, secret: bool) -> String {
+        self.variable(name, String::new(), secret);
+        format!("{{{{{}}}}}", name)
+    }
+
+    fn variable(&mut self, key: &str, value: String, secret: bool) {
+        match self.variables.iter_mut().find(|v| v.key == key) {
+            Some(existing) if existing.value.is_empty() => existing.value = value,
+            Some(_) => {}
+            None => self.variables.push(EnvironmentVariable {
+                key: key.to_string(),
+                value,
+                enabled: true,
+                secret,
+            }),
+        }
+    }
+
+    // One environment per server, each with its own `baseUrl`
+    fn environments(&mut self, title: &str) -> Vec<ImportedEnvironment> {
+        let mut servers: Vec<(String, Vec<EnvironmentVariable>)> = Vec::new();
+        if self.swagger {
+            let host = self.root["host"].as_str().unwrap_or_default();
+            let base_path = self.root["basePath"].as_str().unwrap_or_default();
+            let schemes: Vec<&str> = self.root["schemes"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .filter_map(Value::as_str)
+                .collect();
+            if !host.is_empty() {
+                for scheme in if schemes.is_empty() {
+                    vec!["https"]
+                } else {
+                    schemes
+                } {
+                    let url = format!("{}://{}{}", scheme, host, base_path);
+                    servers.push((url.clone(), vec![base_url(url)]));
+                }
+            } else if !base_path.is_empty() {
+                servers.push((base_path.to_string(), vec![base_url(base_path.to_string())]));
+            }
+        } else {
+            for server in self.root["servers"].as_array().into_iter().flatten() {
+                let url = server["url"].as_str().unwrap_or_default();
+                let mut variables = vec![base_url(url.replace('{', "{{").replace('}', "}}"))];
+                let mut name = url.to_string();
+                for (key, variable) in server["variables"].as_object().into_iter().flatten() {
+                    let default = string(&variable["default"]);
+                    name = name.replace(&format!("{{{}}}", key), &default);
+                    variables.push(EnvironmentVariable {
+                        key: key.clone(),
+                        value: default,
+                        enabled: true,
+                        secret: false,
+                    });
+                }
+                let name = server["description"]
+                    .as_str()
+                    .filter(|d| !d.trim().is_empty())
+                    .map(str::to_string)
+                    .unwrap_or(name);
+                servers.push((name, variables));
+            }
+        }
+        if servers.is_empty() {
+            servers.push((title.to_string(), vec![base_url(String::new())]));
+        }
+
+        servers
+            .into_iter()
+            .map(|(name, mut variables)| {
+                variables.extend(self.variables.iter().cloned());
+                ImportedEnvironment { name, variables }
+            })
+            .collect()
+    }
+
+    fn skip_once(&mut self, message: String) {
+        if !self.imported.skipped.iter().any(|i| i.message == message) {
+            self.imported.skip("", message);
+        }
+    }
+
+    // Follows local `$ref`s; references to other files resolve to null
+    fn resolve(&self, value: &'a Value) -> &'a Value {
+        let mut value = value;
+        for _ in 0..MAX_DEPTH {
+            let Some(reference) = value["$ref"].as_str() else {
+                break;
+            };
+            value = reference
+                .strip_prefix('#')
+                .and_then(|pointer| self.root.pointer(pointer))
+                .unwrap_or(&Value::Null);
+        }
+        value
+    }
+}
+
+fn base_url(value: String) -> EnvironmentVariable {
+    EnvironmentVariable {
+        key: "baseUrl".to_string(),
+        value,
+        enabled: true,
+        secret: false,
+    }
+}
+
+// OpenAPI 3.1 allows a list of types, such as `[string, "null"]`
+fn schema_type(schema: &Value) -> Option<&str> {
+    match &schema["type"] {
+        Value::String(kind) => Some(kind),
+        Value::Array(kinds) => kinds
+            .iter()
+            .filter_map(Value::as_str)
+            .find(|k| *k != "null"),
+        _ if schema.get("properties").is_some() || schema.get("allOf").is_some() => Some("object"),
+        _ if schema.get("items").is_some() => Some("array"),
+        _ => None,
+    }
+}
+
+fn is_binary(schema: &Value) -> bool {
+    schema["type"] == "file" || schema["format"] == "binary"
+}
+
+fn set_form(state: &mut TabState, form: Vec<FormField>, essence: &str) {
+    if essence == "multipart/form-data" {
+        state.body_type = "form-data".to_string();
+        state.body_content = form_body(form);
+    } else {
+        state.body_type = "x-www-form-urlencoded".to_string();
+        state.body_content = url::form_urlencoded::Serializer::new(String::new())
+            .extend_pairs(form.iter().map(|f| (&f.key, &f.value)))
+            .finish();
+    }
+}
+
+fn form_field(name: &str, value: &Value, schema: &Value, multipart: bool) -> FormField {
+    let is_file = multipart && is_binary(schema);
+    FormField {
+        key: name.to_string(),
+        value: match value {
+            Value::Object(_) | Value::Array(_) => value.to_string(),
+            _ if is_file => String::new(),
+            _ => string(value),
+        },
+        enabled: true,
+        description: schema["description"].as_str().map(str::to_string),
+        field_type: if is_file {
+            FormFieldType::File
+        } else {
+            FormFieldType::Text
+        },
+        file_name: None,
+        content_type: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Requests by name, with the folder each one is in
+    fn requests(imported: &Imported) -> Vec<(Option<&str>, &str, &TabState)> {
+        let mut requests = Vec::new();
+        for item in &imported.collections[0].items {
+            match item {
+                ImportedItem::Folder { name, items } => {
+                    for item in items {
+                        if let ImportedItem::Request {
+                            name: request,
+                            state,
+                        } = item
+                        {
+                            requests.push((Some(name.as_str()), request.as_str(), &**state));
+                        }
+                    }
+                }
+                ImportedItem::Request { name, state } => {
+                    requests.push((None, name.as_str(), &**state))
+                }
+            }
+        }
+        requests
+    }
+
+    fn variables(environment: &ImportedEnvironment) -> Vec<(&str, &str)> {
+        environment
+            .variables
+            .iter()
+            .map(|v| (v.key.as_str(), v.value.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn openapi_3() {
+        let imported = parse(
+            r##"{
+              "openapi": "3.0.3",
+              "info": { "title": "Pet Store" },
+              "servers": [
+                {
+                  "url": "https://{region}.example.com/v1",
+                  "description": "Production",
+                  "variables": { "region": { "default": "eu" } }
+                },
+                { "url": "http://localhost:8080" }
+              ],
+              "tags": [{ "name": "pets" }, { "name": "unused" }],
+              "paths": {
+                "/pets/{petId}": {
+                  "parameters": [
+                    { "name": "petId", "in": "path", "required": true, "schema": { "type": "integer", "example": 7 } }
+                  ],
+                  "get": {
+                    "tags": ["pets"],
+                    "summary": "Get pet",
+                    "parameters": [
+                      { "name": "fields", "in": "query", "schema": { "type": "string" } },
+                      { "name": "X-Trace", "in": "header", "required": true, "example": "abc" }
+                    ]
+                  }
+                },
+                "/pets": {
+                  "post": {
+                    "tags": ["pets"],
+                    "operationId": "createPet",
+                    "requestBody": {
+                      "content": {
+                        "application/json": {
+                          "schema": { "$ref": "#/components/schemas/Pet" },
+                          "example": { "name": "Rex", "age": 3 }
+                        }
+                      }
+                    }
+                  }
+                },
+                "/health": { "get": {} }
+              },
+              "components": {
+                "schemas": {
+                  "Pet": {
+                    "type": "object",
+                    "properties": { "name": { "type": "string" }, "age": { "type": "integer" } }
+                  }
+                }
+              }
+            }"##,
+        )
+        .unwrap();
+
+        assert_eq!(imported.name, "Pet Store");
+        assert!(imported.skipped.is_empty());
+        let requests = requests(&imported);
+        let names: Vec<_> = requests
+            .iter()
+            .map(|(folder, name, _)| (*folder, *name))
+            .collect();
+        assert_eq!(
+            names,
+            [
+                (Some("pets"), "Get pet"),
+                (Some("pets"), "createPet"),
+                (None, "GET /health"),
+            ]
+        );
+
+        let get = requests[0].2;
+        assert_eq!(get.method, "GET");
+        assert_eq!(get.url, "{{baseUrl}}/pets/{{petId}}");
+        assert_eq!(get.params.len(), 1);
+        assert_eq!(get.params[0].key, "fields");
+        assert!(!get.params[0].enabled);
+        assert_eq!(get.headers.len(), 1);
+        assert_eq!(
+            (get.headers[0].key.as_str(), get.headers[0].value.as_str()),
+            ("X-Trace", "abc")
+        );
+        assert!(get.headers[0].enabled);
+
+        let create = requests[1].2;
+        assert_eq!(create.method, "POST");
+        assert_eq!(create.url, "{{baseUrl}}/pets");
+        assert_eq!(create.body_type, "raw");
+        assert_eq!(
+            create.body_content,
+            "{\n  \"name\": \"Rex\",\n  \"age\": 3\n}"
+        );
+        assert!(create.headers.is_empty());
+
+        assert_eq!(imported.environments.len(), 2);
+        assert_eq!(imported.environments[0].name, "Production");
+        assert_eq!(
+            variables(&imported.environments[0]),
+            [
+                ("baseUrl", "https://{{region}}.example.com/v1"),
+                ("region", "eu"),
+                ("petId", "7"),
+            ]
+        );
+        assert_eq!(imported.environments[1].name, "http://localhost:8080");
+        assert_eq!(
+            variables(&imported.environments[1]),
+            [("baseUrl", "http://localhost:8080"), ("petId", "7")]
+        );
+    }
+
+    #[test]
+    fn swagger_2() {
+        let imported = parse(
+            r#"
+swagger: 2.0
+info:
+  title: Legacy
+host: api.example.com
+basePath: /v2
+schemes: [https, http]
+consumes: [application/json]
+paths:
+  /users/{userId}:
+    put:
+      tags: [users]
+      summary: Update user
+      parameters:
+        - name: userId
+          in: path
+          required: true
+          type: string
+          x-example: u-1
+        - name: body
+          in: body
+          schema:
+            type: object
+            properties:
+              email:
+                type: string
+                example: a@example.com
+  /upload:
+    post:
+      consumes: [multipart/form-data]
+      parameters:
+        - { name: note, in: formData, type: string, x-example: hi }
+        - { name: file, in: formData, type: file }
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(imported.name, "Legacy");
+        let requests = requests(&imported);
+        let names: Vec<_> = requests
+            .iter()
+            .map(|(folder, name, _)| (*folder, *name))
+            .collect();
+        assert_eq!(
+            names,
+            [(Some("users"), "Update user"), (None, "POST /upload")]
+        );
+
+        let update = requests[0].2;
+        assert_eq!(update.method, "PUT");
+        assert_eq!(update.url, "{{baseUrl}}/users/{{userId}}");
+        assert_eq!(update.body_type, "raw");
+        assert_eq!(update.body_content, "{\n  \"email\": \"a@example.com\"\n}");
+
+        let upload = requests[1].2;
+        assert_eq!(upload.body_type, "form-data");
+        let form: Vec<FormField> = serde_json::from_str(&upload.body_content).unwrap();
+        assert_eq!(form.len(), 2);
+        assert_eq!(
+            (form[0].key.as_str(), form[0].value.as_str()),
+            ("note", "hi")
+        );
+        assert_eq!(form[0].field_type, FormFieldType::Text);
+        assert_eq!(form[1].key, "file");
+        assert_eq!(form[1].field_type, FormFieldType::File);
+
+        let environments: Vec<_> = imported
+            .environments
+            .iter()
+            .map(|e| (e.name.as_str(), variables(e)))
+            .collect();
+        assert_eq!(
+            environments,
+            [
+                (
+                    "https://api.example.com/v2",
+                    vec![("baseUrl", "https://api.example.com/v2"), ("userId", "u-1")]
+                ),
+                (
+                    "http://api.example.com/v2",
+                    vec![("baseUrl", "http://api.example.com/v2"), ("userId", "u-1")]
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_other_documents() {
+        assert!(parse(r#"{ "info": { "title": "Nothing" } }"#).is_err());
+        assert!(parse("openapi: 1.0\n").is_err());
+    }
+}
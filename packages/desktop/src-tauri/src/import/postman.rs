@@ -0,0 +1,552 @@
+use std::collections::HashMap;
+
+use serde_json::Value;
+
+use crate::db::environment::EnvironmentVariable;
+use crate::db::oauth::{OAuth2ClientAuth, OAuth2Config, OAuth2GrantType};
+use crate::db::request::{
+    ApiKeyLocation, AwsCredentials, FormField, FormFieldType, KeyValue, RequestAuth,
+};
+use crate::db::tab::TabState;
+
+use super::{
+    form_body, key_value, query_params, string, Imported, ImportedCollection, ImportedEnvironment,
+    ImportedItem,
+};
+
+/// Reads a Postman v2.0/v2.1 collection or an exported Postman environment.
+pub fn parse(content: &str) -> Result<Imported, String> {
+    let value: Value = serde_json::from_str(content).map_err(|e| e.to_string())?;
+
+    if value.get("info").is_some() && value.get("item").is_some() {
+        return Ok(parse_collection(&value));
+    }
+    if let Some(values) = value.get("values").and_then(Value::as_array) {
+        let name = value["name"]
+            .as_str()
+            .unwrap_or("Postman environment")
+            .to_string();
+        let variables = values.iter().filter_map(variable).collect();
+        return Ok(Imported {
+            name: name.clone(),
+            environments: vec![ImportedEnvironment { name, variables }],
+            ..Default::default()
+        });
+    }
+    if value.get("requests").is_some() && value.get("order").is_some() {
+        return Err(
+            "Postman v1 collections are not supported; export the collection as v2.1".to_string(),
+        );
+    }
+    Err("Not a Postman collection or environment".to_string())
+}
+
+struct Walker {
+    imported: Imported,
+    /// Collection variables, plus the path variables of every request
+    variables: Vec<EnvironmentVariable>,
+}
+
+fn parse_collection(collection: &Value) -> Imported {
+    let name = collection["info"]["name"]
+        .as_str()
+        .unwrap_or("Postman collection")
+        .to_string();
+    let mut walker = Walker {
+        imported: Imported {
+            name: name.clone(),
+            ..Default::default()
+        },
+        variables: collection["variable"]
+            .as_array()
+            .map(|variables| variables.iter().filter_map(variable).collect())
+            .unwrap_or_default(),
+    };
+
+    walker.events(collection, "");
+    let auth = walker.auth(&collection["auth"], "").unwrap_or_default();
+    let items = walker.items(&collection["item"], "", &auth);
+
+    let Walker {
+        mut imported,
+        variables,
+    } = walker;
+    imported.collections.push(ImportedCollection {
+        name: name.clone(),
+        items,
+    });
+    if !variables.is_empty() {
+        imported
+            .environments
+            .push(ImportedEnvironment { name, variables });
+    }
+    imported
+}
+
+impl Walker {
+    fn items(&mut self, items: &Value, parent: &str, auth: &RequestAuth) -> Vec<ImportedItem> {
+        let Some(items) = items.as_array() else {
+            return Vec::new();
+        };
+
+        let mut imported = Vec::new();
+        for item in items {
+            let name = item["name"].as_str().unwrap_or("Untitled").to_string();
+            let location = if parent.is_empty() {
+                name.clone()
+            } else {
+                format!("{} / {}", parent, name)
+            };
+
+            self.events(item, &location);
+            // Folders keep their auth on the item, requests inside the request
+            let own_auth = match &item["request"]["auth"] {
+                Value::Null => &item["auth"],
+                request_auth => request_auth,
+            };
+            let auth = self
+                .auth(own_auth, &location)
+                .unwrap_or_else(|| auth.clone());
+
+            if item.get("item").is_some() {
+                let items = self.items(&item["item"], &location, &auth);
+                imported.push(ImportedItem::Folder { name, items });
+                continue;
+            }
+            if let Some(count) = item["response"].as_array().map(Vec::len).filter(|n| *n > 0) {
+                self.imported
+                    .skip(&location, format!("{} saved example response(s)", count));
+            }
+            let state = self.request(&item["request"], item, auth, &location);
+            imported.push(ImportedItem::Request {
+                name,
+                state: Box::new(state),
+            });
+        }
+        imported
+    }
+
+    fn request(
+        &mut self,
+        request: &Value,
+        item: &Value,
+        auth: RequestAuth,
+        location: &str,
+    ) -> TabState {
+        let mut state = TabState {
+            auth,
+            ..Default::default()
+        };
+        // A request can be written as nothing but its URL
+        if let Some(url) = request.as_str() {
+            state.params = query_params(url);
+            state.url = url.to_string();
+            return state;
+        }
+
+        if let Some(method) = request["method"].as_str() {
+            state.method = method.to_ascii_uppercase();
+        }
+        state.url = self.url(&request["url"]);
+        state.params = match request["url"]["query"].as_array() {
+            Some(query) => query.iter().filter_map(pair).collect(),
+            None => query_params(&state.url),
+        };
+        state.headers = match &request["header"] {
+            Value::Array(headers) => headers.iter().filter_map(pair).collect(),
+            Value::String(headers) => headers
+                .lines()
+                .filter_map(|line| line.split_once(':'))
+                .map(|(name, value)| key_value(name.trim(), value.trim().to_string()))
+                .collect(),
+            _ => Vec::new(),
+        };
+        self.body(&request["body"], &mut state, location);
+
+        let behavior = &item["protocolProfileBehavior"];
+        if let Some(follow) = behavior["followRedirects"].as_bool() {
+            state.settings.redirect.follow = follow;
+        }
+        if let Some(max_redirects) = behavior["maxRedirects"].as_u64() {
+            state.settings.redirect.max_redirects = max_redirects as u32;
+        }
+        if let Some(keep_method) = behavior["followOriginalHttpMethod"].as_bool() {
+            state.settings.redirect.keep_method = keep_method;
+        }
+        if let Some(disable_cookies) = behavior["disableCookies"].as_bool() {
+            state.settings.disable_cookie_jar = disable_cookies;
+        }
+        if behavior["strictSSL"] == Value::Bool(false) {
+            self.imported
+                .skip(location, "Disabled SSL certificate verification");
+        }
+
+        state
+    }
+
+    // Postman's `:name` path variables become `{{name}}` collection variables
+    fn url(&mut self, url: &Value) -> String {
+        let raw = match url {
+            Value::String(raw) => raw.clone(),
+            Value::Object(_) => match url["raw"].as_str() {
+                Some(raw) => raw.to_string(),
+                None => build_url(url),
+            },
+            _ => String::new(),
+        };
+
+        let path_variables: HashMap<&str, String> = url["variable"]
+            .as_array()
+            .map(|variables| {
+                variables
+                    .iter()
+                    .filter_map(|v| Some((v["key"].as_str()?, string(&v["value"]))))
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let (base, rest) = match raw.find(['?', '#']) {
+            Some(index) => raw.split_at(index),
+            None => (raw.as_str(), ""),
+        };
+        let segments: Vec<String> = base
+            .split('/')
+            .map(|segment| match segment.strip_prefix(':') {
+                Some(name) if !name.is_empty() => {
+                    if !self.variables.iter().any(|v| v.key == name) {
+                        self.variables.push(EnvironmentVariable {
+                            key: name.to_string(),
+                            value: path_variables.get(name).cloned().unwrap_or_default(),
+                            enabled: true,
+                            secret: false,
+                        });
+                    }
+                    format!("{{{{{}}}}}", name)
+                }
+                _ => segment.to_string(),
+            })
+            .collect();
+        format!("{}{}", segments.join("/"), rest)
+    }
+
+    fn body(&mut self, body: &Value, state: &mut TabState, location: &str) {
+        if body["disabled"].as_bool() == Some(true) {
+            return;
+        }
+        match body["mode"].as_str() {
+            Some("raw") => {
+                state.body_type = "raw".to_string();
+                state.body_content = string(&body["raw"]);
+                // JSON is what the editor assumes for raw bodies without a Content-Type
+                let content_type = match body["options"]["raw"]["language"].as_str() {
+                    Some("xml") => Some("application/xml"),
+                    Some("html") => Some("text/html"),
+                    Some("javascript") => Some("application/javascript"),
+                    Some("text") => Some("text/plain"),
+                    _ => None,
+                };
+                if let Some(content_type) = content_type {
+                    add_header(state, "Content-Type", content_type);
+                }
+            }
+            Some("urlencoded") => {
+                let fields: Vec<KeyValue> = body["urlencoded"]
+                    .as_array()
+                    .map(|fields| fields.iter().filter_map(pair).collect())
+                    .unwrap_or_default();
+                if fields.iter().any(|f| !f.enabled) {
+                    self.imported
+                        .skip(location, "Disabled URL-encoded body fields");
+                }
+                state.body_type = "x-www-form-urlencoded".to_string();
+                state.body_content = url::form_urlencoded::Serializer::new(String::new())
+                    .extend_pairs(
+                        fields
+                            .iter()
+                            .filter(|f| f.enabled)
+                            .map(|f| (&f.key, &f.value)),
+                    )
+                    .finish();
+            }
+            Some("formdata") => {
+                let mut form = Vec::new();
+                for field in body["formdata"].as_array().into_iter().flatten() {
+                    let Some(key) = field["key"].as_str() else {
+                        continue;
+                    };
+                    let is_file = field["type"].as_str() == Some("file");
+                    let value = if is_file {
+                        let paths = files(&field["src"]);
+                        if paths.len() > 1 {
+                            self.imported.skip(
+                                location,
+                                format!("All but the first file of form field \"{}\"", key),
+                            );
+                        }
+                        paths.into_iter().next().unwrap_or_default()
+                    } else {
+                        string(&field["value"])
+                    };
+                    form.push(FormField {
+                        key: key.to_string(),
+                        value,
+                        enabled: field["disabled"].as_bool() != Some(true),
+                        description: description(&field["description"]),
+                        field_type: if is_file {
+                            FormFieldType::File
+                        } else {
+                            FormFieldType::Text
+                        },
+                        file_name: None,
+                        content_type: field["contentType"]
+                            .as_str()
+                            .filter(|ct| !ct.is_empty())
+                            .map(str::to_string),
+                    });
+                }
+                state.body_type = "form-data".to_string();
+                state.body_content = form_body(form);
+            }
+            Some("file") => {
+                state.body_type = "binary".to_string();
+                state.body_content = files(&body["file"]["src"])
+                    .into_iter()
+                    .next()
+                    .unwrap_or_default();
+            }
+            Some("graphql") => {
+                let graphql = &body["graphql"];
+                let variables = match &graphql["variables"] {
+                    Value::String(variables) if !variables.trim().is_empty() => {
+                        serde_json::from_str(variables).unwrap_or(Value::Null)
+                    }
+                    Value::Object(_) => graphql["variables"].clone(),
+                    _ => Value::Null,
+                };
+                let mut payload = serde_json::json!({ "query": string(&graphql["query"]) });
+                if !variables.is_null() {
+                    payload["variables"] = variables;
+                }
+                state.body_type = "raw".to_string();
+                state.body_content = serde_json::to_string_pretty(&payload).unwrap_or_default();
+            }
+            Some(mode) => self
+                .imported
+                .skip(location, format!("Body mode \"{}\"", mode)),
+            None => {}
+        }
+    }
+
+    // `None` when the item inherits the auth of its parent
+    fn auth(&mut self, auth: &Value, location: &str) -> Option<RequestAuth> {
+        let kind = auth["type"].as_str()?;
+        let params = auth_params(&auth[kind]);
+        let param = |name: &str| params.get(name).cloned().unwrap_or_default();
+
+        Some(match kind {
+            "inherit" => return None,
+            "noauth" => RequestAuth::None,
+            "basic" => RequestAuth::Basic {
+                username: param("username"),
+                password: param("password"),
+            },
+            "digest" => RequestAuth::Digest {
+                username: param("username"),
+                password: param("password"),
+            },
+            "bearer" => RequestAuth::Bearer {
+                token: param("token"),
+            },
+            "apikey" => RequestAuth::ApiKey {
+                key: param("key"),
+                value: param("value"),
+                location: if param("in") == "query" {
+                    ApiKeyLocation::Query
+                } else {
+                    ApiKeyLocation::Header
+                },
+            },
+            "awsv4" => RequestAuth::AwsSigV4(AwsCredentials {
+                access_key_id: param("accessKey"),
+                secret_access_key: param("secretKey"),
+                session_token: param("sessionToken"),
+                region: param("region"),
+                service: param("service"),
+            }),
+            "oauth2" => {
+                let grant_type = match param("grant_type").as_str() {
+                    "" | "client_credentials" => OAuth2GrantType::ClientCredentials,
+                    "password_credentials" => OAuth2GrantType::Password,
+                    "authorization_code" | "authorization_code_with_pkce" => {
+                        OAuth2GrantType::AuthorizationCode
+                    }
+                    other => {
+                        self.imported
+                            .skip(location, format!("OAuth 2.0 grant type \"{}\"", other));
+                        // Keep a token that was already fetched in Postman
+                        return Some(match params.get("accessToken") {
+                            Some(token) => RequestAuth::Bearer {
+                                token: token.clone(),
+                            },
+                            None => RequestAuth::None,
+                        });
+                    }
+                };
+                RequestAuth::OAuth2(OAuth2Config {
+                    grant_type,
+                    token_url: param("accessTokenUrl"),
+                    client_id: param("clientId"),
+                    client_secret: param("clientSecret"),
+                    client_auth: if param("client_authentication") == "body" {
+                        OAuth2ClientAuth::Body
+                    } else {
+                        OAuth2ClientAuth::Basic
+                    },
+                    scope: param("scope"),
+                    username: param("username"),
+                    password: param("password"),
+                    auth_url: param("authUrl"),
+                    ..Default::default()
+                })
+            }
+            other => {
+                self.imported
+                    .skip(location, format!("Authorization type \"{}\"", other));
+                RequestAuth::None
+            }
+        })
+    }
+
+    fn events(&mut self, item: &Value, location: &str) {
+        for event in item["event"].as_array().into_iter().flatten() {
+            let script = match &event["script"]["exec"] {
+                Value::Array(lines) => lines.iter().filter_map(Value::as_str).collect(),
+                Value::String(script) => script.clone(),
+                _ => String::new(),
+            };
+            if script.trim().is_empty() {
+                continue;
+            }
+            let message = match event["listen"].as_str() {
+                Some("prerequest") => "Pre-request script".to_string(),
+                Some("test") => "Test script".to_string(),
+                Some(other) => format!("\"{}\" script", other),
+                None => "Script".to_string(),
+            };
+            self.imported.skip(location, message);
+        }
+    }
+}
+
+// URL objects without `raw` are rebuilt from their parts
+fn build_url(url: &Value) -> String {
+    let join = |value: &Value, separator: &str| match value {
+        Value::Array(parts) => parts
+            .iter()
+            .map(|part| match part {
+                Value::Object(_) => string(&part["value"]),
+                _ => string(part),
+            })
+            .collect::<Vec<_>>()
+            .join(separator),
+        _ => string(value),
+    };
+
+    let mut built = String::new();
+    if let Some(protocol) = url["protocol"].as_str() {
+        built.push_str(&format!("{}://", protocol));
+    }
+    built.push_str(&join(&url["host"], "."));
+    if let Some(port) = url["port"].as_str() {
+        built.push_str(&format!(":{}", port));
+    }
+    let path = join(&url["path"], "/");
+    if !path.is_empty() {
+        built.push('/');
+        built.push_str(path.trim_start_matches('/'));
+    }
+    let query: Vec<String> = url["query"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter(|q| q["disabled"].as_bool() != Some(true))
+        .map(|q| match &q["value"] {
+            Value::Null => string(&q["key"]),
+            value => format!("{}={}", string(&q["key"]), string(value)),
+        })
+        .collect();
+    if !query.is_empty() {
+        built.push('?');
+        built.push_str(&query.join("&"));
+    }
+    built
+}
+
+fn add_header(state: &mut TabState, name: &str, value: &str) {
+    if !state
+        .headers
+        .iter()
+        .any(|h| h.enabled && h.key.eq_ignore_ascii_case(name))
+    {
+        state.headers.push(key_value(name, value.to_string()));
+    }
+}
+
+// v2.1 stores auth settings as `[{ "key", "value" }]`, v2.0 as a plain object
+fn auth_params(params: &Value) -> HashMap<String, String> {
+    match params {
+        Value::Array(params) => params
+            .iter()
+            .filter_map(|p| Some((p["key"].as_str()?.to_string(), string(&p["value"]))))
+            .collect(),
+        Value::Object(params) => params
+            .iter()
+            .map(|(key, value)| (key.clone(), string(value)))
+            .collect(),
+        _ => HashMap::new(),
+    }
+}
+
+// Headers, query parameters and URL-encoded fields
+fn pair(value: &Value) -> Option<KeyValue> {
+    Some(KeyValue {
+        key: value["key"].as_str()?.to_string(),
+        value: string(&value["value"]),
+        enabled: value["disabled"].as_bool() != Some(true),
+        description: description(&value["description"]),
+    })
+}
+
+// Collection and environment variables; environments say `enabled`, collections `disabled`
+fn variable(value: &Value) -> Option<EnvironmentVariable> {
+    let key = value["key"].as_str().filter(|key| !key.is_empty())?;
+    Some(EnvironmentVariable {
+        key: key.to_string(),
+        value: string(&value["value"]),
+        enabled: value["enabled"].as_bool() != Some(false)
+            && value["disabled"].as_bool() != Some(true),
+        secret: value["type"].as_str() == Some("secret"),
+    })
+}
+
+// Descriptions are either a string or `{ "content", "type" }`
+fn description(value: &Value) -> Option<String> {
+    let content = match value {
+        Value::Object(_) => value["content"].as_str(),
+        _ => value.as_str(),
+    }?;
+    (!content.is_empty()).then(|| content.to_string())
+}
+
+// File sources are a path, a list of paths, or null when the file was never picked
+fn files(src: &Value) -> Vec<String> {
+    match src {
+        Value::String(path) if !path.is_empty() => vec![path.clone()],
+        Value::Array(paths) => paths
+            .iter()
+            .filter_map(Value::as_str)
+            .map(str::to_string)
+            .collect(),
+        _ => Vec::new(),
+    }
+}
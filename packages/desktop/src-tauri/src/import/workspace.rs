@@ -0,0 +1,269 @@
+use std::collections::{HashMap, HashSet};
+
+use serde_json::Value;
+
+use super::{ImportIssue, ImportReport};
+use crate::db::collection::{Collection, CollectionItem, KIND_FOLDER, KIND_REQUEST};
+use crate::db::environment::Environment;
+use crate::db::import::ImportBatch;
+use crate::db::request::{CreateRequestInput, SavedRequest};
+use crate::db::tab::Tab;
+use crate::db::workspace::Workspace;
+use crate::db::Database;
+use crate::export::workspace::{WorkspaceArchive, FORMAT, VERSION};
+
+/// Reads a workspace archive written by `export::workspace`.
+pub fn parse(content: &str) -> Result<WorkspaceArchive, String> {
+    let value: Value = serde_json::from_str(content).map_err(|e| format!("Invalid JSON: {}", e))?;
+    if value["format"].as_str() != Some(FORMAT) {
+        return Err("Not a PingIT workspace archive".to_string());
+    }
+    let version = value["version"].as_u64().unwrap_or(0);
+    if version > u64::from(VERSION) {
+        return Err(format!(
+            "The archive was written by a newer version of PingIT (format version {})",
+            version
+        ));
+    }
+    serde_json::from_value(value).map_err(|e| format!("Invalid workspace archive: {}", e))
+}
+
+/// Creates a new workspace from an archive, named `name` or after the exported workspace;
+/// a number is appended when a workspace of that name already exists. Nothing is
+/// created when any part of it fails.
+///
+/// Everything gets new ids, and the links between tabs, collection requests, history
+/// and environments follow them.
+pub fn store(
+    db: &Database,
+    archive: WorkspaceArchive,
+    name: Option<&str>,
+) -> Result<ImportReport, String> {
+    let workspace = Workspace::new(&unique_name(db, name.unwrap_or(&archive.workspace.name))?);
+
+    let mut report = ImportReport {
+        workspace_id: workspace.id.clone(),
+        collection_ids: Vec::new(),
+        environment_ids: Vec::new(),
+        folders: 0,
+        requests: 0,
+        history: 0,
+        skipped: Vec::new(),
+    };
+    let mut batch = ImportBatch {
+        workspace: Some(workspace),
+        timeouts: archive.settings.timeouts,
+        ..Default::default()
+    };
+
+    if let Some(proxy) = archive.settings.proxy {
+        if !proxy.username.is_empty() && proxy.password.is_empty() {
+            skip(
+                &mut report,
+                "Proxy",
+                "The proxy password is not exported; enter it again",
+            );
+        }
+        batch.proxy = Some(proxy);
+    }
+
+    // Exported item ids to the ids of the items created for them
+    let mut item_ids = HashMap::new();
+    for archived in archive.collections {
+        let collection = Collection::new(&report.workspace_id, &archived.collection.name, 0);
+        let items = copy_items(
+            &collection.id,
+            &archived.collection.name,
+            archived.items,
+            &mut item_ids,
+            &mut report,
+        );
+        report.collection_ids.push(collection.id.clone());
+        batch.collections.push((collection, items));
+    }
+
+    for environment in archive.environments {
+        for variable in environment.variables.iter().filter(|v| v.secret) {
+            skip(
+                &mut report,
+                &format!("{} / {}", environment.name, variable.key),
+                "Secret values are not exported; set it again",
+            );
+        }
+        let mut created = Environment::new(
+            Some(&report.workspace_id),
+            &environment.name,
+            environment.variables,
+        );
+        created.is_active =
+            environment.is_active && !batch.environments.iter().any(|e| e.is_active);
+        report.environment_ids.push(created.id.clone());
+        batch.environments.push(created);
+    }
+
+    // Exported history ids to the ids of the entries created for them
+    let mut history_ids = HashMap::new();
+    for saved in archive.history.into_iter().flatten() {
+        let created = SavedRequest::new(CreateRequestInput {
+            workspace_id: report.workspace_id.clone(),
+            method: saved.method,
+            url: saved.url,
+            params: saved.params,
+            headers: saved.headers,
+            body_type: saved.body_type,
+            body_content: saved.body_content,
+            response_status: saved.response_status,
+            response_status_text: saved.response_status_text,
+            response_headers: saved.response_headers,
+            response_body: saved.response_body,
+            response_body_encoding: saved.response_body_encoding,
+            response_time_ms: saved.response_time_ms,
+            response_size_bytes: saved.response_size_bytes,
+            response_timings: saved.response_timings,
+            response_redirects: saved.response_redirects,
+            response_connection: saved.response_connection,
+            auth: saved.auth,
+            created_at: Some(saved.created_at),
+        });
+        history_ids.insert(saved.id, created.id.clone());
+        report.history += 1;
+        batch.history.push(created);
+    }
+
+    let mut tabs = archive.tabs.unwrap_or_default();
+    tabs.sort_by_key(|tab| tab.position);
+    for tab in tabs {
+        // Tabs opened from a collection request or a history entry stay linked to its copy
+        let request_id = tab
+            .request_id
+            .as_ref()
+            .and_then(|id| item_ids.get(id).or_else(|| history_ids.get(id)))
+            .cloned();
+        let is_active = tab.is_active && !batch.tabs.iter().any(|t| t.is_active);
+        batch.tabs.push(Tab {
+            id: uuid::Uuid::new_v4().to_string(),
+            workspace_id: report.workspace_id.clone(),
+            request_id,
+            state: tab.state,
+            position: 0,
+            is_active,
+        });
+    }
+    // As when tabs are opened one after another, the last one is shown by default
+    if !batch.tabs.iter().any(|t| t.is_active) {
+        if let Some(last) = batch.tabs.last_mut() {
+            last.is_active = true;
+        }
+    }
+
+    db.insert_import(&report.workspace_id, batch)?;
+
+    Ok(report)
+}
+
+// Copies a collection's items with new ids, keeping the order of siblings
+fn copy_items(
+    collection_id: &str,
+    collection_name: &str,
+    items: Vec<CollectionItem>,
+    item_ids: &mut HashMap<String, String>,
+    report: &mut ImportReport,
+) -> Vec<CollectionItem> {
+    let folders: HashSet<&str> = items
+        .iter()
+        .filter(|item| item.kind == KIND_FOLDER)
+        .map(|item| item.id.as_str())
+        .collect();
+
+    let mut children: HashMap<Option<&str>, Vec<&CollectionItem>> = HashMap::new();
+    for item in &items {
+        if item.kind != KIND_FOLDER && item.kind != KIND_REQUEST {
+            skip(
+                report,
+                &format!("{} / {}", collection_name, item.name),
+                format!("Unsupported collection item kind: {}", item.kind),
+            );
+            continue;
+        }
+        let parent = match item.parent_id.as_deref() {
+            Some(parent) if folders.contains(parent) => Some(parent),
+            Some(_) => {
+                skip(
+                    report,
+                    &format!("{} / {}", collection_name, item.name),
+                    "Its folder is missing from the archive; moved to the top of the collection",
+                );
+                None
+            }
+            None => None,
+        };
+        children.entry(parent).or_default().push(item);
+    }
+    for siblings in children.values_mut() {
+        siblings.sort_by_key(|item| item.position);
+    }
+
+    let mut copied = Vec::new();
+    let mut pending = vec![(None, None)];
+    while let Some((parent, new_parent)) = pending.pop() {
+        for (position, item) in children.get(&parent).into_iter().flatten().enumerate() {
+            let is_folder = item.kind == KIND_FOLDER;
+            let created = CollectionItem::new(
+                collection_id,
+                new_parent.as_deref(),
+                &item.kind,
+                &item.name,
+                position as i32,
+                (!is_folder).then(|| item.to_tab_state()),
+            );
+            item_ids.insert(item.id.clone(), created.id.clone());
+            if is_folder {
+                report.folders += 1;
+                pending.push((Some(item.id.as_str()), Some(created.id.clone())));
+            } else {
+                report.requests += 1;
+            }
+            copied.push(created);
+        }
+    }
+
+    // Folders that contain each other are never reached from the top
+    let expected = items
+        .iter()
+        .filter(|item| item.kind == KIND_FOLDER || item.kind == KIND_REQUEST)
+        .count();
+    if copied.len() < expected {
+        skip(
+            report,
+            collection_name,
+            format!(
+                "{} items inside folders that contain each other were left out",
+                expected - copied.len()
+            ),
+        );
+    }
+    copied
+}
+
+fn unique_name(db: &Database, name: &str) -> Result<String, String> {
+    let existing: HashSet<String> = db
+        .get_all_workspaces()?
+        .into_iter()
+        .map(|workspace| workspace.name)
+        .collect();
+
+    let mut candidate = name.to_string();
+    let mut n = 2;
+    while existing.contains(&candidate) {
+        candidate = format!("{} ({})", name, n);
+        n += 1;
+    }
+    Ok(candidate)
+}
+
+fn skip(report: &mut ImportReport, location: &str, message: impl Into<String>) {
+    report.skipped.push(ImportIssue {
+        location: location.to_string(),
+        message: message.into(),
+    });
+}
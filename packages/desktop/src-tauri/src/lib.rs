@@ -1,9 +1,17 @@
 mod db;
 mod commands;
+mod codegen;
+mod import;
+mod export;
+mod http_file;
+mod fs_store;
+mod net;
+mod variables;
 
 use std::path::PathBuf;
 use tauri::Manager;
 use db::Database;
+use net::pool::ClientPool;
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
@@ -22,6 +30,12 @@ pub fn run() {
             
             // Store database in app state
             app.manage(db);
+
+            // Shared HTTP clients, so connections are reused across sends
+            app.manage(ClientPool::default());
+
+            // Pick up changes to workspaces stored in a directory, such as a `git pull`
+            fs_store::watcher::start(app.handle());
             
             // Maximize window on startup
             let main_window = app.get_webview_window("main").unwrap();
@@ -36,6 +50,7 @@ pub fn run() {
             commands::create_workspace,
             commands::update_workspace,
             commands::delete_workspace,
+            commands::set_workspace_directory,
             // Request commands
             commands::get_requests_by_workspace,
             commands::get_request,
@@ -57,9 +72,65 @@ pub fn run() {
             commands::set_setting,
             commands::get_all_settings,
             commands::save_all_settings,
+            commands::get_timeout_settings,
+            commands::save_timeout_settings,
+            commands::get_proxy_settings,
+            commands::save_proxy_settings,
+            // Environment commands
+            commands::get_environments,
+            commands::create_environment,
+            commands::update_environment,
+            commands::delete_environment,
+            commands::set_active_environment,
+            // Collection commands
+            commands::get_collections,
+            commands::create_collection,
+            commands::rename_collection,
+            commands::delete_collection,
+            commands::reorder_collections,
+            commands::get_collection_items,
+            commands::get_collection_item,
+            commands::create_collection_item,
+            commands::rename_collection_item,
+            commands::move_collection_item,
+            commands::duplicate_collection_item,
+            commands::delete_collection_item,
+            commands::save_tab_to_collection,
+            commands::save_tab_as_collection_request,
+            // TLS commands
+            commands::get_tls_host_settings,
+            commands::create_tls_host_settings,
+            commands::update_tls_host_settings,
+            commands::delete_tls_host_settings,
+            // Cookie commands
+            commands::get_cookies,
+            commands::save_cookie,
+            commands::update_cookie,
+            commands::delete_cookie,
+            commands::clear_cookies,
+            // OAuth commands
+            commands::get_oauth_token,
+            commands::clear_oauth_tokens,
+            // Import commands
+            commands::import_curl,
+            commands::import_postman,
+            commands::import_openapi,
+            commands::import_insomnia,
+            commands::import_bruno,
+            commands::import_har,
+            commands::import_workspace,
+            // Export commands
+            commands::export_har,
+            commands::export_workspace,
+            // HTTP file commands
+            commands::open_http_file,
+            commands::save_http_file,
+            // Codegen commands
+            commands::generate_code,
             // HTTP commands
             commands::send_http_request,
             commands::cancel_http_request,
+            commands::reset_http_connections,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
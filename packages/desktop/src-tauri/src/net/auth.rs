@@ -0,0 +1,395 @@
+use base64::Engine;
+use md5::Md5;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue, AUTHORIZATION, WWW_AUTHENTICATE};
+use reqwest::{Method, Url};
+use sha2::{Digest, Sha256};
+
+use crate::db::request::{ApiKeyLocation, RequestAuth};
+
+/// Adds the credentials that can be sent up front. Left out are Digest auth, which can
+/// only answer a challenge from the server, OAuth 2.0, whose token is obtained beforehand
+/// and applied as Bearer auth, and request signing, which covers the final request of a hop.
+///
+/// Returns the name of the header carrying the credentials, if one was set.
+pub fn apply(
+    auth: &RequestAuth,
+    headers: &mut HeaderMap,
+    url: &mut Url,
+) -> Result<Option<HeaderName>, String> {
+    let authorization = match auth {
+        RequestAuth::None
+        | RequestAuth::Digest { .. }
+        | RequestAuth::OAuth2(_)
+        | RequestAuth::AwsSigV4(_)
+        | RequestAuth::Signature(_) => return Ok(None),
+        RequestAuth::Basic { username, password } => {
+            let credentials = base64::engine::general_purpose::STANDARD
+                .encode(format!("{}:{}", username, password));
+            format!("Basic {}", credentials)
+        }
+        RequestAuth::Bearer { token } => format!("Bearer {}", token.trim()),
+        RequestAuth::ApiKey {
+            key,
+            value,
+            location,
+        } => {
+            if key.trim().is_empty() {
+                return Err("API key name is required".to_string());
+            }
+
+            return match location {
+                ApiKeyLocation::Query => {
+                    url.query_pairs_mut().append_pair(key.trim(), value);
+                    Ok(None)
+                }
+                ApiKeyLocation::Header => {
+                    let name = HeaderName::from_bytes(key.trim().as_bytes())
+                        .map_err(|_| format!("Invalid API key header name: {}", key))?;
+                    headers.insert(name.clone(), header_value(value)?);
+                    Ok(Some(name))
+                }
+            };
+        }
+    };
+
+    headers.insert(AUTHORIZATION, header_value(&authorization)?);
+    Ok(Some(AUTHORIZATION))
+}
+
+fn header_value(value: &str) -> Result<HeaderValue, String> {
+    HeaderValue::from_str(value).map_err(|_| "Credentials contain invalid characters".to_string())
+}
+
+/// Parameters of a `WWW-Authenticate: Digest` challenge.
+#[derive(Debug, Clone, Default)]
+pub struct DigestChallenge {
+    pub realm: String,
+    pub nonce: String,
+    pub opaque: Option<String>,
+    pub algorithm: Option<String>,
+    /// Quality of protection options offered by the server
+    pub qop: Vec<String>,
+}
+
+/// Finds the Digest challenge among the `WWW-Authenticate` headers of a response.
+pub fn digest_challenge(headers: &HeaderMap) -> Option<DigestChallenge> {
+    headers
+        .get_all(WWW_AUTHENTICATE)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .find_map(parse_digest_challenge)
+}
+
+fn parse_digest_challenge(value: &str) -> Option<DigestChallenge> {
+    let start = value.to_ascii_lowercase().find("digest ")?;
+    let mut challenge = DigestChallenge::default();
+    let mut found_nonce = false;
+
+    for (name, param) in parse_params(&value[start + "digest ".len()..]) {
+        match name.to_ascii_lowercase().as_str() {
+            "realm" => challenge.realm = param,
+            "nonce" => {
+                challenge.nonce = param;
+                found_nonce = true;
+            }
+            "opaque" => challenge.opaque = Some(param),
+            "algorithm" => challenge.algorithm = Some(param),
+            "qop" => {
+                challenge.qop = param
+                    .split(',')
+                    .map(|q| q.trim().to_ascii_lowercase())
+                    .filter(|q| !q.is_empty())
+                    .collect()
+            }
+            _ => {}
+        }
+    }
+
+    found_nonce.then_some(challenge)
+}
+
+// Splits `name=value, name="quoted, value"` pairs; stops at the next auth scheme
+fn parse_params(input: &str) -> Vec<(String, String)> {
+    let mut params = Vec::new();
+    let mut rest = input.trim_start();
+
+    while let Some(eq) = rest.find('=') {
+        let name = rest[..eq].trim().trim_start_matches(',').trim();
+        if name.contains(' ') {
+            break;
+        }
+        rest = rest[eq + 1..].trim_start();
+
+        let value = if let Some(quoted) = rest.strip_prefix('"') {
+            let mut value = String::new();
+            let mut chars = quoted.char_indices();
+            let mut end = quoted.len();
+            while let Some((i, c)) = chars.next() {
+                match c {
+                    '\\' => {
+                        if let Some((_, escaped)) = chars.next() {
+                            value.push(escaped);
+                        }
+                    }
+                    '"' => {
+                        end = i + 1;
+                        break;
+                    }
+                    _ => value.push(c),
+                }
+            }
+            rest = &quoted[end.min(quoted.len())..];
+            value
+        } else {
+            let end = rest.find(',').unwrap_or(rest.len());
+            let value = rest[..end].trim().to_string();
+            rest = &rest[end..];
+            value
+        };
+
+        params.push((name.to_string(), value));
+        rest = rest.trim_start().trim_start_matches(',').trim_start();
+    }
+
+    params
+}
+
+/// Computes the `Authorization` header answering `challenge` (RFC 7616).
+pub fn digest_authorization(
+    challenge: &DigestChallenge,
+    username: &str,
+    password: &str,
+    method: &Method,
+    url: &Url,
+) -> Result<HeaderValue, String> {
+    let cnonce = uuid::Uuid::new_v4().simple().to_string();
+    let header = digest_header(challenge, username, password, method, url, &cnonce)?;
+    header_value(&header)
+}
+
+// The header value for a given client nonce
+fn digest_header(
+    challenge: &DigestChallenge,
+    username: &str,
+    password: &str,
+    method: &Method,
+    url: &Url,
+    cnonce: &str,
+) -> Result<String, String> {
+    let algorithm = challenge
+        .algorithm
+        .clone()
+        .unwrap_or_else(|| "MD5".to_string());
+    let (hash, session): (fn(&str) -> String, bool) = match algorithm.to_uppercase().as_str() {
+        "MD5" => (md5_hex, false),
+        "MD5-SESS" => (md5_hex, true),
+        "SHA-256" => (sha256_hex, false),
+        "SHA-256-SESS" => (sha256_hex, true),
+        other => return Err(format!("Unsupported digest algorithm: {}", other)),
+    };
+
+    let uri = match url.query() {
+        Some(query) => format!("{}?{}", url.path(), query),
+        None => url.path().to_string(),
+    };
+    let nc = "00000001";
+
+    let mut ha1 = hash(&format!("{}:{}:{}", username, challenge.realm, password));
+    if session {
+        ha1 = hash(&format!("{}:{}:{}", ha1, challenge.nonce, cnonce));
+    }
+    let ha2 = hash(&format!("{}:{}", method.as_str(), uri));
+
+    // Only "auth" is supported; "auth-int" would need the body hashed as well
+    let qop = challenge.qop.iter().find(|q| *q == "auth");
+    if qop.is_none() && !challenge.qop.is_empty() {
+        return Err(format!(
+            "Unsupported digest quality of protection: {}",
+            challenge.qop.join(", ")
+        ));
+    }
+
+    let response = match qop {
+        Some(qop) => hash(&format!(
+            "{}:{}:{}:{}:{}:{}",
+            ha1, challenge.nonce, nc, cnonce, qop, ha2
+        )),
+        None => hash(&format!("{}:{}:{}", ha1, challenge.nonce, ha2)),
+    };
+
+    let mut header = format!(
+        "Digest username=\"{}\", realm=\"{}\", nonce=\"{}\", uri=\"{}\", algorithm={}, response=\"{}\"",
+        quote(username),
+        quote(&challenge.realm),
+        quote(&challenge.nonce),
+        quote(&uri),
+        algorithm,
+        response
+    );
+    if let Some(qop) = qop {
+        header.push_str(&format!(", qop={}, nc={}, cnonce=\"{}\"", qop, nc, cnonce));
+    }
+    if let Some(opaque) = &challenge.opaque {
+        header.push_str(&format!(", opaque=\"{}\"", quote(opaque)));
+    }
+
+    Ok(header)
+}
+
+fn quote(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+fn md5_hex(input: &str) -> String {
+    hex(&Md5::digest(input.as_bytes()))
+}
+
+fn sha256_hex(input: &str) -> String {
+    hex(&Sha256::digest(input.as_bytes()))
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // RFC 7616 section 3.9.1
+    const RFC7616_CHALLENGE: &str =
+        "Digest realm=\"http-auth@example.org\", qop=\"auth, auth-int\", \
+        algorithm=SHA-256, nonce=\"7ypf/xlj9XXwfDPEoM4URrv/xwf94BcCAzFZH4GiTo0v\", \
+        opaque=\"FQhe/qaU925kfnzjCev0ciny7QMkPqMAFRtzCUYo5tdS\"";
+    const RFC7616_CNONCE: &str = "f2/wE4q74E6zIJEtWaHKaf5wv/H5QzzpXusqGemxURZJ";
+
+    fn response_param(header: &str) -> String {
+        parse_params(header.strip_prefix("Digest ").unwrap())
+            .into_iter()
+            .find(|(name, _)| name == "response")
+            .map(|(_, value)| value)
+            .unwrap()
+    }
+
+    fn rfc7616_response(algorithm: &str) -> String {
+        let mut challenge = parse_digest_challenge(RFC7616_CHALLENGE).unwrap();
+        challenge.algorithm = Some(algorithm.to_string());
+        let url = Url::parse("https://example.org/dir/index.html").unwrap();
+        let header = digest_header(
+            &challenge,
+            "Mufasa",
+            "Circle of Life",
+            &Method::GET,
+            &url,
+            RFC7616_CNONCE,
+        )
+        .unwrap();
+        response_param(&header)
+    }
+
+    #[test]
+    fn parses_rfc7616_challenge() {
+        let challenge = parse_digest_challenge(RFC7616_CHALLENGE).unwrap();
+        assert_eq!(challenge.realm, "http-auth@example.org");
+        assert_eq!(
+            challenge.nonce,
+            "7ypf/xlj9XXwfDPEoM4URrv/xwf94BcCAzFZH4GiTo0v"
+        );
+        assert_eq!(
+            challenge.opaque.as_deref(),
+            Some("FQhe/qaU925kfnzjCev0ciny7QMkPqMAFRtzCUYo5tdS")
+        );
+        assert_eq!(challenge.algorithm.as_deref(), Some("SHA-256"));
+        assert_eq!(challenge.qop, ["auth", "auth-int"]);
+    }
+
+    #[test]
+    fn rfc7616_md5_and_sha256() {
+        assert_eq!(rfc7616_response("MD5"), "8ca523f5e9506fed4657c9700eebdbec");
+        assert_eq!(
+            rfc7616_response("SHA-256"),
+            "753927fa0e85d155564e2e272a28d1802ca10daf4496794697cf8db5856cb6c1"
+        );
+    }
+
+    #[test]
+    fn session_algorithms_hash_the_nonces_into_ha1() {
+        assert_eq!(
+            rfc7616_response("MD5-sess"),
+            "e783283f46242139c486a698fec7211d"
+        );
+        assert_eq!(
+            rfc7616_response("SHA-256-sess"),
+            "2fd51b3a77ad75bad6afad6003e818d767133c46d9e2749e7f5232ae1ea3efd7"
+        );
+    }
+
+    #[test]
+    fn rfc2617_example() {
+        let challenge = parse_digest_challenge(
+            "Digest realm=\"testrealm@host.com\", qop=\"auth,auth-int\", \
+             nonce=\"dcd98b7102dd2f0e8b11d0f600bfb0c093\", \
+             opaque=\"5ccc069c403ebaf9f0171e9517f40e41\"",
+        )
+        .unwrap();
+        let url = Url::parse("http://www.nowhere.org/dir/index.html").unwrap();
+        let header = digest_header(
+            &challenge,
+            "Mufasa",
+            "Circle Of Life",
+            &Method::GET,
+            &url,
+            "0a4f113b",
+        )
+        .unwrap();
+
+        assert_eq!(response_param(&header), "6629fae49393a05397450978507c4ef1");
+        assert!(header.contains("uri=\"/dir/index.html\""));
+        assert!(header.contains("qop=auth, nc=00000001, cnonce=\"0a4f113b\""));
+        assert!(header.contains("opaque=\"5ccc069c403ebaf9f0171e9517f40e41\""));
+    }
+
+    #[test]
+    fn finds_digest_among_several_schemes() {
+        let challenge = parse_digest_challenge(
+            "Basic realm=\"simple\", Digest realm=\"api\", nonce=abc123, \
+             algorithm=MD5-sess, Bearer realm=\"tokens\", error=\"invalid_token\"",
+        )
+        .unwrap();
+        assert_eq!(challenge.realm, "api");
+        assert_eq!(challenge.nonce, "abc123");
+        assert_eq!(challenge.algorithm.as_deref(), Some("MD5-sess"));
+        assert!(challenge.opaque.is_none());
+        assert!(challenge.qop.is_empty());
+
+        let mut headers = HeaderMap::new();
+        headers.append(
+            WWW_AUTHENTICATE,
+            HeaderValue::from_static("Bearer realm=\"x\""),
+        );
+        headers.append(
+            WWW_AUTHENTICATE,
+            HeaderValue::from_static("Digest realm=\"second\", nonce=\"n\""),
+        );
+        assert_eq!(digest_challenge(&headers).unwrap().realm, "second");
+
+        assert!(parse_digest_challenge("Basic realm=\"simple\"").is_none());
+        assert!(parse_digest_challenge("Digest realm=\"no nonce\"").is_none());
+    }
+
+    #[test]
+    fn quoted_values_keep_commas_and_escapes() {
+        let params = parse_params(
+            "realm=\"users, admins\", nonce=\"a,b\\\"c\", qop=\"auth,auth-int\", stale=FALSE",
+        );
+        assert_eq!(
+            params,
+            [
+                ("realm".to_string(), "users, admins".to_string()),
+                ("nonce".to_string(), "a,b\"c".to_string()),
+                ("qop".to_string(), "auth,auth-int".to_string()),
+                ("stale".to_string(), "FALSE".to_string()),
+            ]
+        );
+    }
+}
@@ -0,0 +1,237 @@
+use std::cell::Cell;
+use std::collections::HashMap;
+use std::future::Future;
+use std::net::SocketAddr;
+use std::sync::Mutex;
+
+use hyper_util::client::legacy::connect::{Connection, HttpInfo};
+use rustls::pki_types::CertificateDer;
+use rustls::CipherSuite;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use x509_parser::extensions::GeneralName;
+use x509_parser::prelude::{FromDer, X509Certificate};
+
+// Bounds what a pool remembers about its connections and servers
+const MAX_REMEMBERED: usize = 1024;
+
+/// The connection a response arrived on.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ConnectionInfo {
+    /// Peer IP and port; the proxy's address when the request went through one
+    pub remote_address: Option<String>,
+    pub http_version: String,
+    /// `None` for plain HTTP
+    pub tls: Option<TlsInfo>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TlsInfo {
+    pub version: Option<String>,
+    pub cipher_suite: Option<String>,
+    /// Certificates sent by the server, leaf first
+    pub certificates: Vec<CertificateInfo>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CertificateInfo {
+    pub subject: String,
+    pub issuer: String,
+    pub subject_alt_names: Vec<String>,
+    pub serial_number: String,
+    /// Validity period, in milliseconds since the epoch
+    pub not_before: i64,
+    pub not_after: i64,
+    pub sha256_fingerprint: String,
+}
+
+tokio::task_local! {
+    static NEGOTIATED: Cell<Option<CipherSuite>>;
+}
+
+/// TLS details reqwest does not report, remembered for the connections of one pool so
+/// that responses arriving on reused connections report them as well.
+#[derive(Debug, Default)]
+pub struct Handshakes {
+    // Keyed by the local and remote address of each connection
+    cipher_suites: Mutex<HashMap<(SocketAddr, SocketAddr), CipherSuite>>,
+    // Intermediates sent along with each end entity certificate. Resumed sessions skip
+    // verification, so they are looked up by the leaf reqwest reports.
+    intermediates: Mutex<HashMap<Vec<u8>, Vec<CertificateDer<'static>>>>,
+}
+
+impl Handshakes {
+    /// Waits for a connection being established, then files the cipher suite its
+    /// handshake negotiated under the connection's addresses.
+    pub(crate) async fn capture<C, E>(
+        &self,
+        connecting: impl Future<Output = Result<C, E>>,
+    ) -> Result<C, E>
+    where
+        C: Connection,
+    {
+        NEGOTIATED
+            .scope(Cell::new(None), async {
+                let result = connecting.await;
+                if let Ok(conn) = &result {
+                    let mut extensions = http::Extensions::new();
+                    conn.connected().get_extras(&mut extensions);
+                    if let Some(info) = extensions.get::<HttpInfo>() {
+                        let addresses = (info.local_addr(), info.remote_addr());
+                        let mut suites = self.cipher_suites.lock().unwrap();
+                        // A plain connection may reuse the addresses of an earlier one
+                        match NEGOTIATED.with(Cell::get) {
+                            Some(suite) => insert_bounded(&mut suites, addresses, suite),
+                            None => {
+                                suites.remove(&addresses);
+                            }
+                        }
+                    }
+                }
+                result
+            })
+            .await
+    }
+
+    /// Remembers the intermediates a server sent with `end_entity`.
+    pub(crate) fn remember_chain(
+        &self,
+        end_entity: &CertificateDer<'_>,
+        intermediates: &[CertificateDer<'_>],
+    ) {
+        let intermediates = intermediates
+            .iter()
+            .map(|cert| cert.clone().into_owned())
+            .collect();
+        insert_bounded(
+            &mut self.intermediates.lock().unwrap(),
+            end_entity.to_vec(),
+            intermediates,
+        );
+    }
+}
+
+fn insert_bounded<K: std::hash::Hash + Eq, V>(map: &mut HashMap<K, V>, key: K, value: V) {
+    if map.len() >= MAX_REMEMBERED && !map.contains_key(&key) {
+        map.clear();
+    }
+    map.insert(key, value);
+}
+
+/// Records the cipher suite negotiated by the handshake running in the current task.
+pub(crate) fn record_cipher_suite(suite: CipherSuite) {
+    let _ = NEGOTIATED.try_with(|negotiated| negotiated.set(Some(suite)));
+}
+
+/// Describes the connection `response` arrived on, from a client of the pool that
+/// `handshakes` belongs to.
+pub fn connection_info(response: &reqwest::Response, handshakes: &Handshakes) -> ConnectionInfo {
+    // reqwest attaches these to the connection, so a response arriving on a reused
+    // connection has them as well
+    let addresses = response
+        .extensions()
+        .get::<HttpInfo>()
+        .map(|info| (info.local_addr(), info.remote_addr()));
+    let tls = response
+        .extensions()
+        .get::<reqwest::tls::TlsInfo>()
+        .map(|info| tls_info(info, addresses, handshakes));
+
+    ConnectionInfo {
+        remote_address: response.remote_addr().map(|addr| addr.to_string()),
+        http_version: http_version(response.version()).to_string(),
+        tls,
+    }
+}
+
+fn http_version(version: reqwest::Version) -> &'static str {
+    match version {
+        reqwest::Version::HTTP_09 => "HTTP/0.9",
+        reqwest::Version::HTTP_10 => "HTTP/1.0",
+        reqwest::Version::HTTP_11 => "HTTP/1.1",
+        reqwest::Version::HTTP_2 => "HTTP/2",
+        reqwest::Version::HTTP_3 => "HTTP/3",
+        _ => "unknown",
+    }
+}
+
+fn tls_info(
+    info: &reqwest::tls::TlsInfo,
+    addresses: Option<(SocketAddr, SocketAddr)>,
+    handshakes: &Handshakes,
+) -> TlsInfo {
+    let cipher_suite = addresses.and_then(|addresses| {
+        let suites = handshakes.cipher_suites.lock().unwrap();
+        suites.get(&addresses).copied()
+    });
+
+    let mut certificates = Vec::new();
+    if let Some(end_entity) = info.peer_certificate() {
+        certificates.push(end_entity.to_vec());
+        let intermediates = handshakes.intermediates.lock().unwrap();
+        if let Some(intermediates) = intermediates.get(end_entity) {
+            certificates.extend(intermediates.iter().map(|cert| cert.to_vec()));
+        }
+    }
+
+    TlsInfo {
+        version: info.version().map(|version| match version {
+            reqwest::tls::Version::TLS_1_2 => "TLS 1.2".to_string(),
+            reqwest::tls::Version::TLS_1_3 => "TLS 1.3".to_string(),
+            other => format!("{:?}", other),
+        }),
+        cipher_suite: cipher_suite.map(|suite| format!("{:?}", suite)),
+        certificates: certificates
+            .iter()
+            .filter_map(|der| certificate_info(der))
+            .collect(),
+    }
+}
+
+fn certificate_info(der: &[u8]) -> Option<CertificateInfo> {
+    let (_, cert) = X509Certificate::from_der(der).ok()?;
+
+    let subject_alt_names = cert
+        .subject_alternative_name()
+        .ok()
+        .flatten()
+        .map(|san| {
+            san.value
+                .general_names
+                .iter()
+                .map(|name| match name {
+                    GeneralName::DNSName(dns) => format!("DNS:{}", dns),
+                    GeneralName::IPAddress(ip) => format!("IP:{}", format_ip(ip)),
+                    GeneralName::RFC822Name(email) => format!("email:{}", email),
+                    GeneralName::URI(uri) => format!("URI:{}", uri),
+                    other => other.to_string(),
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    Some(CertificateInfo {
+        subject: cert.subject().to_string(),
+        issuer: cert.issuer().to_string(),
+        subject_alt_names,
+        serial_number: cert.raw_serial_as_string(),
+        not_before: cert.validity().not_before.timestamp() * 1000,
+        not_after: cert.validity().not_after.timestamp() * 1000,
+        sha256_fingerprint: Sha256::digest(der)
+            .iter()
+            .map(|b| format!("{:02X}", b))
+            .collect::<Vec<_>>()
+            .join(":"),
+    })
+}
+
+fn format_ip(bytes: &[u8]) -> String {
+    match bytes.len() {
+        4 => std::net::Ipv4Addr::from(<[u8; 4]>::try_from(bytes).unwrap()).to_string(),
+        16 => std::net::Ipv6Addr::from(<[u8; 16]>::try_from(bytes).unwrap()).to_string(),
+        _ => bytes
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect::<String>(),
+    }
+}
@@ -0,0 +1,292 @@
+use std::net::IpAddr;
+
+use reqwest::header::{HeaderMap, SET_COOKIE};
+use reqwest::Url;
+
+use crate::db::cookie::{Cookie, CookieInput};
+use crate::db::Database;
+
+/// The cookie jar of one workspace, read and written as a send goes through its hops.
+pub struct CookieJar<'a> {
+    db: &'a Database,
+    workspace_id: String,
+}
+
+impl<'a> CookieJar<'a> {
+    pub fn new(db: &'a Database, workspace_id: String) -> Self {
+        Self { db, workspace_id }
+    }
+
+    /// Builds the `Cookie` header value for a request to `url`, or `None` when no
+    /// stored cookie applies.
+    pub fn header_for(&self, url: &Url) -> Result<Option<String>, String> {
+        let Some(host) = url.host_str().map(|h| h.to_lowercase()) else {
+            return Ok(None);
+        };
+        let now = now_millis();
+
+        let mut cookies: Vec<Cookie> = self
+            .db
+            .get_cookies(&self.workspace_id, None)?
+            .into_iter()
+            .filter(|c| !expired(c.expires, now))
+            .filter(|c| !c.secure || url.scheme() == "https")
+            .filter(|c| domain_matches(c, &host))
+            .filter(|c| path_matches(&c.path, url.path()))
+            .collect();
+
+        if cookies.is_empty() {
+            return Ok(None);
+        }
+
+        // More specific paths first, then older cookies first (RFC 6265, section 5.4)
+        cookies.sort_by(|a, b| {
+            b.path
+                .len()
+                .cmp(&a.path.len())
+                .then(a.created_at.cmp(&b.created_at))
+        });
+
+        Ok(Some(
+            cookies
+                .iter()
+                .map(|c| format!("{}={}", c.name, c.value))
+                .collect::<Vec<_>>()
+                .join("; "),
+        ))
+    }
+
+    /// Stores the cookies set by a response to `url`; expired ones are removed instead.
+    pub fn store(&self, url: &Url, headers: &HeaderMap) -> Result<(), String> {
+        let Some(host) = url.host_str().map(|h| h.to_lowercase()) else {
+            return Ok(());
+        };
+
+        for value in headers.get_all(SET_COOKIE) {
+            let Ok(value) = value.to_str() else {
+                continue;
+            };
+            let Ok(parsed) = cookie::Cookie::parse(value) else {
+                continue;
+            };
+
+            let Some((domain, host_only)) = cookie_domain(&host, parsed.domain()) else {
+                continue;
+            };
+
+            let path = match parsed.path() {
+                Some(path) if path.starts_with('/') => path.to_string(),
+                _ => default_path(url.path()),
+            };
+
+            let now = now_millis();
+            let expires = expires_at(&parsed, now);
+            if expired(expires, now) {
+                self.db
+                    .remove_cookie(&self.workspace_id, &domain, &path, parsed.name())?;
+                continue;
+            }
+
+            self.db.save_cookie(
+                &self.workspace_id,
+                CookieInput {
+                    name: parsed.name().to_string(),
+                    value: parsed.value().to_string(),
+                    domain,
+                    path,
+                    expires,
+                    secure: parsed.secure().unwrap_or(false),
+                    http_only: parsed.http_only().unwrap_or(false),
+                    same_site: parsed.same_site().map(|s| s.to_string()),
+                    host_only,
+                },
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+fn now_millis() -> i64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_millis() as i64
+}
+
+// Domain the cookie is stored under and whether it is host-only, or `None` when the
+// response may not set it (RFC 6265, section 5.3)
+fn cookie_domain(host: &str, attribute: Option<&str>) -> Option<(String, bool)> {
+    let domain = match attribute.map(str::to_lowercase) {
+        Some(domain) if !domain.is_empty() => domain,
+        _ => return Some((host.to_string(), true)),
+    };
+
+    // A Domain attribute may only widen the cookie to a parent of the request host
+    if !host_matches_domain(host, &domain) {
+        return None;
+    }
+    // A public suffix such as `com` is shared by unrelated sites, so a cookie naming it is
+    // only kept for the host that set it. Without a suffix list, single labels stand in.
+    if !domain.contains('.') {
+        return (domain == host).then_some((domain, true));
+    }
+    Some((domain, false))
+}
+
+// Expiry in milliseconds since the epoch; Max-Age takes precedence over Expires
+fn expires_at(cookie: &cookie::Cookie, now: i64) -> Option<i64> {
+    match (cookie.max_age(), cookie.expires_datetime()) {
+        (Some(max_age), _) => Some(now + max_age.whole_milliseconds() as i64),
+        (None, Some(expires)) => Some(expires.unix_timestamp() * 1000),
+        (None, None) => None,
+    }
+}
+
+fn expired(expires: Option<i64>, now: i64) -> bool {
+    expires.is_some_and(|expires| expires <= now)
+}
+
+fn domain_matches(cookie: &Cookie, host: &str) -> bool {
+    if cookie.host_only {
+        host == cookie.domain
+    } else {
+        host_matches_domain(host, &cookie.domain)
+    }
+}
+
+// The host is the domain itself or one of its subdomains; IP addresses only match exactly
+fn host_matches_domain(host: &str, domain: &str) -> bool {
+    host == domain
+        || (host.ends_with(domain)
+            && host[..host.len() - domain.len()].ends_with('.')
+            && host.trim_matches(['[', ']']).parse::<IpAddr>().is_err())
+}
+
+fn path_matches(cookie_path: &str, request_path: &str) -> bool {
+    request_path == cookie_path
+        || (request_path.starts_with(cookie_path)
+            && (cookie_path.ends_with('/') || request_path[cookie_path.len()..].starts_with('/')))
+}
+
+// The directory of the request path, used when a cookie does not set its own path
+fn default_path(request_path: &str) -> String {
+    match request_path.rfind('/') {
+        Some(0) | None => "/".to_string(),
+        Some(index) => request_path[..index].to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cookie(domain: &str, host_only: bool) -> Cookie {
+        Cookie {
+            id: String::new(),
+            workspace_id: String::new(),
+            name: "session".to_string(),
+            value: "1".to_string(),
+            domain: domain.to_string(),
+            path: "/".to_string(),
+            expires: None,
+            secure: false,
+            http_only: false,
+            same_site: None,
+            host_only,
+            created_at: 0,
+            updated_at: 0,
+        }
+    }
+
+    #[test]
+    fn domain_attribute_widens_to_parents_only() {
+        let domain = |attribute| cookie_domain("api.example.com", attribute);
+        assert_eq!(domain(None), Some(("api.example.com".to_string(), true)));
+        assert_eq!(
+            domain(Some("")),
+            Some(("api.example.com".to_string(), true))
+        );
+        assert_eq!(
+            domain(Some("Example.COM")),
+            Some(("example.com".to_string(), false))
+        );
+        assert_eq!(
+            domain(Some("api.example.com")),
+            Some(("api.example.com".to_string(), false))
+        );
+        assert_eq!(domain(Some("other.com")), None);
+        assert_eq!(domain(Some("www.api.example.com")), None);
+        assert_eq!(domain(Some("ample.com")), None);
+    }
+
+    #[test]
+    fn public_suffix_domains_are_rejected() {
+        assert_eq!(cookie_domain("example.com", Some("com")), None);
+        assert_eq!(cookie_domain("app.localhost", Some("localhost")), None);
+        // Naming the host itself leaves a host-only cookie
+        assert_eq!(
+            cookie_domain("localhost", Some("localhost")),
+            Some(("localhost".to_string(), true))
+        );
+    }
+
+    #[test]
+    fn domain_matching() {
+        let shared = cookie("example.com", false);
+        assert!(domain_matches(&shared, "example.com"));
+        assert!(domain_matches(&shared, "api.example.com"));
+        assert!(!domain_matches(&shared, "badexample.com"));
+        assert!(!domain_matches(&shared, "example.org"));
+
+        let host_only = cookie("example.com", true);
+        assert!(domain_matches(&host_only, "example.com"));
+        assert!(!domain_matches(&host_only, "api.example.com"));
+
+        // IP addresses never match a parent domain
+        assert!(host_matches_domain("127.0.0.1", "127.0.0.1"));
+        assert!(!host_matches_domain("127.0.0.1", "0.0.1"));
+    }
+
+    #[test]
+    fn path_matching() {
+        assert!(path_matches("/", "/anything"));
+        assert!(path_matches("/docs", "/docs"));
+        assert!(path_matches("/docs", "/docs/intro"));
+        assert!(path_matches("/docs/", "/docs/intro"));
+        assert!(!path_matches("/docs", "/docsearch"));
+        assert!(!path_matches("/docs/intro", "/docs"));
+
+        assert_eq!(default_path("/"), "/");
+        assert_eq!(default_path("/login"), "/");
+        assert_eq!(default_path("/account/login"), "/account");
+        assert_eq!(default_path(""), "/");
+    }
+
+    #[test]
+    fn expiry() {
+        let now = 1_700_000_000_000;
+        let expiry = |header| expires_at(&cookie::Cookie::parse(header).unwrap(), now);
+
+        assert_eq!(expiry("a=1"), None);
+        assert_eq!(expiry("a=1; Max-Age=60"), Some(now + 60_000));
+        assert_eq!(
+            expiry("a=1; Expires=Tue, 01 Jan 2030 00:00:00 GMT"),
+            Some(1_893_456_000_000)
+        );
+        // Max-Age wins over Expires
+        assert_eq!(
+            expiry("a=1; Max-Age=0; Expires=Tue, 01 Jan 2030 00:00:00 GMT"),
+            Some(now)
+        );
+
+        assert!(!expired(None, now));
+        assert!(!expired(Some(now + 1), now));
+        assert!(expired(Some(now), now));
+        assert!(expired(expiry("a=1; Max-Age=0"), now));
+        assert!(expired(
+            expiry("a=1; Expires=Thu, 01 Jan 1970 00:00:00 GMT"),
+            now
+        ));
+    }
+}
@@ -0,0 +1,11 @@
+pub mod auth;
+pub mod connection;
+pub mod cookies;
+pub mod oauth;
+pub mod pool;
+pub mod proxy;
+pub mod redirect;
+pub mod signing;
+pub mod sigv4;
+pub mod timing;
+pub mod tls;
@@ -0,0 +1,194 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use reqwest::Url;
+
+use crate::db::settings::{ProxySettings, TimeoutSettings};
+use crate::db::tls::TlsHostSettings;
+
+use super::connection::{self, ConnectionInfo, Handshakes};
+use super::proxy::ResolvedProxy;
+use super::timing::{TimingLayer, TimingResolver};
+use super::tls;
+
+// Each client keeps its own idle connections and TLS sessions; beyond this many
+// distinct options the least recently used client is dropped
+const MAX_CLIENTS: usize = 16;
+
+/// Everything that shapes how a client connects. Requests with equal options share a
+/// client, and with it the client's idle connections and TLS sessions.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct ClientOptions {
+    pub connect_timeout: Option<Duration>,
+    pub read_timeout: Option<Duration>,
+    pub proxy: ResolvedProxy,
+    pub tls: Option<TlsHostSettings>,
+}
+
+impl ClientOptions {
+    pub fn new(timeouts: &TimeoutSettings, proxy: &ProxySettings) -> Self {
+        Self {
+            connect_timeout: non_zero_millis(timeouts.connect_ms),
+            read_timeout: non_zero_millis(timeouts.read_ms),
+            proxy: ResolvedProxy::new(proxy),
+            tls: None,
+        }
+    }
+}
+
+fn non_zero_millis(ms: u64) -> Option<Duration> {
+    (ms > 0).then(|| Duration::from_millis(ms))
+}
+
+/// Long-lived HTTP clients kept in Tauri state, one per distinct `ClientOptions`, up to
+/// `MAX_CLIENTS` of the most recently used.
+#[derive(Default)]
+pub struct ClientPool {
+    clients: Mutex<Clients>,
+    handshakes: Arc<Handshakes>,
+}
+
+#[derive(Default)]
+struct Clients {
+    // Each client with the tick it was last handed out at
+    by_options: HashMap<ClientOptions, (reqwest::Client, u64)>,
+    tick: u64,
+}
+
+impl ClientPool {
+    /// Returns the pooled client for `options`, creating it on first use.
+    pub fn get(&self, options: &ClientOptions) -> Result<reqwest::Client, String> {
+        let mut clients = self.clients.lock().unwrap();
+        clients.tick += 1;
+        let tick = clients.tick;
+
+        if let Some((client, last_used)) = clients.by_options.get_mut(options) {
+            *last_used = tick;
+            return Ok(client.clone());
+        }
+
+        let client = build_client(options, &self.handshakes)?;
+        if clients.by_options.len() >= MAX_CLIENTS {
+            let least_recent = clients
+                .by_options
+                .iter()
+                .min_by_key(|(_, (_, last_used))| *last_used)
+                .map(|(options, _)| options.clone());
+            if let Some(least_recent) = least_recent {
+                clients.by_options.remove(&least_recent);
+            }
+        }
+
+        clients
+            .by_options
+            .insert(options.clone(), (client.clone(), tick));
+        Ok(client)
+    }
+
+    /// Drops every pooled client; their connections close once in-flight requests finish.
+    pub fn reset(&self) -> usize {
+        let mut clients = self.clients.lock().unwrap();
+        let count = clients.by_options.len();
+        clients.by_options.clear();
+        count
+    }
+}
+
+/// Hands out clients for the hops of a single send. TLS settings depend on the host, so
+/// a redirect to another host may need a different client.
+pub struct ClientSelector<'a> {
+    pool: &'a ClientPool,
+    // Clients for sends that must not reuse pooled connections, dropped with the send
+    fresh: Option<ClientPool>,
+    options: ClientOptions,
+    tls_hosts: Vec<TlsHostSettings>,
+}
+
+impl<'a> ClientSelector<'a> {
+    pub fn new(
+        pool: &'a ClientPool,
+        options: ClientOptions,
+        tls_hosts: Vec<TlsHostSettings>,
+        fresh_connection: bool,
+    ) -> Self {
+        Self {
+            pool,
+            fresh: fresh_connection.then(ClientPool::default),
+            options,
+            tls_hosts,
+        }
+    }
+
+    pub fn client_for(&self, url: &Url) -> Result<reqwest::Client, String> {
+        let options = ClientOptions {
+            tls: tls::find_host_settings(&self.tls_hosts, url).cloned(),
+            ..self.options.clone()
+        };
+
+        self.fresh.as_ref().unwrap_or(self.pool).get(&options)
+    }
+
+    /// Describes the connection `response`, received with one of these clients, arrived on.
+    pub fn connection_info(&self, response: &reqwest::Response) -> ConnectionInfo {
+        let pool = self.fresh.as_ref().unwrap_or(self.pool);
+        connection::connection_info(response, &pool.handshakes)
+    }
+}
+
+fn build_client(
+    options: &ClientOptions,
+    handshakes: &Arc<Handshakes>,
+) -> Result<reqwest::Client, String> {
+    // Redirects are followed by hand so every hop can be recorded. The total timeout is
+    // enforced per send, since it has to span redirects and the body download.
+    let mut builder = reqwest::Client::builder()
+        .redirect(reqwest::redirect::Policy::none())
+        .dns_resolver(Arc::new(TimingResolver))
+        .connector_layer(TimingLayer::new(handshakes.clone()))
+        .tls_info(true)
+        .tls_backend_preconfigured(tls::client_config(
+            options.tls.as_ref(),
+            handshakes.clone(),
+        )?);
+
+    if let Some(timeout) = options.connect_timeout {
+        builder = builder.connect_timeout(timeout);
+    }
+    if let Some(timeout) = options.read_timeout {
+        builder = builder.read_timeout(timeout);
+    }
+
+    builder = options.proxy.apply(builder)?;
+
+    builder.build().map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn options(connect_ms: u64) -> ClientOptions {
+        ClientOptions {
+            connect_timeout: Some(Duration::from_millis(connect_ms)),
+            ..ClientOptions::default()
+        }
+    }
+
+    #[test]
+    fn least_recently_used_client_is_dropped() {
+        let pool = ClientPool::default();
+        for ms in 1..=MAX_CLIENTS as u64 {
+            pool.get(&options(ms)).unwrap();
+        }
+        // Used again, so the second one is now the least recent
+        pool.get(&options(1)).unwrap();
+        pool.get(&options(100)).unwrap();
+
+        let clients = pool.clients.lock().unwrap();
+        assert_eq!(clients.by_options.len(), MAX_CLIENTS);
+        assert!(clients.by_options.contains_key(&options(1)));
+        assert!(!clients.by_options.contains_key(&options(2)));
+        assert!(clients.by_options.contains_key(&options(100)));
+    }
+}
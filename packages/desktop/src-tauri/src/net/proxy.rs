@@ -0,0 +1,115 @@
+use reqwest::{ClientBuilder, NoProxy, Proxy};
+
+use crate::db::settings::ProxySettings;
+
+/// Proxy URLs a client is built with, after settings and environment were combined.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct ResolvedProxy {
+    pub http: Option<String>,
+    pub https: Option<String>,
+    /// SOCKS5 or catch-all proxy, used when no scheme-specific proxy applies
+    pub all: Option<String>,
+    pub username: String,
+    pub password: String,
+    pub no_proxy: String,
+}
+
+impl ResolvedProxy {
+    /// Combines the saved settings with the system variables when those are honoured.
+    /// Explicit settings take precedence over the environment.
+    pub fn new(settings: &ProxySettings) -> Self {
+        let mut resolved = ResolvedProxy::default();
+
+        if settings.enabled {
+            resolved.http = non_empty(&settings.http_url);
+            resolved.https = non_empty(&settings.https_url);
+            resolved.all = non_empty(&settings.socks_url).map(|url| {
+                if url.contains("://") {
+                    url
+                } else {
+                    format!("socks5://{}", url)
+                }
+            });
+            resolved.username = settings.username.clone();
+            resolved.password = settings.password.clone();
+        }
+
+        let mut no_proxy: Vec<String> = if settings.enabled {
+            settings
+                .no_proxy
+                .iter()
+                .map(|host| host.trim().to_string())
+                .filter(|host| !host.is_empty())
+                .collect()
+        } else {
+            Vec::new()
+        };
+
+        if settings.use_system {
+            if resolved.http.is_none() {
+                resolved.http = env_var(&["HTTP_PROXY", "http_proxy"]);
+            }
+            if resolved.https.is_none() {
+                resolved.https = env_var(&["HTTPS_PROXY", "https_proxy"]);
+            }
+            if resolved.all.is_none() {
+                resolved.all = env_var(&["ALL_PROXY", "all_proxy"]);
+            }
+            if let Some(hosts) = env_var(&["NO_PROXY", "no_proxy"]) {
+                no_proxy.extend(
+                    hosts
+                        .split(',')
+                        .map(|host| host.trim().to_string())
+                        .filter(|host| !host.is_empty()),
+                );
+            }
+        }
+
+        resolved.no_proxy = no_proxy.join(",");
+        resolved
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.http.is_none() && self.https.is_none() && self.all.is_none()
+    }
+
+    /// Installs the proxies on a client builder; without any, the system proxy is disabled
+    /// too so that turning the setting off is never overridden by the environment.
+    pub fn apply(&self, builder: ClientBuilder) -> Result<ClientBuilder, String> {
+        if self.is_empty() {
+            return Ok(builder.no_proxy());
+        }
+
+        let mut builder = builder;
+        let proxies = [
+            self.http.as_deref().map(Proxy::http),
+            self.https.as_deref().map(Proxy::https),
+            self.all.as_deref().map(Proxy::all),
+        ];
+
+        for proxy in proxies.into_iter().flatten() {
+            let mut proxy = proxy
+                .map_err(|e| format!("Invalid proxy URL: {}", e))?
+                .no_proxy(NoProxy::from_string(&self.no_proxy));
+            if !self.username.is_empty() {
+                proxy = proxy.basic_auth(&self.username, &self.password);
+            }
+
+            builder = builder.proxy(proxy);
+        }
+
+        Ok(builder)
+    }
+}
+
+fn non_empty(value: &str) -> Option<String> {
+    let value = value.trim();
+    (!value.is_empty()).then(|| value.to_string())
+}
+
+fn env_var(names: &[&str]) -> Option<String> {
+    names
+        .iter()
+        .find_map(|name| std::env::var(name).ok())
+        .and_then(|value| non_empty(&value))
+}
@@ -0,0 +1,176 @@
+use reqwest::header::{
+    HeaderMap, HeaderName, AUTHORIZATION, CONTENT_LENGTH, CONTENT_TYPE, COOKIE,
+    PROXY_AUTHORIZATION, TRANSFER_ENCODING,
+};
+use reqwest::{Method, StatusCode, Url};
+use serde::{Deserialize, Serialize};
+
+use crate::db::request::{KeyValue, RedirectSettings};
+
+/// A response that redirected the request elsewhere.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RedirectHop {
+    pub url: String,
+    pub status: u16,
+    pub headers: Vec<KeyValue>,
+}
+
+/// Where to send the request next and whether its body should be kept.
+pub struct NextHop {
+    pub url: Url,
+    pub method: Method,
+    pub keep_body: bool,
+}
+
+/// Works out the follow-up request for a redirect response, or `None` when the
+/// response is not a redirect that should be followed.
+pub fn next_hop(
+    settings: &RedirectSettings,
+    method: &Method,
+    current: &Url,
+    status: StatusCode,
+    headers: &HeaderMap,
+) -> Option<NextHop> {
+    if !settings.follow {
+        return None;
+    }
+
+    let keep_method = match status {
+        StatusCode::MOVED_PERMANENTLY | StatusCode::FOUND => {
+            settings.keep_method || *method != Method::POST
+        }
+        StatusCode::SEE_OTHER => *method == Method::HEAD,
+        StatusCode::TEMPORARY_REDIRECT | StatusCode::PERMANENT_REDIRECT => true,
+        _ => return None,
+    };
+
+    let location = headers.get(reqwest::header::LOCATION)?.to_str().ok()?;
+    let url = current.join(location).ok()?;
+
+    Some(NextHop {
+        url,
+        method: if keep_method {
+            method.clone()
+        } else {
+            Method::GET
+        },
+        keep_body: keep_method,
+    })
+}
+
+/// Adjusts the request headers for the next hop. `auth_header` is the header the
+/// auth section put the credentials in, such as a custom API key header.
+///
+/// Returns whether credentials were dropped because the next hop goes to another
+/// origin; from then on none should be added for the rest of the chain.
+pub fn prepare_headers(
+    settings: &RedirectSettings,
+    headers: &mut HeaderMap,
+    auth_header: Option<&HeaderName>,
+    current: &Url,
+    next: &NextHop,
+) -> bool {
+    if !next.keep_body {
+        headers.remove(CONTENT_TYPE);
+        headers.remove(CONTENT_LENGTH);
+        headers.remove(TRANSFER_ENCODING);
+    }
+
+    if !settings.strip_auth_cross_origin || current.origin() == next.url.origin() {
+        return false;
+    }
+
+    headers.remove(AUTHORIZATION);
+    headers.remove(PROXY_AUTHORIZATION);
+    headers.remove(COOKIE);
+    if let Some(name) = auth_header {
+        headers.remove(name);
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::{HeaderValue, LOCATION};
+
+    fn redirect(current: &Url, location: &str) -> NextHop {
+        let mut headers = HeaderMap::new();
+        headers.insert(LOCATION, HeaderValue::from_str(location).unwrap());
+        next_hop(
+            &RedirectSettings::default(),
+            &Method::GET,
+            current,
+            StatusCode::FOUND,
+            &headers,
+        )
+        .unwrap()
+    }
+
+    fn credentials() -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(AUTHORIZATION, HeaderValue::from_static("Bearer secret"));
+        headers.insert(COOKIE, HeaderValue::from_static("session=1"));
+        headers.insert("x-api-key", HeaderValue::from_static("secret"));
+        headers.insert("accept", HeaderValue::from_static("*/*"));
+        headers
+    }
+
+    #[test]
+    fn cross_origin_redirect_drops_credentials() {
+        let current = Url::parse("https://api.example.com/v1/items").unwrap();
+        let next = redirect(&current, "https://cdn.example.net/items");
+        let mut headers = credentials();
+        let api_key = HeaderName::from_static("x-api-key");
+
+        let stripped = prepare_headers(
+            &RedirectSettings::default(),
+            &mut headers,
+            Some(&api_key),
+            &current,
+            &next,
+        );
+
+        assert!(stripped);
+        assert!(!headers.contains_key(AUTHORIZATION));
+        assert!(!headers.contains_key(COOKIE));
+        assert!(!headers.contains_key("x-api-key"));
+        assert_eq!(headers["accept"], "*/*");
+    }
+
+    #[test]
+    fn same_origin_redirect_keeps_credentials() {
+        let current = Url::parse("https://api.example.com/v1/items").unwrap();
+        let next = redirect(&current, "/v2/items");
+        let mut headers = credentials();
+        let api_key = HeaderName::from_static("x-api-key");
+
+        let stripped = prepare_headers(
+            &RedirectSettings::default(),
+            &mut headers,
+            Some(&api_key),
+            &current,
+            &next,
+        );
+
+        assert!(!stripped);
+        assert_eq!(headers["x-api-key"], "secret");
+        assert_eq!(headers[AUTHORIZATION], "Bearer secret");
+    }
+
+    #[test]
+    fn port_change_is_another_origin() {
+        let current = Url::parse("http://localhost:8080/").unwrap();
+        let next = redirect(&current, "http://localhost:9090/");
+        let mut headers = credentials();
+
+        assert!(prepare_headers(
+            &RedirectSettings::default(),
+            &mut headers,
+            None,
+            &current,
+            &next,
+        ));
+        assert!(!headers.contains_key(AUTHORIZATION));
+    }
+}
@@ -0,0 +1,274 @@
+use std::time::SystemTime;
+
+use aws_lc_rs::rand::SystemRandom;
+use aws_lc_rs::signature::{
+    EcdsaKeyPair, RsaKeyPair, ECDSA_P256_SHA256_FIXED_SIGNING, RSA_PKCS1_SHA256,
+};
+use base64::Engine;
+use hmac::{Hmac, Mac};
+use reqwest::header::{HeaderName, HeaderValue};
+use reqwest::Request;
+use rustls::pki_types::pem::PemObject;
+use rustls::pki_types::PrivateKeyDer;
+use sha2::{Digest, Sha256, Sha512};
+
+use crate::db::request::{SignatureAlgorithm, SignatureConfig, SignatureEncoding};
+
+/// Renders the template against `request` and puts the signature in the configured
+/// header. Runs after the request is built, so the body it covers is the one sent.
+pub fn sign(
+    request: &mut Request,
+    config: &SignatureConfig,
+    now: SystemTime,
+) -> Result<(), String> {
+    if config.template.is_empty() {
+        return Err("Signature template is required".to_string());
+    }
+    let header = HeaderName::from_bytes(config.header.trim().as_bytes())
+        .map_err(|_| format!("Invalid signature header name: {}", config.header))?;
+
+    let elapsed = now
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default();
+    let timestamp = elapsed.as_secs().to_string();
+    let message = render(request, config, &timestamp, elapsed.as_millis())?;
+
+    let signature = match config.encoding {
+        SignatureEncoding::Hex => hex(&signature(config, message.as_bytes())?),
+        SignatureEncoding::Base64 => {
+            base64::engine::general_purpose::STANDARD.encode(signature(config, message.as_bytes())?)
+        }
+        SignatureEncoding::Jwt => jwt(config, &message)?,
+    };
+
+    let headers = request.headers_mut();
+    headers.insert(
+        header,
+        HeaderValue::from_str(&format!("{}{}", config.prefix, signature))
+            .map_err(|_| "Signature prefix contains invalid characters".to_string())?,
+    );
+    if !config.timestamp_header.trim().is_empty() {
+        let name = HeaderName::from_bytes(config.timestamp_header.trim().as_bytes())
+            .map_err(|_| format!("Invalid timestamp header name: {}", config.timestamp_header))?;
+        headers.insert(
+            name,
+            HeaderValue::from_str(&timestamp).map_err(|e| e.to_string())?,
+        );
+    }
+
+    Ok(())
+}
+
+fn render(
+    request: &Request,
+    config: &SignatureConfig,
+    timestamp: &str,
+    timestamp_ms: u128,
+) -> Result<String, String> {
+    let template = &config.template;
+    let uses = |name: &str| template.contains(&format!("{{{}}}", name));
+
+    let body = if uses("body") || uses("body_sha256") {
+        match request.body() {
+            None => Vec::new(),
+            Some(body) => body
+                .as_bytes()
+                .ok_or("Streamed bodies (files, multipart) cannot be signed")?
+                .to_vec(),
+        }
+    } else {
+        Vec::new()
+    };
+
+    let url = request.url();
+    let target = match url.query() {
+        Some(query) => format!("{}?{}", url.path(), query),
+        None => url.path().to_string(),
+    };
+    let values = [
+        ("method", request.method().as_str().to_string()),
+        ("path", url.path().to_string()),
+        ("query", url.query().unwrap_or_default().to_string()),
+        ("target", target),
+        ("host", url.host_str().unwrap_or_default().to_string()),
+        ("url", url.to_string()),
+        ("timestamp", timestamp.to_string()),
+        ("timestamp_ms", timestamp_ms.to_string()),
+        ("nonce", uuid::Uuid::new_v4().simple().to_string()),
+        ("body", String::from_utf8_lossy(&body).into_owned()),
+        ("body_sha256", hex(&Sha256::digest(&body))),
+    ];
+
+    // Substituted in one pass, so that text a value brings in, such as a body that
+    // mentions `{nonce}`, is left as it is. JWT claims are JSON, so substituted values
+    // are escaped as JSON string content.
+    let json = config.encoding == SignatureEncoding::Jwt;
+    let mut rendered = String::with_capacity(template.len());
+    let mut rest = template.as_str();
+    while let Some(start) = rest.find('{') {
+        rendered.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        let value = after.find('}').and_then(|end| {
+            values
+                .iter()
+                .find(|(name, _)| *name == &after[..end])
+                .map(|(_, value)| (end, value))
+        });
+        let Some((end, value)) = value else {
+            rendered.push('{');
+            rest = after;
+            continue;
+        };
+        if json {
+            let quoted = serde_json::to_string(value).map_err(|e| e.to_string())?;
+            rendered.push_str(&quoted[1..quoted.len() - 1]);
+        } else {
+            rendered.push_str(value);
+        }
+        rest = &after[end + 1..];
+    }
+    rendered.push_str(rest);
+
+    Ok(rendered)
+}
+
+fn signature(config: &SignatureConfig, message: &[u8]) -> Result<Vec<u8>, String> {
+    match config.algorithm {
+        SignatureAlgorithm::HmacSha256 => {
+            let mut mac =
+                Hmac::<Sha256>::new_from_slice(config.key.as_bytes()).map_err(|e| e.to_string())?;
+            mac.update(message);
+            Ok(mac.finalize().into_bytes().to_vec())
+        }
+        SignatureAlgorithm::HmacSha512 => {
+            let mut mac =
+                Hmac::<Sha512>::new_from_slice(config.key.as_bytes()).map_err(|e| e.to_string())?;
+            mac.update(message);
+            Ok(mac.finalize().into_bytes().to_vec())
+        }
+        SignatureAlgorithm::Rs256 => {
+            let der = private_key(&config.key)?;
+            let key = RsaKeyPair::from_pkcs8(der.secret_der())
+                .or_else(|_| RsaKeyPair::from_der(der.secret_der()))
+                .map_err(|e| format!("Invalid RSA private key: {}", e))?;
+            let mut signature = vec![0; key.public_modulus_len()];
+            key.sign(
+                &RSA_PKCS1_SHA256,
+                &SystemRandom::new(),
+                message,
+                &mut signature,
+            )
+            .map_err(|_| "Failed to sign with the RSA key".to_string())?;
+            Ok(signature)
+        }
+        SignatureAlgorithm::Es256 => {
+            let der = private_key(&config.key)?;
+            let key = EcdsaKeyPair::from_private_key_der(
+                &ECDSA_P256_SHA256_FIXED_SIGNING,
+                der.secret_der(),
+            )
+            .map_err(|e| format!("Invalid P-256 private key: {}", e))?;
+            let signature = key
+                .sign(&SystemRandom::new(), message)
+                .map_err(|_| "Failed to sign with the EC key".to_string())?;
+            Ok(signature.as_ref().to_vec())
+        }
+    }
+}
+
+fn private_key(pem: &str) -> Result<PrivateKeyDer<'static>, String> {
+    PrivateKeyDer::from_pem_slice(pem.trim().as_bytes())
+        .map_err(|e| format!("Failed to read PEM private key: {}", e))
+}
+
+fn jwt(config: &SignatureConfig, claims: &str) -> Result<String, String> {
+    let claims: serde_json::Value = serde_json::from_str(claims)
+        .map_err(|e| format!("JWT claims template is not valid JSON: {}", e))?;
+    if !claims.is_object() {
+        return Err("JWT claims template must be a JSON object".to_string());
+    }
+
+    let alg = match config.algorithm {
+        SignatureAlgorithm::HmacSha256 => "HS256",
+        SignatureAlgorithm::HmacSha512 => "HS512",
+        SignatureAlgorithm::Rs256 => "RS256",
+        SignatureAlgorithm::Es256 => "ES256",
+    };
+    let encode = |bytes: &[u8]| base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(bytes);
+    let header = serde_json::json!({ "alg": alg, "typ": "JWT" });
+    let signing_input = format!(
+        "{}.{}",
+        encode(header.to_string().as_bytes()),
+        encode(claims.to_string().as_bytes())
+    );
+    let signature = signature(config, signing_input.as_bytes())?;
+
+    Ok(format!("{}.{}", signing_input, encode(&signature)))
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::{Method, Url};
+
+    fn request(body: &str) -> Request {
+        let mut request = Request::new(
+            Method::POST,
+            Url::parse("https://api.example.com/v1/orders?page=2").unwrap(),
+        );
+        *request.body_mut() = Some(body.to_string().into());
+        request
+    }
+
+    fn config(template: &str) -> SignatureConfig {
+        SignatureConfig {
+            key: "secret".to_string(),
+            template: template.to_string(),
+            header: "X-Signature".to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn placeholders_in_the_body_are_not_expanded() {
+        let request = request(r#"{"note":"{method} {timestamp}"}"#);
+        let rendered = render(&request, &config("{method}\n{body}"), "1700000000", 0).unwrap();
+
+        assert_eq!(rendered, "POST\n{\"note\":\"{method} {timestamp}\"}");
+    }
+
+    #[test]
+    fn unknown_placeholders_and_json_are_kept() {
+        let request = request("");
+        let rendered = render(
+            &request,
+            &config(r#"{"path":"{target}","x":"{unknown}"}"#),
+            "1",
+            0,
+        )
+        .unwrap();
+
+        assert_eq!(rendered, r#"{"path":"/v1/orders?page=2","x":"{unknown}"}"#);
+    }
+
+    #[test]
+    fn hmac_signature_goes_in_the_header() {
+        let mut request = request("hello");
+        let mut config = config("{method}\n{target}\n{timestamp}\n{body}");
+        config.prefix = "sha256=".to_string();
+        config.timestamp_header = "X-Timestamp".to_string();
+        let now = std::time::UNIX_EPOCH + std::time::Duration::from_secs(1_700_000_000);
+
+        sign(&mut request, &config, now).unwrap();
+
+        let mut mac = Hmac::<Sha256>::new_from_slice(b"secret").unwrap();
+        mac.update(b"POST\n/v1/orders?page=2\n1700000000\nhello");
+        let expected = format!("sha256={}", hex(&mac.finalize().into_bytes()));
+        assert_eq!(request.headers()["x-signature"], expected.as_str());
+        assert_eq!(request.headers()["x-timestamp"], "1700000000");
+    }
+}
@@ -0,0 +1,426 @@
+use std::time::SystemTime;
+
+use hmac::{Hmac, Mac};
+use reqwest::header::{HeaderName, HeaderValue, AUTHORIZATION};
+use reqwest::Request;
+use sha2::{Digest, Sha256};
+
+use crate::db::request::AwsCredentials;
+
+const ALGORITHM: &str = "AWS4-HMAC-SHA256";
+// S3 accepts streamed bodies without a hash of their content
+const UNSIGNED_PAYLOAD: &str = "UNSIGNED-PAYLOAD";
+
+/// Signs `request` in place with AWS Signature Version 4. Every header already on the
+/// request is signed, so this has to run after the headers and body are final.
+///
+/// A streamed body cannot be hashed from the request, so its hash is passed as
+/// `streamed_hash`; without one it is only accepted by S3.
+pub fn sign(
+    request: &mut Request,
+    credentials: &AwsCredentials,
+    streamed_hash: Option<&str>,
+    now: SystemTime,
+) -> Result<(), String> {
+    if credentials.access_key_id.trim().is_empty() || credentials.secret_access_key.is_empty() {
+        return Err("AWS access key ID and secret access key are required".to_string());
+    }
+    if credentials.region.trim().is_empty() || credentials.service.trim().is_empty() {
+        return Err("AWS region and service are required".to_string());
+    }
+
+    let region = credentials.region.trim();
+    let service = credentials.service.trim();
+    let (amz_date, date) = format_time(now);
+
+    let payload_hash = match request.body() {
+        None => hex(&Sha256::digest(b"")),
+        Some(body) => match (body.as_bytes(), streamed_hash) {
+            (Some(bytes), _) => hex(&Sha256::digest(bytes)),
+            (None, Some(hash)) => hash.to_string(),
+            (None, None) if service == "s3" => UNSIGNED_PAYLOAD.to_string(),
+            (None, None) => {
+                return Err(format!(
+                    "Multipart bodies with files cannot be signed for the {} service",
+                    service
+                ))
+            }
+        },
+    };
+
+    let headers = request.headers_mut();
+    headers.remove(AUTHORIZATION);
+    headers.insert(
+        HeaderName::from_static("x-amz-date"),
+        HeaderValue::from_str(&amz_date).map_err(|e| e.to_string())?,
+    );
+    if !credentials.session_token.is_empty() {
+        headers.insert(
+            HeaderName::from_static("x-amz-security-token"),
+            HeaderValue::from_str(credentials.session_token.trim())
+                .map_err(|_| "AWS session token contains invalid characters".to_string())?,
+        );
+    }
+    // S3 refuses requests without the payload hash header
+    if service == "s3" {
+        headers.insert(
+            HeaderName::from_static("x-amz-content-sha256"),
+            HeaderValue::from_str(&payload_hash).map_err(|e| e.to_string())?,
+        );
+    }
+
+    let url = request.url();
+    let host = match (url.host_str(), url.port()) {
+        (Some(host), Some(port)) => format!("{}:{}", host, port),
+        (Some(host), None) => host.to_string(),
+        (None, _) => return Err("AWS signing requires a URL with a host".to_string()),
+    };
+
+    let mut canonical_headers: Vec<(String, String)> = request
+        .headers()
+        .iter()
+        .filter(|(name, _)| *name != AUTHORIZATION)
+        .map(|(name, value)| {
+            let value = String::from_utf8_lossy(value.as_bytes());
+            (
+                name.as_str().to_string(),
+                value.split_whitespace().collect::<Vec<_>>().join(" "),
+            )
+        })
+        .collect();
+    if !request.headers().contains_key("host") {
+        canonical_headers.push(("host".to_string(), host));
+    }
+    canonical_headers.sort();
+
+    // Repeated headers are combined into one comma-separated entry
+    let mut merged: Vec<(String, String)> = Vec::new();
+    for (name, value) in canonical_headers {
+        match merged.last_mut() {
+            Some((last, values)) if *last == name => {
+                values.push(',');
+                values.push_str(&value);
+            }
+            _ => merged.push((name, value)),
+        }
+    }
+
+    let signed_headers = merged
+        .iter()
+        .map(|(name, _)| name.as_str())
+        .collect::<Vec<_>>()
+        .join(";");
+
+    let canonical_request = format!(
+        "{}\n{}\n{}\n{}\n{}\n{}",
+        request.method().as_str(),
+        canonical_uri(url.path(), service),
+        canonical_query(url),
+        merged
+            .iter()
+            .map(|(name, value)| format!("{}:{}\n", name, value))
+            .collect::<String>(),
+        signed_headers,
+        payload_hash
+    );
+
+    let scope = format!("{}/{}/{}/aws4_request", date, region, service);
+    let string_to_sign = format!(
+        "{}\n{}\n{}\n{}",
+        ALGORITHM,
+        amz_date,
+        scope,
+        hex(&Sha256::digest(canonical_request.as_bytes()))
+    );
+
+    let mut key = hmac(
+        format!("AWS4{}", credentials.secret_access_key).as_bytes(),
+        date.as_bytes(),
+    );
+    for part in [region, service, "aws4_request"] {
+        key = hmac(&key, part.as_bytes());
+    }
+    let signature = hex(&hmac(&key, string_to_sign.as_bytes()));
+
+    let authorization = format!(
+        "{} Credential={}/{}, SignedHeaders={}, Signature={}",
+        ALGORITHM,
+        credentials.access_key_id.trim(),
+        scope,
+        signed_headers,
+        signature
+    );
+    request.headers_mut().insert(
+        AUTHORIZATION,
+        HeaderValue::from_str(&authorization).map_err(|e| e.to_string())?,
+    );
+
+    Ok(())
+}
+
+// S3 signs the path as sent; other services expect each segment encoded twice
+fn canonical_uri(path: &str, service: &str) -> String {
+    if path.is_empty() {
+        return "/".to_string();
+    }
+
+    path.split('/')
+        .map(|segment| {
+            let encoded = uri_encode(percent_decode(segment));
+            if service == "s3" {
+                encoded
+            } else {
+                uri_encode(&encoded)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+fn canonical_query(url: &reqwest::Url) -> String {
+    let mut pairs: Vec<(String, String)> = url
+        .query_pairs()
+        .map(|(key, value)| (uri_encode(key.as_bytes()), uri_encode(value.as_bytes())))
+        .collect();
+    pairs.sort();
+
+    pairs
+        .iter()
+        .map(|(key, value)| format!("{}={}", key, value))
+        .collect::<Vec<_>>()
+        .join("&")
+}
+
+// Percent-encodes everything except unreserved characters (RFC 3986)
+fn uri_encode(value: impl AsRef<[u8]>) -> String {
+    value
+        .as_ref()
+        .iter()
+        .map(|&b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                (b as char).to_string()
+            }
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+// Undoes the URL parser's encoding so the segment can be encoded the AWS way
+fn percent_decode(value: &str) -> Vec<u8> {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let escaped = bytes
+            .get(i + 1..i + 3)
+            .filter(|_| bytes[i] == b'%')
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match escaped {
+            Some(byte) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            None => {
+                decoded.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+    decoded
+}
+
+/// Whether a file sent as the body has to be hashed up front for `credentials`. S3
+/// takes large uploads unsigned rather than having them read twice.
+pub fn needs_file_hash(credentials: &AwsCredentials) -> bool {
+    credentials.service.trim() != "s3"
+}
+
+/// SHA-256 of a file, read in chunks, for signing a body that is streamed from it.
+pub async fn file_hash(path: &str) -> Result<String, String> {
+    use tokio::io::AsyncReadExt;
+
+    let mut file = tokio::fs::File::open(path)
+        .await
+        .map_err(|e| format!("Failed to open {}: {}", path, e))?;
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0; 64 * 1024];
+    loop {
+        let read = file.read(&mut buffer).await.map_err(|e| e.to_string())?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+    Ok(hex(&hasher.finalize()))
+}
+
+fn hmac(key: &[u8], data: &[u8]) -> Vec<u8> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(data);
+    mac.finalize().into_bytes().to_vec()
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+// Returns the `YYYYMMDDTHHMMSSZ` timestamp and the `YYYYMMDD` date in UTC
+fn format_time(time: SystemTime) -> (String, String) {
+    let secs = time
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs() as i64;
+    let days = secs.div_euclid(86_400);
+    let seconds_of_day = secs.rem_euclid(86_400);
+
+    // Civil date from days since the epoch (Howard Hinnant's algorithm)
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    let date = format!("{:04}{:02}{:02}", year, month, day);
+    let amz_date = format!(
+        "{}T{:02}{:02}{:02}Z",
+        date,
+        seconds_of_day / 3600,
+        seconds_of_day % 3600 / 60,
+        seconds_of_day % 60
+    );
+
+    (amz_date, date)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::CONTENT_TYPE;
+    use reqwest::Method;
+    use std::time::{Duration, UNIX_EPOCH};
+
+    // Credentials and time of the AWS Signature Version 4 test suite
+    fn credentials(service: &str) -> AwsCredentials {
+        AwsCredentials {
+            access_key_id: "AKIDEXAMPLE".to_string(),
+            secret_access_key: "wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY".to_string(),
+            region: "us-east-1".to_string(),
+            service: service.to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn now() -> SystemTime {
+        // 20150830T123600Z
+        UNIX_EPOCH + Duration::from_secs(1_440_938_160)
+    }
+
+    fn signed(mut request: Request, service: &str) -> String {
+        sign(&mut request, &credentials(service), None, now()).unwrap();
+        assert_eq!(request.headers()["x-amz-date"], "20150830T123600Z");
+        request.headers()[AUTHORIZATION]
+            .to_str()
+            .unwrap()
+            .to_string()
+    }
+
+    fn request(method: Method, url: &str) -> Request {
+        Request::new(method, url.parse().unwrap())
+    }
+
+    #[test]
+    fn get_vanilla() {
+        let authorization = signed(
+            request(Method::GET, "https://example.amazonaws.com/"),
+            "service",
+        );
+        assert_eq!(
+            authorization,
+            "AWS4-HMAC-SHA256 Credential=AKIDEXAMPLE/20150830/us-east-1/service/aws4_request, \
+             SignedHeaders=host;x-amz-date, \
+             Signature=5fa00fa31553b73ebf1942676e86291e8372ff2a2260956d9b8aae1d763fbf31"
+        );
+    }
+
+    #[test]
+    fn get_vanilla_query_order_key_case() {
+        let authorization = signed(
+            request(
+                Method::GET,
+                "https://example.amazonaws.com/?Param2=value2&Param1=value1",
+            ),
+            "service",
+        );
+        assert!(authorization.ends_with(
+            "Signature=b97d918cfa904a5beff61c982a1b6f458b799221646efd99d3219ec94cdf2500"
+        ));
+    }
+
+    #[test]
+    fn post_vanilla() {
+        let authorization = signed(
+            request(Method::POST, "https://example.amazonaws.com/"),
+            "service",
+        );
+        assert!(authorization.ends_with(
+            "Signature=5da7c1a2acd57cee7505fc6676e4e544621c30862966e37dddb68e92efbe5d6b"
+        ));
+    }
+
+    #[test]
+    fn iam_list_users() {
+        let mut request = request(
+            Method::GET,
+            "https://iam.amazonaws.com/?Action=ListUsers&Version=2010-05-08",
+        );
+        request.headers_mut().insert(
+            CONTENT_TYPE,
+            HeaderValue::from_static("application/x-www-form-urlencoded; charset=utf-8"),
+        );
+        let authorization = signed(request, "iam");
+        assert_eq!(
+            authorization,
+            "AWS4-HMAC-SHA256 Credential=AKIDEXAMPLE/20150830/us-east-1/iam/aws4_request, \
+             SignedHeaders=content-type;host;x-amz-date, \
+             Signature=5d672d79c15b13162d9279b0855cfba6789a8edb4c82c400e06b5924a6f2b5d7"
+        );
+    }
+
+    #[tokio::test]
+    async fn streamed_bodies_need_a_hash_outside_s3() {
+        let path = std::env::temp_dir().join(format!("sigv4-{}", uuid::Uuid::new_v4()));
+        std::fs::write(&path, b"content").unwrap();
+        let path = path.to_string_lossy().into_owned();
+        let streamed = || async {
+            let mut request = request(Method::PUT, "https://example.amazonaws.com/upload");
+            let file = tokio::fs::File::open(&path).await.unwrap();
+            *request.body_mut() = Some(file.into());
+            request
+        };
+
+        let mut request = streamed().await;
+        assert!(sign(&mut request, &credentials("execute-api"), None, now()).is_err());
+
+        let hash = file_hash(&path).await.unwrap();
+        assert_eq!(hash, hex(&Sha256::digest(b"content")));
+        let mut request = streamed().await;
+        sign(
+            &mut request,
+            &credentials("execute-api"),
+            Some(&hash),
+            now(),
+        )
+        .unwrap();
+
+        let mut request = streamed().await;
+        sign(&mut request, &credentials("s3"), None, now()).unwrap();
+        assert_eq!(request.headers()["x-amz-content-sha256"], UNSIGNED_PAYLOAD);
+
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,237 @@
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use std::time::Instant;
+
+use hyper_util::client::legacy::connect::Connection;
+use reqwest::dns::{Addrs, Name, Resolve, Resolving};
+use serde::{Deserialize, Serialize};
+use tower_layer::Layer;
+use tower_service::Service;
+
+use super::connection::Handshakes;
+
+/// Duration of each phase of a request, in milliseconds.
+///
+/// Connection phases are `None` when no new connection was opened for the request,
+/// and `tls_ms` is `None` for plain HTTP.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Timings {
+    pub dns_ms: Option<f64>,
+    pub tcp_ms: Option<f64>,
+    pub tls_ms: Option<f64>,
+    pub ttfb_ms: f64,
+    pub download_ms: f64,
+    pub total_ms: f64,
+}
+
+// Phases of one connection, filled in while it is being established
+#[derive(Debug, Default)]
+struct Connecting {
+    dns_start: Option<Instant>,
+    dns_end: Option<Instant>,
+    tls_start: Option<Instant>,
+}
+
+#[derive(Debug)]
+struct Connected {
+    dns: Option<(Instant, Instant)>,
+    start: Instant,
+    tls_start: Option<Instant>,
+    end: Instant,
+}
+
+// Marks of the current hop; a redirect starts a new one
+#[derive(Debug, Default)]
+struct Marks {
+    hop: usize,
+    connection: Option<Connected>,
+    request_start: Option<Instant>,
+    response_start: Option<Instant>,
+}
+
+tokio::task_local! {
+    static MARKS: Arc<Mutex<Marks>>;
+    static CONNECTING: Arc<Mutex<Connecting>>;
+}
+
+// Records a mark for the request running in the current task. Connections that are
+// finished in the background after a pooled one was picked are not attributed to anyone.
+fn mark(f: impl FnOnce(&mut Marks)) {
+    let _ = MARKS.try_with(|marks| f(&mut marks.lock().unwrap()));
+}
+
+// Records a phase of the connection being established by the current task
+fn mark_connecting(f: impl FnOnce(&mut Connecting)) {
+    let _ = CONNECTING.try_with(|connecting| f(&mut connecting.lock().unwrap()));
+}
+
+/// Collects phase timings for everything executed inside [`TimingRecorder::scope`].
+pub struct TimingRecorder {
+    start: Instant,
+    marks: Arc<Mutex<Marks>>,
+}
+
+impl TimingRecorder {
+    /// Starts the clock for a new request.
+    pub fn start() -> Self {
+        Self {
+            start: Instant::now(),
+            marks: Arc::new(Mutex::new(Marks::default())),
+        }
+    }
+
+    pub async fn scope<F: Future>(&self, future: F) -> F::Output {
+        MARKS.scope(self.marks.clone(), future).await
+    }
+
+    /// Marks the moment a request is handed to the client. Each redirect starts a new
+    /// hop, and only the phases of the last one are reported.
+    pub fn mark_request_start() {
+        mark(|m| {
+            *m = Marks {
+                hop: m.hop + 1,
+                request_start: Some(Instant::now()),
+                ..Marks::default()
+            }
+        });
+    }
+
+    /// Marks the arrival of the response headers.
+    pub fn mark_response_start() {
+        mark(|m| m.response_start = Some(Instant::now()));
+    }
+
+    /// Computes the phase durations, treating now as the end of the download.
+    pub fn finish(&self) -> Timings {
+        let end = Instant::now();
+        let marks = self.marks.lock().unwrap();
+        let ms =
+            |from: Instant, to: Instant| to.saturating_duration_since(from).as_secs_f64() * 1000.0;
+
+        let connection = marks.connection.as_ref();
+        let dns_ms = connection
+            .and_then(|c| c.dns)
+            .map(|(start, end)| ms(start, end));
+        let tcp_ms = connection.map(|c| {
+            let tcp_start = c.dns.map_or(c.start, |(_, end)| end);
+            ms(tcp_start, c.tls_start.unwrap_or(c.end))
+        });
+        let tls_ms = connection.and_then(|c| Some(ms(c.tls_start?, c.end)));
+
+        // Waiting starts once the connection is ready, whether new or reused
+        let request_start = marks.request_start.unwrap_or(self.start);
+        let waiting_start = connection.map_or(request_start, |c| c.end.max(request_start));
+        let response_start = marks.response_start.unwrap_or(end);
+
+        Timings {
+            dns_ms,
+            tcp_ms,
+            tls_ms,
+            ttfb_ms: ms(waiting_start, response_start),
+            download_ms: ms(response_start, end),
+            total_ms: ms(self.start, end),
+        }
+    }
+}
+
+/// Marks the start of the TLS handshake of the connection being established, which is
+/// when its TCP connection is ready.
+pub(crate) fn mark_tls_start() {
+    mark_connecting(|c| {
+        c.tls_start.get_or_insert_with(Instant::now);
+    });
+}
+
+/// System resolver that records how long each lookup takes.
+pub struct TimingResolver;
+
+impl Resolve for TimingResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        let host = name.as_str().to_string();
+        Box::pin(async move {
+            mark_connecting(|c| c.dns_start = Some(Instant::now()));
+            let addrs = tokio::net::lookup_host((host, 0)).await;
+            mark_connecting(|c| c.dns_end = Some(Instant::now()));
+
+            let addrs: Addrs = Box::new(addrs?);
+            Ok(addrs)
+        })
+    }
+}
+
+/// Connector layer that times each connection it establishes, and files the phases
+/// under the hop that asked for it. The TLS details of each connection go to
+/// `handshakes`.
+#[derive(Clone)]
+pub struct TimingLayer {
+    handshakes: Arc<Handshakes>,
+}
+
+impl TimingLayer {
+    pub fn new(handshakes: Arc<Handshakes>) -> Self {
+        Self { handshakes }
+    }
+}
+
+impl<S> Layer<S> for TimingLayer {
+    type Service = TimingConnector<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        TimingConnector {
+            inner,
+            handshakes: self.handshakes.clone(),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct TimingConnector<S> {
+    inner: S,
+    handshakes: Arc<Handshakes>,
+}
+
+impl<S, R> Service<R> for TimingConnector<S>
+where
+    S: Service<R>,
+    S::Response: Connection,
+    S::Future: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<S::Response, S::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: R) -> Self::Future {
+        let hop = MARKS.try_with(|marks| marks.lock().unwrap().hop).ok();
+        let start = Instant::now();
+        let phases = Arc::new(Mutex::new(Connecting::default()));
+        let connecting = CONNECTING.scope(phases.clone(), self.inner.call(request));
+        let handshakes = self.handshakes.clone();
+
+        Box::pin(async move {
+            let result = handshakes.capture(connecting).await;
+            let end = Instant::now();
+            if result.is_ok() {
+                let phases = phases.lock().unwrap();
+                let connected = Connected {
+                    dns: phases.dns_start.zip(phases.dns_end),
+                    start,
+                    tls_start: phases.tls_start,
+                    end,
+                };
+                // A connection finished after its hop was redirected is left out
+                mark(|m| {
+                    if Some(m.hop) == hop {
+                        m.connection = Some(connected);
+                    }
+                });
+            }
+            result
+        })
+    }
+}
@@ -0,0 +1,479 @@
+use std::sync::Arc;
+
+use reqwest::Url;
+use rustls::client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier};
+use rustls::crypto::cipher::{
+    AeadKey, Iv, KeyBlockShape, MessageDecrypter, MessageEncrypter, Tls12AeadAlgorithm,
+    Tls13AeadAlgorithm, UnsupportedOperationError,
+};
+use rustls::crypto::{
+    verify_tls12_signature, verify_tls13_signature, ActiveKeyExchange, CompletedKeyExchange,
+    CryptoProvider, SupportedKxGroup,
+};
+use rustls::ffdhe_groups::FfdheGroup;
+use rustls::pki_types::pem::PemObject;
+use rustls::pki_types::{CertificateDer, PrivateKeyDer, PrivatePkcs8KeyDer, ServerName, UnixTime};
+use rustls::{
+    CipherSuite, CipherSuiteCommon, ClientConfig, ConnectionTrafficSecrets, DigitallySignedStruct,
+    DistinguishedName, NamedGroup, ProtocolVersion, SignatureScheme, SupportedCipherSuite,
+    Tls12CipherSuite, Tls13CipherSuite,
+};
+
+use super::connection::{self, Handshakes};
+use super::timing;
+use crate::db::tls::{ClientCertificate, TlsHostSettings};
+
+/// Builds the rustls configuration used for HTTPS requests, applying the settings of
+/// the host being contacted when there are any. Details of the handshakes that reqwest
+/// does not report are kept in `handshakes`.
+pub fn client_config(
+    host: Option<&TlsHostSettings>,
+    handshakes: Arc<Handshakes>,
+) -> Result<ClientConfig, String> {
+    let provider = CryptoProvider::get_default()
+        .cloned()
+        .unwrap_or_else(|| Arc::new(rustls::crypto::aws_lc_rs::default_provider()));
+    let provider = Arc::new(CryptoProvider {
+        cipher_suites: provider
+            .cipher_suites
+            .iter()
+            .map(|suite| recording_suite(*suite))
+            .collect(),
+        kx_groups: provider
+            .kx_groups
+            .iter()
+            .map(|group| timing_kx_group(*group))
+            .collect(),
+        ..(*provider).clone()
+    });
+
+    let verifier: Arc<dyn ServerCertVerifier> = match host {
+        Some(host) if host.insecure => Arc::new(NoVerification(provider.clone())),
+        Some(host) if !host.ca_paths.is_empty() => {
+            let roots = load_ca_certificates(&host.ca_paths)?;
+            Arc::new(
+                rustls_platform_verifier::Verifier::new_with_extra_roots(roots, provider.clone())
+                    .map_err(|e| format!("Failed to load CA certificates: {}", e))?,
+            )
+        }
+        _ => Arc::new(
+            rustls_platform_verifier::Verifier::new(provider.clone())
+                .map_err(|e| format!("Failed to load system certificates: {}", e))?,
+        ),
+    };
+
+    let verifier = Arc::new(RecordingVerifier {
+        inner: verifier,
+        handshakes,
+    });
+
+    let builder = ClientConfig::builder_with_provider(provider)
+        .with_safe_default_protocol_versions()
+        .map_err(|e| e.to_string())?
+        .dangerous()
+        .with_custom_certificate_verifier(verifier);
+
+    let mut config = match host.and_then(|h| h.client_cert.as_ref()) {
+        Some(client_cert) => {
+            let (chain, key) = load_client_certificate(client_cert)?;
+            builder
+                .with_client_auth_cert(chain, key)
+                .map_err(|e| format!("Invalid client certificate: {}", e))?
+        }
+        None => builder.with_no_client_auth(),
+    };
+
+    config.alpn_protocols = vec![b"h2".to_vec(), b"http/1.1".to_vec()];
+
+    Ok(config)
+}
+
+/// Picks the settings that apply to `url`: an exact host and port match first, then the
+/// exact host, then the most specific wildcard.
+pub fn find_host_settings<'a>(
+    settings: &'a [TlsHostSettings],
+    url: &Url,
+) -> Option<&'a TlsHostSettings> {
+    let host = url
+        .host_str()?
+        .trim_start_matches('[')
+        .trim_end_matches(']');
+    let host = host.to_lowercase();
+    let host_port = url
+        .port_or_known_default()
+        .map(|port| format!("{}:{}", host, port));
+
+    if let Some(host_port) = host_port {
+        if let Some(found) = settings.iter().find(|s| s.host == host_port) {
+            return Some(found);
+        }
+    }
+
+    if let Some(found) = settings.iter().find(|s| s.host == host) {
+        return Some(found);
+    }
+
+    settings
+        .iter()
+        .filter(|s| {
+            s.host
+                .strip_prefix("*.")
+                .is_some_and(|domain| host.ends_with(&format!(".{}", domain)))
+        })
+        .max_by_key(|s| s.host.len())
+}
+
+fn load_ca_certificates(paths: &[String]) -> Result<Vec<CertificateDer<'static>>, String> {
+    let mut roots = Vec::new();
+
+    for path in paths {
+        let certs = CertificateDer::pem_file_iter(path)
+            .map_err(|e| format!("Failed to read CA file {}: {}", path, e))?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| format!("Invalid CA file {}: {}", path, e))?;
+
+        if certs.is_empty() {
+            return Err(format!("No certificates found in {}", path));
+        }
+        roots.extend(certs);
+    }
+
+    Ok(roots)
+}
+
+fn load_client_certificate(
+    client_cert: &ClientCertificate,
+) -> Result<(Vec<CertificateDer<'static>>, PrivateKeyDer<'static>), String> {
+    match client_cert {
+        ClientCertificate::Pem {
+            cert_path,
+            key_path,
+        } => {
+            let chain = CertificateDer::pem_file_iter(cert_path)
+                .map_err(|e| format!("Failed to read certificate {}: {}", cert_path, e))?
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|e| format!("Invalid certificate {}: {}", cert_path, e))?;
+            if chain.is_empty() {
+                return Err(format!("No certificates found in {}", cert_path));
+            }
+
+            let key = PrivateKeyDer::from_pem_file(key_path)
+                .map_err(|e| format!("Failed to read private key {}: {}", key_path, e))?;
+
+            Ok((chain, key))
+        }
+        ClientCertificate::Pkcs12 { path, password } => {
+            let data =
+                std::fs::read(path).map_err(|e| format!("Failed to read {}: {}", path, e))?;
+            let keystore = p12_keystore::KeyStore::from_pkcs12(&data, password)
+                .map_err(|e| format!("Failed to open PKCS#12 bundle {}: {}", path, e))?;
+            let (_, key_chain) = keystore
+                .private_key_chain()
+                .ok_or_else(|| format!("No private key found in {}", path))?;
+
+            let chain = key_chain
+                .chain()
+                .iter()
+                .map(|cert| CertificateDer::from(cert.as_der().to_vec()))
+                .collect();
+            let key = PrivateKeyDer::Pkcs8(PrivatePkcs8KeyDer::from(key_chain.key().to_vec()));
+
+            Ok((chain, key))
+        }
+    }
+}
+
+// Remembers the intermediates the server sent with its certificate once the actual
+// verifier has accepted them
+#[derive(Debug)]
+struct RecordingVerifier {
+    inner: Arc<dyn ServerCertVerifier>,
+    handshakes: Arc<Handshakes>,
+}
+
+impl ServerCertVerifier for RecordingVerifier {
+    fn verify_server_cert(
+        &self,
+        end_entity: &CertificateDer<'_>,
+        intermediates: &[CertificateDer<'_>],
+        server_name: &ServerName<'_>,
+        ocsp_response: &[u8],
+        now: UnixTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        let verified = self.inner.verify_server_cert(
+            end_entity,
+            intermediates,
+            server_name,
+            ocsp_response,
+            now,
+        )?;
+        self.handshakes.remember_chain(end_entity, intermediates);
+        Ok(verified)
+    }
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        self.inner.verify_tls12_signature(message, cert, dss)
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        self.inner.verify_tls13_signature(message, cert, dss)
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.inner.supported_verify_schemes()
+    }
+
+    fn requires_raw_public_keys(&self) -> bool {
+        self.inner.requires_raw_public_keys()
+    }
+
+    fn root_hint_subjects(&self) -> Option<&[DistinguishedName]> {
+        self.inner.root_hint_subjects()
+    }
+}
+
+fn tls13_suites() -> impl Iterator<Item = &'static Tls13CipherSuite> {
+    rustls::crypto::aws_lc_rs::ALL_CIPHER_SUITES
+        .iter()
+        .filter_map(|suite| match suite {
+            SupportedCipherSuite::Tls13(suite) => Some(*suite),
+            SupportedCipherSuite::Tls12(_) => None,
+        })
+}
+
+fn tls12_suites() -> impl Iterator<Item = &'static Tls12CipherSuite> {
+    rustls::crypto::aws_lc_rs::ALL_CIPHER_SUITES
+        .iter()
+        .filter_map(|suite| match suite {
+            SupportedCipherSuite::Tls12(suite) => Some(*suite),
+            SupportedCipherSuite::Tls13(_) => None,
+        })
+}
+
+// rustls wants cipher suites with a static lifetime, so the recording wrappers are
+// created once, for every suite of the crypto provider the app is built with
+lazy_static::lazy_static! {
+    static ref TLS13_AEADS: Vec<RecordingTls13Aead> = tls13_suites()
+        .map(|suite| RecordingTls13Aead {
+            inner: suite.aead_alg,
+            suite: suite.common.suite,
+        })
+        .collect();
+    static ref TLS13_SUITES: Vec<Tls13CipherSuite> = tls13_suites()
+        .zip(TLS13_AEADS.iter())
+        .map(|(suite, aead_alg)| Tls13CipherSuite {
+            common: copy_common(&suite.common),
+            hkdf_provider: suite.hkdf_provider,
+            aead_alg,
+            quic: suite.quic,
+        })
+        .collect();
+    static ref TLS12_AEADS: Vec<RecordingTls12Aead> = tls12_suites()
+        .map(|suite| RecordingTls12Aead {
+            inner: suite.aead_alg,
+            suite: suite.common.suite,
+        })
+        .collect();
+    static ref TLS12_SUITES: Vec<Tls12CipherSuite> = tls12_suites()
+        .zip(TLS12_AEADS.iter())
+        .map(|(suite, aead_alg)| Tls12CipherSuite {
+            common: copy_common(&suite.common),
+            prf_provider: suite.prf_provider,
+            kx: suite.kx,
+            sign: suite.sign,
+            aead_alg,
+        })
+        .collect();
+    static ref KX_GROUPS: Vec<TimingKxGroup> = rustls::crypto::aws_lc_rs::ALL_KX_GROUPS
+        .iter()
+        .map(|group| TimingKxGroup(*group))
+        .collect();
+}
+
+// Swaps a cipher suite for its recording wrapper; suites without one are used as they are
+fn recording_suite(suite: SupportedCipherSuite) -> SupportedCipherSuite {
+    let id = suite.suite();
+    let tls13 = TLS13_SUITES.iter().find(|s| s.common.suite == id);
+    let tls12 = TLS12_SUITES.iter().find(|s| s.common.suite == id);
+    match (tls13, tls12) {
+        (Some(tls13), _) => SupportedCipherSuite::Tls13(tls13),
+        (None, Some(tls12)) => SupportedCipherSuite::Tls12(tls12),
+        (None, None) => suite,
+    }
+}
+
+fn copy_common(common: &CipherSuiteCommon) -> CipherSuiteCommon {
+    CipherSuiteCommon {
+        suite: common.suite,
+        hash_provider: common.hash_provider,
+        confidentiality_limit: common.confidentiality_limit,
+    }
+}
+
+// Swaps a key exchange group for its timing wrapper; groups without one are used as they are
+fn timing_kx_group(group: &'static dyn SupportedKxGroup) -> &'static dyn SupportedKxGroup {
+    KX_GROUPS
+        .iter()
+        .find(|g| g.0.name() == group.name())
+        .map_or(group, |g| g as &dyn SupportedKxGroup)
+}
+
+// The key share of the ClientHello is generated as the first step of the handshake, once
+// the TCP connection is up
+#[derive(Debug)]
+struct TimingKxGroup(&'static dyn SupportedKxGroup);
+
+impl SupportedKxGroup for TimingKxGroup {
+    fn start(&self) -> Result<Box<dyn ActiveKeyExchange>, rustls::Error> {
+        timing::mark_tls_start();
+        self.0.start()
+    }
+
+    fn start_and_complete(
+        &self,
+        peer_pub_key: &[u8],
+    ) -> Result<CompletedKeyExchange, rustls::Error> {
+        self.0.start_and_complete(peer_pub_key)
+    }
+
+    fn ffdhe_group(&self) -> Option<FfdheGroup<'static>> {
+        self.0.ffdhe_group()
+    }
+
+    fn name(&self) -> NamedGroup {
+        self.0.name()
+    }
+
+    fn fips(&self) -> bool {
+        self.0.fips()
+    }
+
+    fn usable_for_version(&self, version: ProtocolVersion) -> bool {
+        self.0.usable_for_version(version)
+    }
+}
+
+// Decrypters are created once the suite has been negotiated, resumed or not
+struct RecordingTls13Aead {
+    inner: &'static dyn Tls13AeadAlgorithm,
+    suite: CipherSuite,
+}
+
+impl Tls13AeadAlgorithm for RecordingTls13Aead {
+    fn encrypter(&self, key: AeadKey, iv: Iv) -> Box<dyn MessageEncrypter> {
+        self.inner.encrypter(key, iv)
+    }
+
+    fn decrypter(&self, key: AeadKey, iv: Iv) -> Box<dyn MessageDecrypter> {
+        connection::record_cipher_suite(self.suite);
+        self.inner.decrypter(key, iv)
+    }
+
+    fn key_len(&self) -> usize {
+        self.inner.key_len()
+    }
+
+    fn extract_keys(
+        &self,
+        key: AeadKey,
+        iv: Iv,
+    ) -> Result<ConnectionTrafficSecrets, UnsupportedOperationError> {
+        self.inner.extract_keys(key, iv)
+    }
+
+    fn fips(&self) -> bool {
+        self.inner.fips()
+    }
+}
+
+struct RecordingTls12Aead {
+    inner: &'static dyn Tls12AeadAlgorithm,
+    suite: CipherSuite,
+}
+
+impl Tls12AeadAlgorithm for RecordingTls12Aead {
+    fn encrypter(&self, key: AeadKey, iv: &[u8], extra: &[u8]) -> Box<dyn MessageEncrypter> {
+        self.inner.encrypter(key, iv, extra)
+    }
+
+    fn decrypter(&self, key: AeadKey, iv: &[u8]) -> Box<dyn MessageDecrypter> {
+        connection::record_cipher_suite(self.suite);
+        self.inner.decrypter(key, iv)
+    }
+
+    fn key_block_shape(&self) -> KeyBlockShape {
+        self.inner.key_block_shape()
+    }
+
+    fn extract_keys(
+        &self,
+        key: AeadKey,
+        iv: &[u8],
+        explicit: &[u8],
+    ) -> Result<ConnectionTrafficSecrets, UnsupportedOperationError> {
+        self.inner.extract_keys(key, iv, explicit)
+    }
+
+    fn fips(&self) -> bool {
+        self.inner.fips()
+    }
+}
+
+// Accepts any server certificate; handshake signatures are still checked so that the
+// connection itself is sound
+#[derive(Debug)]
+struct NoVerification(Arc<CryptoProvider>);
+
+impl ServerCertVerifier for NoVerification {
+    fn verify_server_cert(
+        &self,
+        _end_entity: &CertificateDer<'_>,
+        _intermediates: &[CertificateDer<'_>],
+        _server_name: &ServerName<'_>,
+        _ocsp_response: &[u8],
+        _now: UnixTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        Ok(ServerCertVerified::assertion())
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        verify_tls12_signature(
+            message,
+            cert,
+            dss,
+            &self.0.signature_verification_algorithms,
+        )
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        verify_tls13_signature(
+            message,
+            cert,
+            dss,
+            &self.0.signature_verification_algorithms,
+        )
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.0.signature_verification_algorithms.supported_schemes()
+    }
+}
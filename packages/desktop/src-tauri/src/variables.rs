@@ -0,0 +1,53 @@
+use std::collections::HashMap;
+
+// Variables may reference other variables; stop expanding after this many passes
+const MAX_DEPTH: usize = 8;
+
+/// Replaces `{{name}}` placeholders in `input` with values from `variables`.
+///
+/// Unknown placeholders are left untouched and their names are appended to `unresolved`.
+pub fn substitute(
+    input: &str,
+    variables: &HashMap<String, String>,
+    unresolved: &mut Vec<String>,
+) -> String {
+    substitute_at_depth(input, variables, unresolved, 0)
+}
+
+fn substitute_at_depth(
+    input: &str,
+    variables: &HashMap<String, String>,
+    unresolved: &mut Vec<String>,
+    depth: usize,
+) -> String {
+    let mut output = String::with_capacity(input.len());
+    let mut rest = input;
+
+    while let Some(start) = rest.find("{{") {
+        let Some(end) = rest[start + 2..].find("}}") else {
+            break;
+        };
+
+        output.push_str(&rest[..start]);
+        let placeholder = &rest[start..start + 2 + end + 2];
+        let name = placeholder[2..placeholder.len() - 2].trim();
+
+        match variables.get(name) {
+            Some(value) if depth < MAX_DEPTH => {
+                output.push_str(&substitute_at_depth(value, variables, unresolved, depth + 1));
+            }
+            Some(value) => output.push_str(value),
+            None => {
+                if !name.is_empty() && !unresolved.iter().any(|n| n == name) {
+                    unresolved.push(name.to_string());
+                }
+                output.push_str(placeholder);
+            }
+        }
+
+        rest = &rest[start + 2 + end + 2..];
+    }
+
+    output.push_str(rest);
+    output
+}
@@ -82,6 +82,9 @@ export function Sidebar() {
       body: request.response_body ?? '',
       time_ms: request.response_time_ms ?? 0,
       size_bytes: request.response_size_bytes ?? 0,
+      timings: request.response_timings ?? undefined,
+      redirects: request.response_redirects ?? undefined,
+      connection: request.response_connection ?? undefined,
     } : null;
     
     await openRequest(activeWorkspaceId, {
@@ -92,6 +95,7 @@ export function Sidebar() {
       headers: request.headers,
       body_type: request.body_type,
       body_content: request.body_content,
+      auth: request.auth,
     }, response);
   };
 
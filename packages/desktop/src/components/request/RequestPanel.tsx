@@ -148,7 +148,7 @@ export function RequestPanel() {
     if (!activeTabId || !activeTab || !activeWorkspaceId) return;
     if (!activeTab.state.url.trim()) return;
 
-    const { method, url, headers, body_type, body_content, params } = activeTab.state;
+    const { method, url, headers, body_type, body_content, params, settings, auth } = activeTab.state;
     
     setTabLoading(activeTabId, true);
     setResponse(activeTabId, null);
@@ -174,7 +174,9 @@ export function RequestPanel() {
         headers: allHeaders,
         body_type,
         body_content: body_type !== 'none' ? body_content : null,
-      });
+        settings,
+        auth,
+      }, activeWorkspaceId);
 
       setResponse(activeTabId, response);
       
@@ -192,6 +194,10 @@ export function RequestPanel() {
         response_body: response.body,
         response_time_ms: response.time_ms,
         response_size_bytes: response.size_bytes,
+        response_timings: response.timings ?? null,
+        response_redirects: response.redirects ?? null,
+        response_connection: response.connection ?? null,
+        auth: auth ?? null,
       });
       
       addLatestRequest(savedRequest);
@@ -470,7 +476,13 @@ export function RequestPanel() {
                       updateTabState(activeTabId, { body_content: value });
                     }
                   }}
-                  placeholder={body_type === 'raw' ? '{\n  "key": "value"\n}' : 'Enter request body...'}
+                  placeholder={
+                    body_type === 'raw'
+                      ? '{\n  "key": "value"\n}'
+                      : body_type === 'binary'
+                        ? 'Path to the file to upload, e.g. /home/user/artifact.zip'
+                        : 'Enter request body...'
+                  }
                   hasError={!!jsonError}
                 />
               </div>
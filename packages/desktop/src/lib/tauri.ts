@@ -1,4 +1,5 @@
 import { invoke } from '@tauri-apps/api/core';
+import { listen, type UnlistenFn } from '@tauri-apps/api/event';
 import type {
   Workspace,
   SavedRequest,
@@ -6,8 +7,28 @@ import type {
   TabState,
   AppSettings,
   CreateRequestInput,
+  Collection,
+  CollectionItem,
+  CreateCollectionItemInput,
+  Environment,
+  EnvironmentVariable,
   HttpRequestInput,
   ResponseData,
+  TimeoutSettings,
+  ProxySettings,
+  TlsHostSettings,
+  TlsHostSettingsInput,
+  Cookie,
+  CookieInput,
+  OAuth2Config,
+  OAuthToken,
+  CodegenTarget,
+  CodegenSource,
+  CurlImport,
+  ImportIssue,
+  ImportReport,
+  HarTarget,
+  WorkspaceDirectoryEvent,
 } from '@/types';
 
 // ============ Workspace Commands ============
@@ -32,6 +53,27 @@ export async function deleteWorkspace(id: string): Promise<void> {
   return invoke('delete_workspace', { id });
 }
 
+// Stores a workspace's collections and environments in a directory (e.g. in a git repo) and
+// reloads them when its files change; null keeps them in the app only. A directory that already
+// holds a workspace replaces its collections and environments.
+export async function setWorkspaceDirectory(id: string, directory: string | null): Promise<Workspace> {
+  return invoke('set_workspace_directory', { id, directory });
+}
+
+// Called after a workspace was reloaded from its directory, e.g. after a `git pull`
+export async function onWorkspaceDirectoryChanged(
+  handler: (event: WorkspaceDirectoryEvent) => void
+): Promise<UnlistenFn> {
+  return listen<WorkspaceDirectoryEvent>('workspace-directory-changed', e => handler(e.payload));
+}
+
+// Called when the files of a workspace could not be reloaded, such as during a merge conflict
+export async function onWorkspaceDirectoryError(
+  handler: (event: WorkspaceDirectoryEvent) => void
+): Promise<UnlistenFn> {
+  return listen<WorkspaceDirectoryEvent>('workspace-directory-error', e => handler(e.payload));
+}
+
 // ============ Request Commands ============
 
 export async function getRequestsByWorkspace(
@@ -130,15 +172,305 @@ export async function saveAllSettings(settings: AppSettings): Promise<void> {
   return invoke('save_all_settings', { settings });
 }
 
+export async function getTimeoutSettings(workspaceId: string): Promise<TimeoutSettings> {
+  return invoke('get_timeout_settings', { workspaceId });
+}
+
+export async function saveTimeoutSettings(
+  workspaceId: string,
+  timeouts: TimeoutSettings
+): Promise<void> {
+  return invoke('save_timeout_settings', { workspaceId, timeouts });
+}
+
+// Without a workspace id these read and write the global proxy settings
+export async function getProxySettings(workspaceId?: string): Promise<ProxySettings | null> {
+  return invoke('get_proxy_settings', { workspaceId: workspaceId ?? null });
+}
+
+// Saving null for a workspace removes its override so the global settings apply
+export async function saveProxySettings(
+  proxy: ProxySettings | null,
+  workspaceId?: string
+): Promise<void> {
+  return invoke('save_proxy_settings', { workspaceId: workspaceId ?? null, proxy });
+}
+
+// ============ Environment Commands ============
+
+export async function getEnvironments(workspaceId?: string): Promise<Environment[]> {
+  return invoke('get_environments', { workspaceId: workspaceId ?? null });
+}
+
+export async function createEnvironment(
+  name: string,
+  workspaceId?: string,
+  variables?: EnvironmentVariable[]
+): Promise<Environment> {
+  return invoke('create_environment', {
+    workspaceId: workspaceId ?? null,
+    name,
+    variables: variables ?? null,
+  });
+}
+
+export async function updateEnvironment(
+  id: string,
+  name: string,
+  variables: EnvironmentVariable[]
+): Promise<Environment> {
+  return invoke('update_environment', { id, name, variables });
+}
+
+export async function deleteEnvironment(id: string): Promise<void> {
+  return invoke('delete_environment', { id });
+}
+
+export async function setActiveEnvironment(
+  workspaceId: string | null,
+  environmentId: string | null
+): Promise<void> {
+  return invoke('set_active_environment', { workspaceId, environmentId });
+}
+
+// ============ Collection Commands ============
+
+export async function getCollections(workspaceId: string): Promise<Collection[]> {
+  return invoke('get_collections', { workspaceId });
+}
+
+export async function createCollection(workspaceId: string, name: string): Promise<Collection> {
+  return invoke('create_collection', { workspaceId, name });
+}
+
+export async function renameCollection(id: string, name: string): Promise<Collection> {
+  return invoke('rename_collection', { id, name });
+}
+
+export async function deleteCollection(id: string): Promise<void> {
+  return invoke('delete_collection', { id });
+}
+
+export async function reorderCollections(
+  workspaceId: string,
+  collectionIds: string[]
+): Promise<void> {
+  return invoke('reorder_collections', { workspaceId, collectionIds });
+}
+
+export async function getCollectionItems(collectionId: string): Promise<CollectionItem[]> {
+  return invoke('get_collection_items', { collectionId });
+}
+
+export async function getCollectionItem(id: string): Promise<CollectionItem | null> {
+  return invoke('get_collection_item', { id });
+}
+
+export async function createCollectionItem(
+  input: CreateCollectionItemInput
+): Promise<CollectionItem> {
+  return invoke('create_collection_item', { input });
+}
+
+export async function renameCollectionItem(id: string, name: string): Promise<CollectionItem> {
+  return invoke('rename_collection_item', { id, name });
+}
+
+export async function moveCollectionItem(
+  id: string,
+  collectionId: string,
+  parentId: string | null,
+  position: number
+): Promise<CollectionItem> {
+  return invoke('move_collection_item', { id, collectionId, parentId, position });
+}
+
+export async function duplicateCollectionItem(id: string): Promise<CollectionItem> {
+  return invoke('duplicate_collection_item', { id });
+}
+
+export async function deleteCollectionItem(id: string): Promise<void> {
+  return invoke('delete_collection_item', { id });
+}
+
+export async function saveTabToCollection(tabId: string, state: TabState): Promise<CollectionItem> {
+  return invoke('save_tab_to_collection', { tabId, state });
+}
+
+export async function saveTabAsCollectionRequest(
+  tabId: string,
+  state: TabState,
+  collectionId: string,
+  name: string,
+  parentId?: string
+): Promise<CollectionItem> {
+  return invoke('save_tab_as_collection_request', {
+    tabId,
+    state,
+    collectionId,
+    parentId: parentId ?? null,
+    name,
+  });
+}
+
+// ============ TLS Commands ============
+
+export async function getTlsHostSettings(): Promise<TlsHostSettings[]> {
+  return invoke('get_tls_host_settings');
+}
+
+export async function createTlsHostSettings(input: TlsHostSettingsInput): Promise<TlsHostSettings> {
+  return invoke('create_tls_host_settings', { input });
+}
+
+export async function updateTlsHostSettings(
+  id: string,
+  input: TlsHostSettingsInput
+): Promise<TlsHostSettings> {
+  return invoke('update_tls_host_settings', { id, input });
+}
+
+export async function deleteTlsHostSettings(id: string): Promise<void> {
+  return invoke('delete_tls_host_settings', { id });
+}
+
+// ============ Cookie Commands ============
+
+export async function getCookies(workspaceId: string, domain?: string): Promise<Cookie[]> {
+  return invoke('get_cookies', { workspaceId, domain: domain ?? null });
+}
+
+// Adds a cookie, or replaces the one with the same domain, path and name
+export async function saveCookie(workspaceId: string, input: CookieInput): Promise<Cookie> {
+  return invoke('save_cookie', { workspaceId, input });
+}
+
+export async function updateCookie(id: string, input: CookieInput): Promise<Cookie> {
+  return invoke('update_cookie', { id, input });
+}
+
+export async function deleteCookie(id: string): Promise<void> {
+  return invoke('delete_cookie', { id });
+}
+
+// Returns the number of cookies removed
+export async function clearCookies(workspaceId: string, domain?: string): Promise<number> {
+  return invoke('clear_cookies', { workspaceId, domain: domain ?? null });
+}
+
+// ============ OAuth Commands ============
+
+// Returns the cached token, refreshing or requesting one as needed; force always requests a new one
+export async function getOAuthToken(
+  workspaceId: string,
+  config: OAuth2Config,
+  force = false
+): Promise<OAuthToken> {
+  return invoke('get_oauth_token', { workspaceId, config, force });
+}
+
+// Returns the number of tokens removed
+export async function clearOAuthTokens(workspaceId: string): Promise<number> {
+  return invoke('clear_oauth_tokens', { workspaceId });
+}
+
+// ============ Import Commands ============
+
+// Parses a curl command (as copied from docs or devtools) into the state of a new tab
+export async function importCurl(command: string): Promise<CurlImport> {
+  return invoke('import_curl', { command });
+}
+
+// Imports a Postman v2 collection or environment file; a new workspace is created when none is given
+export async function importPostman(content: string, workspaceId?: string): Promise<ImportReport> {
+  return invoke('import_postman', { workspaceId: workspaceId ?? null, content });
+}
+
+// Imports an OpenAPI 3 or Swagger 2 document (JSON or YAML) as a collection, with an environment per server
+export async function importOpenApi(content: string, workspaceId?: string): Promise<ImportReport> {
+  return invoke('import_openapi', { workspaceId: workspaceId ?? null, content });
+}
+
+// Imports an Insomnia v4 JSON export; each Insomnia workspace becomes a collection
+export async function importInsomnia(content: string, workspaceId?: string): Promise<ImportReport> {
+  return invoke('import_insomnia', { workspaceId: workspaceId ?? null, content });
+}
+
+// Imports a Bruno collection from its directory (the one containing bruno.json)
+export async function importBruno(path: string, workspaceId?: string): Promise<ImportReport> {
+  return invoke('import_bruno', { workspaceId: workspaceId ?? null, path });
+}
+
+// Imports a HAR archive from browser devtools into history (with responses) or as a collection
+export async function importHar(
+  content: string,
+  target: HarTarget,
+  workspaceId?: string
+): Promise<ImportReport> {
+  return invoke('import_har', { workspaceId: workspaceId ?? null, content, target });
+}
+
+// Imports a workspace archive as a new workspace; a number is appended to a name already in use
+export async function importWorkspace(content: string, name?: string): Promise<ImportReport> {
+  return invoke('import_workspace', { content, name: name ?? null });
+}
+
+// ============ Export Commands ============
+
+// Writes a workspace's history (or the given entries of it) as a HAR 1.2 archive
+export async function exportHar(workspaceId: string, requestIds?: string[]): Promise<string> {
+  return invoke('export_har', { workspaceId, requestIds: requestIds ?? null });
+}
+
+// Writes a workspace as a JSON archive, without secret variable values, the proxy password
+// or auth credentials
+export async function exportWorkspace(
+  workspaceId: string,
+  includeHistory = false,
+  includeTabs = false
+): Promise<string> {
+  return invoke('export_workspace', { workspaceId, includeHistory, includeTabs });
+}
+
+// ============ HTTP File Commands ============
+
+// Opens a .http/.rest file as a new collection; its @variables become an environment
+export async function openHttpFile(path: string, workspaceId?: string): Promise<ImportReport> {
+  return invoke('open_http_file', { workspaceId: workspaceId ?? null, path });
+}
+
+// Writes a collection opened from a .http file back to it, keeping unchanged requests as written;
+// returns what could not be expressed in the file
+export async function saveHttpFile(collectionId: string): Promise<ImportIssue[]> {
+  return invoke('save_http_file', { collectionId });
+}
+
+// ============ Codegen Commands ============
+
+// Generates a curl/HTTPie/wget command or a client snippet, with workspace variables substituted
+export async function generateCode(
+  target: CodegenTarget,
+  source: CodegenSource,
+  workspaceId?: string
+): Promise<string> {
+  return invoke('generate_code', { workspaceId: workspaceId ?? null, target, source });
+}
+
 // ============ HTTP Commands ============
 
 export async function sendHttpRequest(
   requestId: string,
-  input: HttpRequestInput
+  input: HttpRequestInput,
+  workspaceId?: string
 ): Promise<ResponseData> {
-  return invoke('send_http_request', { requestId, input });
+  return invoke('send_http_request', { requestId, workspaceId: workspaceId ?? null, input });
 }
 
 export async function cancelHttpRequest(requestId: string): Promise<boolean> {
   return invoke('cancel_http_request', { requestId });
 }
+
+// Returns the number of pooled clients that were dropped
+export async function resetHttpConnections(): Promise<number> {
+  return invoke('reset_http_connections');
+}
@@ -1,5 +1,5 @@
 import { create } from 'zustand';
-import type { Tab, TabState, HttpMethod, ResponseData } from '@/types';
+import type { Tab, TabState, HttpMethod, ResponseData, RequestAuth } from '@/types';
 import * as api from '@/lib/tauri';
 import { MAX_TABS_IN_MEMORY } from '@/lib/constants';
 
@@ -36,6 +36,7 @@ interface TabStore {
     headers: Array<{ key: string; value: string; enabled: boolean }> | null;
     body_type: string | null;
     body_content: string | null;
+    auth?: RequestAuth | null;
   }, response?: ResponseData | null) => Promise<Tab>;
 }
 
@@ -272,6 +273,7 @@ export const useTabStore = create<TabStore>((set, get) => ({
       headers: request.headers ?? [],
       body_type: (request.body_type as TabState['body_type']) ?? 'none',
       body_content: request.body_content ?? '',
+      auth: request.auth ?? undefined,
       is_dirty: false,
     };
 
@@ -12,10 +12,23 @@ export interface KeyValue {
   description?: string;
 }
 
+// Multipart form-data field; for file fields `value` is the path of the file on disk
+export interface FormField {
+  key: string;
+  value: string;
+  enabled: boolean;
+  description?: string;
+  type: 'text' | 'file';
+  file_name?: string;
+  content_type?: string;
+}
+
 // Workspace
 export interface Workspace {
   id: string;
   name: string;
+  // Directory its collections and environments are stored in, one file per request
+  directory: string | null;
   created_at: number;
   updated_at: number;
 }
@@ -35,8 +48,14 @@ export interface SavedRequest {
   response_status_text: string | null;
   response_headers: KeyValue[] | null;
   response_body: string | null;
+  // 'base64' when the body is binary and response_body holds it base64-encoded
+  response_body_encoding: string | null;
   response_time_ms: number | null;
   response_size_bytes: number | null;
+  response_timings: Timings | null;
+  response_redirects: RedirectHop[] | null;
+  response_connection: ConnectionInfo | null;
+  auth: RequestAuth | null;
   created_at: number;
   updated_at: number;
 }
@@ -49,9 +68,110 @@ export interface TabState {
   headers: KeyValue[];
   body_type: BodyType;
   body_content: string;
+  settings?: RequestSettings;
+  auth?: RequestAuth;
   is_dirty: boolean;
 }
 
+// Credentials turned into headers or query parameters when the request is sent
+export type RequestAuth =
+  | { type: 'none' }
+  | { type: 'basic'; username: string; password: string }
+  | { type: 'bearer'; token: string }
+  | { type: 'api_key'; key: string; value: string; location: 'header' | 'query' }
+  | { type: 'digest'; username: string; password: string }
+  | ({ type: 'oauth2' } & OAuth2Config)
+  | ({ type: 'aws_sigv4' } & AwsCredentials)
+  | ({ type: 'signature' } & SignatureConfig);
+
+// Signs each request with AWS Signature Version 4; session_token is optional
+export interface AwsCredentials {
+  access_key_id: string;
+  secret_access_key: string;
+  session_token: string;
+  region: string;
+  service: string;
+}
+
+export type OAuth2GrantType = 'client_credentials' | 'password' | 'authorization_code';
+
+// Token endpoint settings; the redirect listener runs on http://127.0.0.1:<redirect_port>/callback
+export interface OAuth2Config {
+  grant_type: OAuth2GrantType;
+  token_url: string;
+  client_id: string;
+  client_secret: string;
+  // 'basic' sends the client credentials in an Authorization header, 'body' as form fields
+  client_auth: 'basic' | 'body';
+  scope: string;
+  username: string;
+  password: string;
+  auth_url: string;
+  // 0 picks a free port
+  redirect_port: number;
+}
+
+export type SignatureAlgorithm = 'hmac_sha256' | 'hmac_sha512' | 'rs256' | 'es256';
+
+// Signature computed at send time over the rendered template. Placeholders: {method}, {path},
+// {query}, {target}, {host}, {url}, {timestamp}, {timestamp_ms}, {nonce}, {body}, {body_sha256}.
+// key is the HMAC secret or a PEM private key; with 'jwt' the template is the claims object.
+export interface SignatureConfig {
+  algorithm: SignatureAlgorithm;
+  key: string;
+  template: string;
+  encoding: 'hex' | 'base64' | 'jwt';
+  header: string;
+  prefix: string;
+  timestamp_header: string;
+}
+
+// Cached OAuth 2.0 token; expires_at is epoch milliseconds
+export interface OAuthToken {
+  id: string;
+  workspace_id: string;
+  cache_key: string;
+  access_token: string;
+  token_type: string;
+  refresh_token: string | null;
+  scope: string | null;
+  expires_at: number | null;
+  created_at: number;
+  updated_at: number;
+}
+
+// Redirect handling; auth and cookie headers are dropped on cross-origin hops unless disabled
+export interface RedirectSettings {
+  follow: boolean;
+  max_redirects: number;
+  keep_method: boolean;
+  strip_auth_cross_origin: boolean;
+}
+
+// Per-request timeouts in milliseconds; null falls back to the workspace setting
+export interface TimeoutOverrides {
+  connect_ms: number | null;
+  read_ms: number | null;
+  total_ms: number | null;
+}
+
+// Per-request options that are not part of the HTTP message itself
+export interface RequestSettings {
+  redirect: RedirectSettings;
+  timeouts: TimeoutOverrides;
+  // Open a new connection instead of reusing a pooled one
+  fresh_connection: boolean;
+  // Neither send nor store cookies from the workspace cookie jar
+  disable_cookie_jar: boolean;
+}
+
+// A response that redirected the request elsewhere
+export interface RedirectHop {
+  url: string;
+  status: number;
+  headers: KeyValue[];
+}
+
 // Tab
 export interface Tab {
   id: string;
@@ -62,6 +182,42 @@ export interface Tab {
   is_active: boolean;
 }
 
+// Duration of each request phase in milliseconds; connection phases are null
+// when no new connection was opened, and tls_ms is null for plain HTTP
+export interface Timings {
+  dns_ms: number | null;
+  tcp_ms: number | null;
+  tls_ms: number | null;
+  ttfb_ms: number;
+  download_ms: number;
+  total_ms: number;
+}
+
+// Certificate presented by the server; validity times are epoch milliseconds
+export interface CertificateInfo {
+  subject: string;
+  issuer: string;
+  subject_alt_names: string[];
+  serial_number: string;
+  not_before: number;
+  not_after: number;
+  sha256_fingerprint: string;
+}
+
+export interface TlsInfo {
+  version: string | null;
+  cipher_suite: string | null;
+  // Leaf certificate first
+  certificates: CertificateInfo[];
+}
+
+// Connection a response arrived on; tls is null for plain HTTP
+export interface ConnectionInfo {
+  remote_address: string | null;
+  http_version: string;
+  tls: TlsInfo | null;
+}
+
 // Response data from HTTP request
 export interface ResponseData {
   status: number;
@@ -70,6 +226,73 @@ export interface ResponseData {
   body: string;
   time_ms: number;
   size_bytes: number;
+  timings?: Timings;
+  redirects?: RedirectHop[];
+  connection?: ConnectionInfo;
+  resolved_request?: HttpRequestInput;
+  unresolved_variables?: string[];
+}
+
+// Environment variable
+export interface EnvironmentVariable {
+  key: string;
+  value: string;
+  enabled: boolean;
+  secret: boolean;
+}
+
+// Environment (workspace_id is null for global environments)
+export interface Environment {
+  id: string;
+  workspace_id: string | null;
+  name: string;
+  variables: EnvironmentVariable[];
+  is_active: boolean;
+  created_at: number;
+  updated_at: number;
+}
+
+// Collection (named, durable requests, separate from history)
+export interface Collection {
+  id: string;
+  workspace_id: string;
+  name: string;
+  position: number;
+  // The .http file the collection was opened from and is saved back to
+  file_path: string | null;
+  created_at: number;
+  updated_at: number;
+}
+
+export type CollectionItemKind = 'folder' | 'request';
+
+// Collection item (folder or request; parent_id is null at the collection root)
+export interface CollectionItem {
+  id: string;
+  collection_id: string;
+  parent_id: string | null;
+  kind: CollectionItemKind;
+  name: string;
+  position: number;
+  method: HttpMethod | null;
+  url: string | null;
+  params: KeyValue[] | null;
+  headers: KeyValue[] | null;
+  body_type: BodyType | null;
+  body_content: string | null;
+  settings: RequestSettings | null;
+  auth: RequestAuth | null;
+  created_at: number;
+  updated_at: number;
+}
+
+// Create Collection Item Input
+export interface CreateCollectionItemInput {
+  collection_id: string;
+  parent_id: string | null;
+  kind: CollectionItemKind;
+  name: string;
+  state: TabState | null;
 }
 
 // App Settings
@@ -79,6 +302,78 @@ export interface AppSettings {
   active_workspace_id: string | null;
 }
 
+// Workspace request timeouts in milliseconds; 0 disables a timeout
+export interface TimeoutSettings {
+  connect_ms: number;
+  read_ms: number;
+  total_ms: number;
+}
+
+// Proxy settings, global or per workspace; use_system fills blanks from HTTP_PROXY etc.
+export interface ProxySettings {
+  enabled: boolean;
+  http_url: string;
+  https_url: string;
+  socks_url: string;
+  username: string;
+  password: string;
+  no_proxy: string[];
+  use_system: boolean;
+}
+
+// Client certificate for mutual TLS
+export type ClientCertificate =
+  | { format: 'pem'; cert_path: string; key_path: string }
+  | { format: 'pkcs12'; path: string; password: string };
+
+// TLS settings for a host: exact ("api.internal", "api.internal:8443") or wildcard ("*.internal")
+export interface TlsHostSettings {
+  id: string;
+  host: string;
+  ca_paths: string[];
+  client_cert: ClientCertificate | null;
+  insecure: boolean;
+  created_at: number;
+  updated_at: number;
+}
+
+export interface TlsHostSettingsInput {
+  host: string;
+  ca_paths: string[];
+  client_cert: ClientCertificate | null;
+  insecure: boolean;
+}
+
+// Cookie in a workspace's jar; expires is epoch milliseconds, null for session cookies
+export interface Cookie {
+  id: string;
+  workspace_id: string;
+  name: string;
+  value: string;
+  domain: string;
+  path: string;
+  expires: number | null;
+  secure: boolean;
+  http_only: boolean;
+  same_site: string | null;
+  // Sent to domain only, not to its subdomains
+  host_only: boolean;
+  created_at: number;
+  updated_at: number;
+}
+
+export interface CookieInput {
+  name: string;
+  value: string;
+  domain: string;
+  path: string;
+  expires: number | null;
+  secure: boolean;
+  http_only: boolean;
+  same_site: string | null;
+  host_only: boolean;
+}
+
 // HTTP Request Input (for sending)
 export interface HttpRequestInput {
   method: string;
@@ -86,6 +381,8 @@ export interface HttpRequestInput {
   headers: KeyValue[];
   body_type: string;
   body_content: string | null;
+  settings?: RequestSettings;
+  auth?: RequestAuth;
 }
 
 // Create Request Input (for saving to history)
@@ -103,4 +400,52 @@ export interface CreateRequestInput {
   response_body: string | null;
   response_time_ms: number | null;
   response_size_bytes: number | null;
+  response_timings?: Timings | null;
+  response_redirects?: RedirectHop[] | null;
+  response_connection?: ConnectionInfo | null;
+  auth?: RequestAuth | null;
+  response_body_encoding?: string | null;
+  // When the request was sent (ms since epoch); defaults to now
+  created_at?: number | null;
+}
+
+export type CodegenTarget = 'curl' | 'httpie' | 'wget' | 'reqwest' | 'python' | 'javascript' | 'go';
+
+// Request to generate code for: an open tab, a history entry or a saved collection request
+export type CodegenSource =
+  | { type: 'tab'; state: TabState }
+  | { type: 'request'; id: string }
+  | { type: 'collection_item'; id: string };
+
+// Something an import had to leave out (location is empty for the file itself)
+export interface ImportIssue {
+  location: string;
+  message: string;
+}
+
+// A pasted curl command as the state of a new tab, and the options it could not carry over
+export interface CurlImport {
+  state: TabState;
+  skipped: ImportIssue[];
+}
+
+// Where the requests of a HAR archive are imported to
+export type HarTarget = 'history' | 'collection';
+
+// Sent when a workspace stored in a directory was reloaded after its files changed,
+// or with `error` when they could not be read
+export interface WorkspaceDirectoryEvent {
+  workspace_id: string;
+  error?: string;
+}
+
+// What an import created, and what it skipped
+export interface ImportReport {
+  workspace_id: string;
+  collection_ids: string[];
+  environment_ids: string[];
+  folders: number;
+  requests: number;
+  history: number;
+  skipped: ImportIssue[];
 }